@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use xmas_elf::ElfFile;
+use xmas_elf::sections::SectionData;
+use xmas_elf::symbol_table::{Entry, Type};
 
 fn main() {
     // set by cargo, build scripts should use this directory for output files
@@ -7,6 +11,9 @@ fn main() {
     // https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#artifact-dependencies
     let kernel = PathBuf::from(std::env::var_os("CARGO_BIN_FILE_KERNEL_kernel").unwrap());
 
+    // embed the kernel symbol table, used to print backtraces
+    let kernel = embed_symbol_table(&kernel, &out_dir);
+
     // create an UEFI disk image (optional)
     let uefi_path = out_dir.join("uefi.img");
     bootloader::UefiBoot::new(&kernel)
@@ -23,3 +30,92 @@ fn main() {
     println!("cargo:rustc-env=UEFI_PATH={}", uefi_path.display());
     println!("cargo:rustc-env=BIOS_PATH={}", bios_path.display());
 }
+
+/// Writes the function symbols of the kernel into its `.ksyms` section, in the format described
+/// in `kernel::backtrace::symbols`, and returns the path of the patched kernel.
+///
+/// Only the content of a section that is already part of the image changes, so none of the
+/// addresses of the kernel move.
+fn embed_symbol_table(kernel: &Path, out_dir: &Path) -> PathBuf {
+    let mut image = std::fs::read(kernel).unwrap();
+    let elf = ElfFile::new(&image).unwrap();
+
+    let section = elf
+        .find_section_by_name(".ksyms")
+        .expect("the kernel has no .ksyms section");
+    let section_offset = section.offset() as usize;
+    let section_size = section.size() as usize;
+    let section_address = section.address();
+
+    let Ok(SectionData::SymbolTable64(entries)) = elf
+        .find_section_by_name(".symtab")
+        .expect("the kernel has no symbol table")
+        .get_data(&elf)
+    else {
+        panic!("unexpected kernel symbol table format");
+    };
+
+    let mut symbols: Vec<(u64, u64, String)> = entries
+        .iter()
+        .filter(|entry| matches!(entry.get_type(), Ok(Type::Func)) && entry.value() != 0)
+        .filter_map(|entry| {
+            let name = entry.get_name(&elf).ok()?;
+            let name = format!("{:#}", rustc_demangle::demangle(name));
+            Some((entry.value(), entry.size(), name))
+        })
+        .collect();
+    symbols.sort_by_key(|(address, _, _)| *address);
+    symbols.dedup_by_key(|(address, _, _)| *address);
+
+    let table = encode_symbol_table(&symbols, section_address, section_size);
+    image[section_offset..(section_offset + table.len())].copy_from_slice(&table);
+
+    let patched = out_dir.join("kernel");
+    std::fs::write(&patched, image).unwrap();
+    patched
+}
+
+fn encode_symbol_table(
+    symbols: &[(u64, u64, String)],
+    section_address: u64,
+    size: usize,
+) -> Vec<u8> {
+    const HEADER_SIZE: usize = 16;
+    const ENTRY_SIZE: usize = 18;
+
+    // keep as many symbols as fit in the section
+    let mut count = 0;
+    let mut names_size = 0;
+    for (_, _, name) in symbols {
+        let next_size = HEADER_SIZE + (count + 1) * ENTRY_SIZE + names_size + name.len();
+        if next_size > size {
+            println!(
+                "cargo:warning=kernel symbol table truncated to {count} of {} symbols, increase `SYMBOL_TABLE_SIZE`",
+                symbols.len()
+            );
+            break;
+        }
+        count += 1;
+        names_size += name.len();
+    }
+
+    let mut table = Vec::with_capacity(size);
+    table.extend_from_slice(b"KSYM");
+    table.extend_from_slice(&(count as u32).to_le_bytes());
+    table.extend_from_slice(&section_address.to_le_bytes());
+
+    let mut name_offset = HEADER_SIZE + count * ENTRY_SIZE;
+    for (address, size, name) in &symbols[..count] {
+        table.extend_from_slice(&address.to_le_bytes());
+        table.extend_from_slice(&(*size as u32).to_le_bytes());
+        table.extend_from_slice(&(name_offset as u32).to_le_bytes());
+        table.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        name_offset += name.len();
+    }
+
+    for (_, _, name) in &symbols[..count] {
+        table.extend_from_slice(name.as_bytes());
+    }
+
+    table
+}
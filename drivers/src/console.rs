@@ -0,0 +1,9 @@
+mod inner;
+pub mod macros;
+mod serial;
+
+pub use inner::{Console, Error, MAX_CONSOLES, register};
+pub use serial::SerialConsole;
+
+#[cfg(test)]
+mod tests;
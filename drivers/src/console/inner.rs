@@ -0,0 +1,82 @@
+use core::fmt;
+
+use utils::sync::IrqSpinlock;
+
+/// Maximum number of consoles that can be registered at once.
+pub const MAX_CONSOLES: usize = 8;
+
+static CONSOLES: IrqSpinlock<[Option<&'static dyn Console>; MAX_CONSOLES]> =
+    IrqSpinlock::new("CONSOLES", [None; MAX_CONSOLES]);
+
+/// A sink that shows the text printed by the kernel, like a screen or a serial port.
+///
+/// Every registered console receives all the text passed to [`print!`](crate::print), including
+/// the escape sequences of the terminal, which it handles as far as it can.
+pub trait Console: Sync {
+    /// Returns the name of the console, unique among the registered ones.
+    fn name(&self) -> &'static str;
+
+    /// Shows `s`.
+    fn write_str(&self, s: &str);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A console with the same name is already registered
+    AlreadyRegistered,
+    /// [`MAX_CONSOLES`] consoles are already registered
+    Full,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyRegistered => write!(f, "console already registered"),
+            Self::Full => write!(f, "too many consoles"),
+        }
+    }
+}
+
+/// Adds `console` to the sinks of [`print!`](crate::print).
+///
+/// The registry doesn't allocate, so consoles can be registered before the heap is ready.
+pub fn register(console: &'static dyn Console) -> Result<(), Error> {
+    let mut consoles = CONSOLES.lock();
+    if consoles
+        .iter()
+        .flatten()
+        .any(|registered| registered.name() == console.name())
+    {
+        return Err(Error::AlreadyRegistered);
+    }
+
+    let slot = consoles
+        .iter_mut()
+        .find(|slot| slot.is_none())
+        .ok_or(Error::Full)?;
+    *slot = Some(console);
+
+    Ok(())
+}
+
+/// Writes the formatted text to every registered console.
+///
+/// The consoles are copied out of the registry, so that slow ones don't keep the interrupts
+/// disabled while they write.
+pub(super) fn write_fmt(args: fmt::Arguments) {
+    let consoles = *CONSOLES.lock();
+    for console in consoles.into_iter().flatten() {
+        // Formatting can't fail, as consoles show everything they are given
+        let _ = fmt::Write::write_fmt(&mut Adapter(console), args);
+    }
+}
+
+/// Formats text directly into a console.
+struct Adapter(&'static dyn Console);
+
+impl fmt::Write for Adapter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s);
+        Ok(())
+    }
+}
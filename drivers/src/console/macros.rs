@@ -0,0 +1,19 @@
+use core::fmt;
+
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+    super::inner::write_fmt(args);
+}
+
+/// Prints to every registered console.
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => ($crate::console::macros::_print(format_args!($($arg)*)));
+}
+
+/// Prints to every registered console, appending a newline.
+#[macro_export]
+macro_rules! println {
+    () => ($crate::print!("\n"));
+    ($($arg:tt)*) => ($crate::print!("{}\n", format_args!($($arg)*)));
+}
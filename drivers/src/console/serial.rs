@@ -0,0 +1,22 @@
+use super::Console;
+use crate::serial::COM1;
+
+/// Sends the text to the host through the first serial port, once it is set up.
+///
+/// The escape sequences are sent as they are, for the terminal of the host to handle them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SerialConsole;
+
+impl Console for SerialConsole {
+    fn name(&self) -> &'static str {
+        "serial"
+    }
+
+    fn write_str(&self, s: &str) {
+        // The port is locked for each byte, as sending one takes about 260 µs at the default
+        // baud rate, during which the interrupts are disabled.
+        for byte in s.bytes() {
+            COM1.lock().write(&[byte]);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use alloc::string::String;
+
+use utils::sync::IrqSpinlock;
+
+use crate::println;
+
+use super::{Console, Error, register};
+
+/// Keeps the text it is given.
+struct Recorder {
+    name: &'static str,
+    text: IrqSpinlock<String>,
+}
+
+impl Console for Recorder {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn write_str(&self, s: &str) {
+        self.text.lock().push_str(s);
+    }
+}
+
+static FIRST: Recorder = Recorder {
+    name: "first",
+    text: IrqSpinlock::new("FIRST", String::new()),
+};
+static SECOND: Recorder = Recorder {
+    name: "second",
+    text: IrqSpinlock::new("SECOND", String::new()),
+};
+
+#[test_case]
+fn test_println_reaches_every_console() {
+    register(&FIRST).unwrap();
+    register(&SECOND).unwrap();
+
+    println!("answer: {}", 42);
+
+    assert!(FIRST.text.lock().ends_with("answer: 42\n"));
+    assert!(SECOND.text.lock().ends_with("answer: 42\n"));
+}
+
+#[test_case]
+fn test_register_same_name_twice() {
+    static DUPLICATE: Recorder = Recorder {
+        name: "duplicate",
+        text: IrqSpinlock::new("DUPLICATE", String::new()),
+    };
+
+    let _ = register(&DUPLICATE);
+    assert_eq!(register(&DUPLICATE), Err(Error::AlreadyRegistered));
+}
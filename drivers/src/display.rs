@@ -1,2 +1,4 @@
 pub mod frame_buffer;
+pub mod graphics;
+pub mod terminal;
 pub mod vga_buffer;
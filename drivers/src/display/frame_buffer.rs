@@ -1,7 +1,15 @@
+mod color;
+mod console;
 mod constants;
+mod font;
 mod init;
-pub mod macros;
 mod writer;
 
+pub use color::Rgb;
+pub use console::FrameBufferConsole;
+pub use font::{Font, FontWeight, RasterHeight};
 pub use init::init;
-pub use writer::WRITER;
+pub use writer::{DEFAULT_BACKGROUND, DEFAULT_FOREGROUND, FrameBufferWriter, WRITER};
+
+#[cfg(test)]
+mod tests;
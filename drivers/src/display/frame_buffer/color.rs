@@ -0,0 +1,78 @@
+use crate::display::terminal::Color;
+
+/// A 24-bit colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb::new(0, 0, 0);
+
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Returns the colour of a pixel of a glyph drawn in `self` on `background`, covering
+    /// `alpha` / 255 of the pixel.
+    pub fn blend(self, background: Rgb, alpha: u8) -> Rgb {
+        let mix = |foreground: u8, background: u8| {
+            let alpha = alpha as u32;
+            ((foreground as u32 * alpha + background as u32 * (255 - alpha) + 127) / 255) as u8
+        };
+
+        Rgb::new(
+            mix(self.red, background.red),
+            mix(self.green, background.green),
+            mix(self.blue, background.blue),
+        )
+    }
+
+    /// Returns the brightness of the colour, from 0 to 255.
+    pub fn luma(self) -> u8 {
+        ((self.red as u32 * 299 + self.green as u32 * 587 + self.blue as u32 * 114) / 1000) as u8
+    }
+}
+
+/// The 16 ANSI colours, with the values of the VGA text mode.
+const ANSI_COLORS: [Rgb; 16] = [
+    Rgb::new(0x00, 0x00, 0x00),
+    Rgb::new(0xaa, 0x00, 0x00),
+    Rgb::new(0x00, 0xaa, 0x00),
+    Rgb::new(0xaa, 0x55, 0x00),
+    Rgb::new(0x00, 0x00, 0xaa),
+    Rgb::new(0xaa, 0x00, 0xaa),
+    Rgb::new(0x00, 0xaa, 0xaa),
+    Rgb::new(0xaa, 0xaa, 0xaa),
+    Rgb::new(0x55, 0x55, 0x55),
+    Rgb::new(0xff, 0x55, 0x55),
+    Rgb::new(0x55, 0xff, 0x55),
+    Rgb::new(0xff, 0xff, 0x55),
+    Rgb::new(0x55, 0x55, 0xff),
+    Rgb::new(0xff, 0x55, 0xff),
+    Rgb::new(0x55, 0xff, 0xff),
+    Rgb::new(0xff, 0xff, 0xff),
+];
+
+/// Returns the value of `color`, or `default` for [`Color::Default`].
+///
+/// The indexed colours are the ones of the 256-colour palette of xterm: the ANSI colours, a
+/// 6×6×6 colour cube, and 24 shades of grey.
+pub fn resolve(color: Color, default: Rgb) -> Rgb {
+    match color {
+        Color::Default => default,
+        Color::Indexed(index @ 0..16) => ANSI_COLORS[index as usize],
+        Color::Indexed(index @ 16..232) => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            Rgb::new(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        Color::Indexed(index) => {
+            let grey = 8 + (index - 232) * 10;
+            Rgb::new(grey, grey, grey)
+        }
+        Color::Rgb(red, green, blue) => Rgb::new(red, green, blue),
+    }
+}
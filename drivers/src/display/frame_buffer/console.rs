@@ -0,0 +1,21 @@
+use core::fmt::Write;
+
+use super::WRITER;
+use crate::console::Console;
+
+/// Shows the text on the framebuffer, once it is initialised.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameBufferConsole;
+
+impl Console for FrameBufferConsole {
+    fn name(&self) -> &'static str {
+        "framebuffer"
+    }
+
+    fn write_str(&self, s: &str) {
+        if let Some(writer) = WRITER.r#try() {
+            // Writing to the framebuffer never fails
+            let _ = writer.lock().write_str(s);
+        }
+    }
+}
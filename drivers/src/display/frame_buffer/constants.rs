@@ -7,20 +7,6 @@ pub const LETTER_SPACING: usize = 0;
 /// Padding from the border. Prevent that font is too close to border.
 pub const BORDER_PADDING: usize = 1;
 
-/// Constants for the usage of the [`noto_sans_mono_bitmap`] crate.
-pub mod font {
-    use noto_sans_mono_bitmap::{get_raster_width, FontWeight, RasterHeight};
-
-    /// Height of each char raster. The font size is ~0.84% of this. Thus, this is the line height that
-    /// enables multiple characters to be side-by-side and appear optically in one line in a natural way.
-    pub const CHAR_RASTER_HEIGHT: RasterHeight = RasterHeight::Size16;
-
-    /// The width of each single symbol of the mono space font.
-    pub const CHAR_RASTER_WIDTH: usize = get_raster_width(FontWeight::Regular, CHAR_RASTER_HEIGHT);
-
-    /// Backup character if a desired symbol is not available by the font.
-    /// The '�' character requires the feature "unicode-specials".
-    pub const BACKUP_CHAR: char = '�';
-
-    pub const FONT_WEIGHT: FontWeight = FontWeight::Regular;
-}
+/// Backup character if a desired symbol is not available by the font.
+/// The '�' character requires the feature "unicode-specials".
+pub const BACKUP_CHAR: char = '�';
@@ -0,0 +1,84 @@
+use core::fmt;
+
+pub use noto_sans_mono_bitmap::{FontWeight, RasterHeight};
+use noto_sans_mono_bitmap::{RasterizedChar, get_raster, get_raster_width};
+
+use super::constants::{BACKUP_CHAR, LETTER_SPACING, LINE_SPACING};
+
+/// A size and a weight of the Noto Sans Mono font, in which the text is drawn.
+#[derive(Debug, Clone, Copy)]
+pub struct Font {
+    pub height: RasterHeight,
+    /// Weight of the regular text, the emphasized text is bold
+    pub weight: FontWeight,
+}
+
+impl Font {
+    pub const HEIGHTS: [RasterHeight; 4] = [
+        RasterHeight::Size16,
+        RasterHeight::Size20,
+        RasterHeight::Size24,
+        RasterHeight::Size32,
+    ];
+
+    pub const WEIGHTS: [FontWeight; 3] = [FontWeight::Light, FontWeight::Regular, FontWeight::Bold];
+
+    pub const fn new(height: RasterHeight, weight: FontWeight) -> Self {
+        Self { height, weight }
+    }
+
+    /// Returns the height of the font whose chars are `pixels` high.
+    pub fn height_from_pixels(pixels: usize) -> Option<RasterHeight> {
+        Self::HEIGHTS
+            .into_iter()
+            .find(|height| height.val() == pixels)
+    }
+
+    /// Returns the weight named `name`: `light`, `regular` or `bold`, ignoring its case.
+    pub fn weight_from_name(name: &str) -> Option<FontWeight> {
+        Self::WEIGHTS
+            .into_iter()
+            .find(|&weight| weight_name(weight).eq_ignore_ascii_case(name))
+    }
+
+    /// Width of the cells of the text grid, a char of any weight and the space after it.
+    pub fn cell_width(&self) -> usize {
+        let width = get_raster_width(self.weight, self.height);
+        width.max(get_raster_width(FontWeight::Bold, self.height)) + LETTER_SPACING
+    }
+
+    /// Height of the cells of the text grid, a line and the space below it.
+    pub fn cell_height(&self) -> usize {
+        self.height.val() + LINE_SPACING
+    }
+
+    /// Returns the raster of `c`, in bold if `bold` is set, or the raster of [`BACKUP_CHAR`] if
+    /// the font does not have it.
+    pub fn raster(&self, c: char, bold: bool) -> RasterizedChar {
+        let weight = if bold { FontWeight::Bold } else { self.weight };
+
+        get_raster(c, weight, self.height)
+            .or_else(|| get_raster(BACKUP_CHAR, weight, self.height))
+            .expect("Should get raster of backup char.")
+    }
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::new(RasterHeight::Size16, FontWeight::Regular)
+    }
+}
+
+impl fmt::Display for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.height.val(), weight_name(self.weight))
+    }
+}
+
+fn weight_name(weight: FontWeight) -> &'static str {
+    match weight {
+        FontWeight::Light => "light",
+        FontWeight::Regular => "regular",
+        FontWeight::Bold => "bold",
+    }
+}
@@ -1,11 +1,20 @@
 use bootloader_api::info::FrameBuffer;
-use spin::Mutex;
+use utils::sync::IrqSpinlock;
 
-use super::{writer::FrameBufferWriter, WRITER};
+use super::{WRITER, console::FrameBufferConsole, writer::FrameBufferWriter};
+use crate::console;
 
-pub fn init(frame_buffer: FrameBuffer) {
+/// Draws the text printed by the kernel on `frame_buffer`, through `back_buffer` of
+/// [`DoubleBuffer::back_size`](crate::display::graphics::DoubleBuffer::back_size) bytes.
+pub fn init(frame_buffer: FrameBuffer, back_buffer: &'static mut [u8]) {
     let info = frame_buffer.info();
     let frame_buffer = frame_buffer.into_buffer();
 
-    WRITER.call_once(|| Mutex::new(FrameBufferWriter::new(frame_buffer, info)));
+    WRITER.call_once(|| {
+        IrqSpinlock::new(
+            "WRITER",
+            FrameBufferWriter::new(frame_buffer, back_buffer, info),
+        )
+    });
+    console::register(&FrameBufferConsole).expect("framebuffer console registration failed");
 }
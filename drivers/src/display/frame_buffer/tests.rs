@@ -0,0 +1,52 @@
+use super::color::{self, Rgb};
+use super::{Font, FontWeight};
+use crate::display::terminal::Color;
+
+#[test_case]
+fn test_blend() {
+    let white = Rgb::new(0xff, 0xff, 0xff);
+    let blue = Rgb::new(0, 0, 0xff);
+
+    assert_eq!(white.blend(blue, 0), blue);
+    assert_eq!(white.blend(blue, 0xff), white);
+    assert_eq!(white.blend(blue, 0x80), Rgb::new(0x80, 0x80, 0xff));
+}
+
+#[test_case]
+fn test_resolve_colors() {
+    let default = Rgb::new(1, 2, 3);
+
+    assert_eq!(color::resolve(Color::Default, default), default);
+    assert_eq!(
+        color::resolve(Color::Indexed(9), default),
+        Rgb::new(0xff, 0x55, 0x55)
+    );
+    // colour cube
+    assert_eq!(
+        color::resolve(Color::Indexed(16 + 36 * 5 + 6 * 2), default),
+        Rgb::new(0xff, 0x87, 0x00)
+    );
+    // shades of grey
+    assert_eq!(
+        color::resolve(Color::Indexed(255), default),
+        Rgb::new(0xee, 0xee, 0xee)
+    );
+    assert_eq!(
+        color::resolve(Color::Rgb(4, 5, 6), default),
+        Rgb::new(4, 5, 6)
+    );
+}
+
+#[test_case]
+fn test_fonts() {
+    assert!(Font::height_from_pixels(24).is_some());
+    assert!(Font::height_from_pixels(18).is_none());
+    assert!(matches!(
+        Font::weight_from_name("Bold"),
+        Some(FontWeight::Bold)
+    ));
+    assert!(Font::weight_from_name("heavy").is_none());
+
+    let font = Font::default();
+    assert!(font.cell_width() < font.cell_height());
+}
@@ -1,134 +1,221 @@
-use core::{fmt, ptr};
+use core::fmt;
 
-use bootloader_api::info::{FrameBufferInfo, PixelFormat};
-use noto_sans_mono_bitmap::{get_raster, RasterizedChar};
-use spin::{Mutex, Once};
+use bootloader_api::info::FrameBufferInfo;
+use spin::Once;
+use utils::sync::IrqSpinlock;
 
+use super::color::{self, Rgb};
 use super::constants::*;
+use super::font::Font;
+use crate::display::graphics::{DoubleBuffer, Point, Rect};
+use crate::display::terminal::{Attributes, Terminal};
 
-pub static WRITER: Once<Mutex<FrameBufferWriter>> = Once::new();
+pub static WRITER: Once<IrqSpinlock<FrameBufferWriter>> = Once::new();
 
-/// Returns the raster of the given char or the raster of [`font_constants::BACKUP_CHAR`].
-fn get_char_raster(c: char) -> RasterizedChar {
-    fn get(c: char) -> Option<RasterizedChar> {
-        get_raster(c, font::FONT_WEIGHT, font::CHAR_RASTER_HEIGHT)
-    }
+/// Colour of the text without colour.
+pub const DEFAULT_FOREGROUND: Rgb = Rgb::new(0xff, 0xff, 0x7f);
 
-    get(c).unwrap_or_else(|| get(font::BACKUP_CHAR).expect("Should get raster of backup char."))
-}
+/// Colour of the background without colour.
+pub const DEFAULT_BACKGROUND: Rgb = Rgb::BLACK;
 
 /// Allows logging text to a pixel-based framebuffer.
+///
+/// The text goes through a [`Terminal`], whose cells are drawn again when they change.
 #[derive(Debug)]
 pub struct FrameBufferWriter {
-    frame_buffer: &'static mut [u8],
-    info: FrameBufferInfo,
-    x_pos: usize,
-    y_pos: usize,
+    /// Copy of the framebuffer in RAM, where the cells are drawn
+    buffer: DoubleBuffer<'static>,
+    terminal: Terminal,
+    font: Font,
+    foreground: Rgb,
+    background: Rgb,
+    /// Whether the cursor is drawn under the cell of the next char
+    cursor_visible: bool,
+    /// Cell under which the cursor is drawn
+    drawn_cursor: Option<(usize, usize)>,
 }
 
 impl FrameBufferWriter {
-    /// Creates a new logger that uses the given framebuffer.
-    pub fn new(frame_buffer: &'static mut [u8], info: FrameBufferInfo) -> Self {
+    /// Creates a new logger that uses the given framebuffer, drawing in `back_buffer` of
+    /// [`DoubleBuffer::back_size`] bytes.
+    pub fn new(
+        frame_buffer: &'static mut [u8],
+        back_buffer: &'static mut [u8],
+        info: FrameBufferInfo,
+    ) -> Self {
+        let font = Font::default();
+        let (columns, rows) = grid_size(&info, &font);
+
         let mut writer = Self {
-            frame_buffer,
-            info,
-            x_pos: 0,
-            y_pos: 0,
+            buffer: DoubleBuffer::new(frame_buffer, back_buffer, info),
+            terminal: Terminal::new(columns, rows),
+            font,
+            foreground: DEFAULT_FOREGROUND,
+            background: DEFAULT_BACKGROUND,
+            cursor_visible: false,
+            drawn_cursor: None,
         };
 
-        writer.clear();
+        writer.redraw();
         writer
     }
 
-    fn carriage_return(&mut self) {
-        self.x_pos = BORDER_PADDING;
+    /// Erases all text on the screen, and moves the cursor to the top left corner.
+    pub fn clear(&mut self) {
+        self.terminal.clear();
+        self.render();
     }
 
-    fn newline(&mut self) {
-        self.y_pos += font::CHAR_RASTER_HEIGHT.val() + LINE_SPACING;
-        self.carriage_return()
+    /// Shows or hides the cursor, a bar under the cell of the next char.
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+        self.render();
     }
 
-    /// Erases all text on the screen. Resets `self.x_pos` and `self.y_pos`.
-    pub fn clear(&mut self) {
-        self.x_pos = BORDER_PADDING;
-        self.y_pos = BORDER_PADDING;
-        self.frame_buffer.fill(0);
+    pub fn font(&self) -> Font {
+        self.font
     }
 
-    fn width(&self) -> usize {
-        self.info.width
+    /// Draws the text in `font`, which changes the number of lines and of chars per line.
+    pub fn set_font(&mut self, font: Font) {
+        self.font = font;
+        let (columns, rows) = grid_size(&self.buffer.info(), &font);
+        self.terminal.resize(columns, rows);
+        self.redraw();
     }
 
-    fn height(&self) -> usize {
-        self.info.height
+    /// Sets the colours of the text and of the background that have no colour.
+    pub fn set_default_colors(&mut self, foreground: Rgb, background: Rgb) {
+        self.foreground = foreground;
+        self.background = background;
+        self.redraw();
     }
 
-    /// Writes a single char to the framebuffer. Takes care of special control characters, such as
-    /// newlines and carriage returns.
-    fn write_char(&mut self, c: char) {
-        match c {
-            '\n' => self.newline(),
-            '\r' => self.carriage_return(),
-            c => {
-                let new_xpos = self.x_pos + font::CHAR_RASTER_WIDTH;
-                if new_xpos >= self.width() {
-                    self.newline();
-                }
+    /// Number of chars that fit on a line.
+    pub fn columns(&self) -> usize {
+        self.terminal.columns()
+    }
+
+    /// Number of lines that fit on the screen.
+    pub fn rows(&self) -> usize {
+        self.terminal.rows()
+    }
+
+    /// Shows `lines` more lines of the scrollback, until text is written again.
+    pub fn scroll_back(&mut self, lines: usize) {
+        self.terminal.scroll_back(lines);
+        self.render();
+    }
+
+    /// Shows `lines` fewer lines of the scrollback.
+    pub fn scroll_forward(&mut self, lines: usize) {
+        self.terminal.scroll_forward(lines);
+        self.render();
+    }
 
-                let new_ypos = self.y_pos + font::CHAR_RASTER_HEIGHT.val() + BORDER_PADDING;
-                if new_ypos >= self.height() {
-                    self.clear();
+    /// Fills the screen with the background colour, and draws every cell.
+    fn redraw(&mut self) {
+        self.buffer.canvas().clear(self.background);
+        self.buffer.mark_all_dirty();
+        self.terminal.damage_all();
+        self.drawn_cursor = None;
+        self.render();
+    }
+
+    /// Moves the lines that scrolled, draws the cells that changed, moves the cursor, and shows
+    /// the result.
+    fn render(&mut self) {
+        let cursor = self.terminal.cursor().filter(|_| self.cursor_visible);
+        let mut erased_cursor = core::mem::replace(&mut self.drawn_cursor, cursor);
+
+        if let Some(scroll) = self.terminal.take_scroll() {
+            let height = self.font.cell_height();
+            let lines = BORDER_PADDING + scroll.rows.start * height
+                ..BORDER_PADDING + scroll.rows.end * height;
+            self.buffer.scroll(lines, scroll.lines * height as isize);
+
+            // The drawn cursor moved with its line, or left the screen with it
+            erased_cursor = erased_cursor.and_then(|(row, column)| {
+                if !scroll.rows.contains(&row) {
+                    return Some((row, column));
                 }
+                row.checked_add_signed(-scroll.lines)
+                    .filter(|row| scroll.rows.contains(row))
+                    .map(|row| (row, column))
+            });
+        }
 
-                self.write_rendered_char(get_char_raster(c));
+        for row in 0..self.terminal.rows() {
+            if let Some(columns) = self.terminal.take_damage(row) {
+                for column in columns {
+                    self.draw_cell(row, column);
+                }
             }
         }
-    }
 
-    /// Prints a rendered char into the framebuffer.
-    /// Updates `self.x_pos`.
-    fn write_rendered_char(&mut self, rendered_char: RasterizedChar) {
-        for (y, row) in rendered_char.raster().iter().enumerate() {
-            for (x, byte) in row.iter().enumerate() {
-                self.write_pixel(self.x_pos + x, self.y_pos + y, *byte);
+        if erased_cursor != cursor {
+            for (row, column) in erased_cursor.into_iter().chain(cursor) {
+                self.draw_cell(row, column);
             }
         }
 
-        self.x_pos += rendered_char.width() + LETTER_SPACING;
+        self.buffer.flush();
     }
 
-    fn write_pixel(&mut self, x: usize, y: usize, intensity: u8) {
-        let pixel_offset = y * self.info.stride + x;
-
-        let color = match self.info.pixel_format {
-            PixelFormat::Rgb => [intensity, intensity, intensity / 2, 0],
-            PixelFormat::Bgr => [intensity / 2, intensity, intensity, 0],
-            PixelFormat::U8 => [if intensity > 200 { 0xf } else { 0 }, 0, 0, 0],
-            other => {
-                // set a supported (but invalid) pixel format before panicking to avoid a double
-                // panic; it might not be readable though
-                self.info.pixel_format = PixelFormat::Rgb;
-                panic!("pixel format {:?} not supported in logger", other)
+    /// Draws the cell at `row` and `column` with the spacing around its char, and the cursor
+    /// in the spacing below it if it is there.
+    fn draw_cell(&mut self, row: usize, column: usize) {
+        let cell = self.terminal.cell(row, column);
+        let attributes = cell.style.attributes;
+        let raster = self
+            .font
+            .raster(cell.character, attributes.contains(Attributes::BOLD));
+
+        let mut foreground = color::resolve(cell.style.foreground, self.foreground);
+        let mut background = color::resolve(cell.style.background, self.background);
+        if attributes.contains(Attributes::REVERSE) {
+            core::mem::swap(&mut foreground, &mut background);
+        }
+        let underline = attributes
+            .contains(Attributes::UNDERLINE)
+            .then(|| raster.height() - 1);
+        let cursor = self.drawn_cursor == Some((row, column));
+
+        let (width, height) = (self.font.cell_width(), self.font.cell_height());
+        let left = BORDER_PADDING + column * width;
+        let top = BORDER_PADDING + row * height;
+        let mut canvas = self.buffer.canvas();
+        for y in 0..height {
+            for x in 0..width {
+                let glyph = raster.raster().get(y).and_then(|line| line.get(x));
+                let color = match glyph {
+                    _ if underline == Some(y) => foreground,
+                    Some(&alpha) => foreground.blend(background, alpha),
+                    None if cursor && y >= raster.height() => foreground,
+                    None => background,
+                };
+                canvas.set_pixel(Point::new((left + x) as isize, (top + y) as isize), color);
             }
-        };
-
-        let bytes_per_pixel = self.info.bytes_per_pixel;
-        let byte_offset = pixel_offset * bytes_per_pixel;
-        self.frame_buffer[byte_offset..(byte_offset + bytes_per_pixel)]
-            .copy_from_slice(&color[..bytes_per_pixel]);
-        let _ = unsafe { ptr::read_volatile(&self.frame_buffer[byte_offset]) };
+        }
+        self.buffer
+            .mark_dirty(Rect::new(left as isize, top as isize, width, height));
     }
 }
 
+/// Returns the number of columns and of rows of the text grid drawn with `font`.
+fn grid_size(info: &FrameBufferInfo, font: &Font) -> (usize, usize) {
+    let columns = (info.width - 2 * BORDER_PADDING) / font.cell_width();
+    let rows = (info.height - 2 * BORDER_PADDING) / font.cell_height();
+    (columns, rows)
+}
+
 unsafe impl Send for FrameBufferWriter {}
 unsafe impl Sync for FrameBufferWriter {}
 
 impl fmt::Write for FrameBufferWriter {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        for c in s.chars() {
-            self.write_char(c);
-        }
+        self.terminal.write(s);
+        self.render();
 
         Ok(())
     }
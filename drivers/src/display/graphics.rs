@@ -0,0 +1,13 @@
+mod canvas;
+mod double_buffer;
+mod geometry;
+mod image;
+mod pixel;
+
+pub use canvas::Canvas;
+pub use double_buffer::DoubleBuffer;
+pub use geometry::{Point, Rect};
+pub use image::{Error, Image, Rgba};
+
+#[cfg(test)]
+mod tests;
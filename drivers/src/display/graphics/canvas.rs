@@ -0,0 +1,214 @@
+use core::fmt;
+
+use bootloader_api::info::FrameBufferInfo;
+
+use super::geometry::{Point, Rect};
+use super::image::Image;
+use super::pixel::{self, MAX_COLOR_BYTES};
+use crate::display::frame_buffer::Rgb;
+
+/// Draws shapes and images on a buffer of pixels laid out as described by a [`FrameBufferInfo`].
+///
+/// Everything drawn out of the canvas is clipped.
+pub struct Canvas<'a> {
+    buffer: &'a mut [u8],
+    info: FrameBufferInfo,
+}
+
+impl<'a> Canvas<'a> {
+    /// Creates a canvas drawing on `buffer`, which must hold `info.height` lines of
+    /// `info.stride` pixels.
+    pub fn new(buffer: &'a mut [u8], info: FrameBufferInfo) -> Self {
+        assert!(
+            buffer.len() >= info.height * info.stride * info.bytes_per_pixel,
+            "buffer too small for the canvas"
+        );
+        Self { buffer, info }
+    }
+
+    pub fn info(&self) -> FrameBufferInfo {
+        self.info
+    }
+
+    pub fn width(&self) -> usize {
+        self.info.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.info.height
+    }
+
+    /// Returns the rectangle covering the whole canvas.
+    pub fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.info.width, self.info.height)
+    }
+
+    /// Returns the colour of the pixel at `point`, or `None` if it is out of the canvas.
+    pub fn pixel(&self, point: Point) -> Option<Rgb> {
+        let offset = self.offset(point)?;
+        let mut bytes = [0; MAX_COLOR_BYTES];
+        let length = self.info.bytes_per_pixel.min(MAX_COLOR_BYTES);
+        bytes[..length].copy_from_slice(&self.buffer[offset..offset + length]);
+
+        Some(pixel::decode(self.info.pixel_format, bytes))
+    }
+
+    pub fn set_pixel(&mut self, point: Point, color: Rgb) {
+        if let Some(offset) = self.offset(point) {
+            let bytes = pixel::encode(self.info.pixel_format, color);
+            self.write_pixels(offset, 1, &bytes);
+        }
+    }
+
+    /// Fills the whole canvas with `color`.
+    pub fn clear(&mut self, color: Rgb) {
+        self.fill_rect(self.bounds(), color);
+    }
+
+    /// Draws a line from `from` to `to`, both included.
+    pub fn draw_line(&mut self, from: Point, to: Point, color: Rgb) {
+        // Bresenham's algorithm, for every octant
+        let (dx, dy) = (
+            from.x.abs_diff(to.x) as isize,
+            -(from.y.abs_diff(to.y) as isize),
+        );
+        let (step_x, step_y) = (
+            if from.x < to.x { 1 } else { -1 },
+            if from.y < to.y { 1 } else { -1 },
+        );
+        let mut error = dx + dy;
+        let mut point = from;
+
+        loop {
+            self.set_pixel(point, color);
+            if point == to {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                point.x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                point.y += step_y;
+            }
+        }
+    }
+
+    /// Draws the outline of `rect`, one pixel wide.
+    pub fn draw_rect(&mut self, rect: Rect, color: Rgb) {
+        if rect.is_empty() {
+            return;
+        }
+        let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+
+        self.fill_rect(Rect::new(rect.x, rect.y, rect.width, 1), color);
+        self.fill_rect(Rect::new(rect.x, bottom, rect.width, 1), color);
+        self.fill_rect(Rect::new(rect.x, rect.y, 1, rect.height), color);
+        self.fill_rect(Rect::new(right, rect.y, 1, rect.height), color);
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: Rgb) {
+        let Some(rect) = rect.intersection(&self.bounds()) else {
+            return;
+        };
+        let bytes = pixel::encode(self.info.pixel_format, color);
+
+        for y in rect.y..rect.bottom() {
+            let offset = self.offset(Point::new(rect.x, y)).unwrap();
+            self.write_pixels(offset, rect.width, &bytes);
+        }
+    }
+
+    /// Draws the outline of the circle of `radius` around `center`, one pixel wide.
+    pub fn draw_circle(&mut self, center: Point, radius: usize, color: Rgb) {
+        self.for_each_octant_point(radius, |canvas, x, y| {
+            for (x, y) in [(x, y), (y, x), (-y, x), (-x, y)] {
+                canvas.set_pixel(Point::new(center.x + x, center.y + y), color);
+                canvas.set_pixel(Point::new(center.x - x, center.y - y), color);
+            }
+        });
+    }
+
+    pub fn fill_circle(&mut self, center: Point, radius: usize, color: Rgb) {
+        self.for_each_octant_point(radius, |canvas, x, y| {
+            for (half_width, dy) in [(x, y), (y, x)] {
+                let width = 2 * half_width as usize + 1;
+                for y in [center.y - dy, center.y + dy] {
+                    canvas.fill_rect(Rect::new(center.x - half_width, y, width, 1), color);
+                }
+            }
+        });
+    }
+
+    /// Draws `image` with its top left corner at `position`, blending its translucent pixels
+    /// with the ones below.
+    pub fn blit(&mut self, image: &Image, position: Point) {
+        let area = Rect::new(position.x, position.y, image.width(), image.height());
+        let Some(visible) = area.intersection(&self.bounds()) else {
+            return;
+        };
+
+        for y in visible.y..visible.bottom() {
+            for x in visible.x..visible.right() {
+                let source = image
+                    .pixel((x - position.x) as usize, (y - position.y) as usize)
+                    .unwrap();
+                let point = Point::new(x, y);
+                let color = match source.alpha {
+                    0 => continue,
+                    0xff => source.rgb(),
+                    alpha => source.rgb().blend(self.pixel(point).unwrap(), alpha),
+                };
+                self.set_pixel(point, color);
+            }
+        }
+    }
+
+    /// Calls `f` with the points of the first octant of the circle of `radius` around the
+    /// origin, from the top, as `x` and `y` with `0 <= x <= y`.
+    fn for_each_octant_point(&mut self, radius: usize, mut f: impl FnMut(&mut Self, isize, isize)) {
+        // Midpoint circle algorithm
+        let (mut x, mut y) = (0, radius as isize);
+        let mut error = 1 - y;
+
+        while x <= y {
+            f(self, x, y);
+            x += 1;
+            if error < 0 {
+                error += 2 * x + 1;
+            } else {
+                y -= 1;
+                error += 2 * (x - y) + 1;
+            }
+        }
+    }
+
+    /// Returns the offset in bytes of the pixel at `point`, or `None` if it is out of the canvas.
+    fn offset(&self, point: Point) -> Option<usize> {
+        if !self.bounds().contains(point) {
+            return None;
+        }
+        let (x, y) = (point.x as usize, point.y as usize);
+
+        Some((y * self.info.stride + x) * self.info.bytes_per_pixel)
+    }
+
+    /// Writes `count` pixels of the colour made of `bytes` from `offset`.
+    fn write_pixels(&mut self, offset: usize, count: usize, bytes: &[u8; MAX_COLOR_BYTES]) {
+        let bytes_per_pixel = self.info.bytes_per_pixel;
+        let length = bytes_per_pixel.min(MAX_COLOR_BYTES);
+        let pixels = &mut self.buffer[offset..offset + count * bytes_per_pixel];
+
+        for pixel in pixels.chunks_exact_mut(bytes_per_pixel) {
+            pixel[..length].copy_from_slice(&bytes[..length]);
+        }
+    }
+}
+
+impl fmt::Debug for Canvas<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Canvas").field("info", &self.info).finish()
+    }
+}
@@ -0,0 +1,129 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use bootloader_api::info::FrameBufferInfo;
+
+use super::canvas::Canvas;
+use super::geometry::Rect;
+
+/// Draws in a copy of a framebuffer in RAM, then copies the pixels that changed to the
+/// framebuffer at once.
+///
+/// Video memory is slow to write pixel by pixel and slower to read, so it is only written by
+/// [`Self::flush`], line by line.
+///
+/// The back buffer is given by the caller, as it is usually too large for the heap.
+pub struct DoubleBuffer<'a> {
+    front: &'a mut [u8],
+    back: &'a mut [u8],
+    info: FrameBufferInfo,
+    /// Columns of each line of pixels that changed since the last flush
+    dirty: Vec<Option<Range<usize>>>,
+}
+
+impl<'a> DoubleBuffer<'a> {
+    /// Returns the number of bytes of the back buffer of a framebuffer described by `info`.
+    pub fn back_size(info: &FrameBufferInfo) -> usize {
+        info.height * info.stride * info.bytes_per_pixel
+    }
+
+    /// Draws in `back` for `front`, starting with what `front` shows.
+    ///
+    /// Panics if `back` is shorter than [`Self::back_size`].
+    pub fn new(front: &'a mut [u8], back: &'a mut [u8], info: FrameBufferInfo) -> Self {
+        let size = Self::back_size(&info);
+        let back = &mut back[..size];
+        back.copy_from_slice(&front[..size]);
+
+        Self {
+            front,
+            back,
+            info,
+            dirty: vec![None; info.height],
+        }
+    }
+
+    pub fn info(&self) -> FrameBufferInfo {
+        self.info
+    }
+
+    /// Returns a canvas drawing in the back buffer, whose changes must be marked with
+    /// [`Self::mark_dirty`] to be flushed.
+    pub fn canvas(&mut self) -> Canvas<'_> {
+        Canvas::new(self.back, self.info)
+    }
+
+    /// Marks the pixels of `rect` as changed.
+    pub fn mark_dirty(&mut self, rect: Rect) {
+        let bounds = Rect::new(0, 0, self.info.width, self.info.height);
+        let Some(rect) = rect.intersection(&bounds) else {
+            return;
+        };
+        let columns = rect.x as usize..rect.right() as usize;
+
+        for line in &mut self.dirty[rect.y as usize..rect.bottom() as usize] {
+            *line = Some(match line.take() {
+                Some(dirty) => dirty.start.min(columns.start)..dirty.end.max(columns.end),
+                None => columns.clone(),
+            });
+        }
+    }
+
+    /// Marks every pixel as changed.
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.fill(Some(0..self.info.width));
+    }
+
+    /// Moves the lines of pixels in `lines` up by `distance` lines if it is positive, or down if
+    /// it is negative.
+    ///
+    /// The lines left behind keep their pixels, for the caller to draw over them.
+    pub fn scroll(&mut self, lines: Range<usize>, distance: isize) {
+        let lines = lines.start..lines.end.min(self.info.height);
+        let moved = distance.unsigned_abs();
+        if moved == 0 || moved >= lines.len() {
+            return;
+        }
+
+        let line_size = self.info.stride * self.info.bytes_per_pixel;
+        let (source, destination) = if distance > 0 {
+            (lines.start + moved..lines.end, lines.start)
+        } else {
+            (lines.start..lines.end - moved, lines.start + moved)
+        };
+        self.back.copy_within(
+            source.start * line_size..source.end * line_size,
+            destination * line_size,
+        );
+        self.mark_dirty(Rect::new(
+            0,
+            lines.start as isize,
+            self.info.width,
+            lines.len(),
+        ));
+    }
+
+    /// Copies the pixels that changed to the framebuffer.
+    pub fn flush(&mut self) {
+        let bytes_per_pixel = self.info.bytes_per_pixel;
+        let line_size = self.info.stride * bytes_per_pixel;
+
+        for (y, dirty) in self.dirty.iter_mut().enumerate() {
+            if let Some(columns) = dirty.take() {
+                let start = y * line_size + columns.start * bytes_per_pixel;
+                let end = y * line_size + columns.end * bytes_per_pixel;
+                self.front[start..end].copy_from_slice(&self.back[start..end]);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DoubleBuffer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DoubleBuffer")
+            .field("info", &self.info)
+            .finish()
+    }
+}
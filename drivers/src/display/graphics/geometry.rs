@@ -0,0 +1,66 @@
+/// A position in pixels, from the top left corner.
+///
+/// The coordinates can be negative or out of the canvas, what is drawn there is clipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A rectangle of pixels, from its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: isize,
+    pub y: isize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub const fn new(x: isize, y: isize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the position of the pixel after the right edge.
+    pub fn right(&self) -> isize {
+        self.x.saturating_add_unsigned(self.width)
+    }
+
+    /// Returns the position of the pixel below the bottom edge.
+    pub fn bottom(&self) -> isize {
+        self.y.saturating_add_unsigned(self.height)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.x..self.right()).contains(&point.x) && (self.y..self.bottom()).contains(&point.y)
+    }
+
+    /// Returns the pixels in both `self` and `other`, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+        let (right, bottom) = (
+            self.right().min(other.right()),
+            self.bottom().min(other.bottom()),
+        );
+        if right <= x || bottom <= y {
+            return None;
+        }
+
+        Some(Rect::new(x, y, x.abs_diff(right), y.abs_diff(bottom)))
+    }
+}
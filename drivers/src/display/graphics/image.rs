@@ -0,0 +1,325 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::display::frame_buffer::Rgb;
+
+/// A 24-bit colour with its opacity, from 0 for transparent to 255 for opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rgba {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Rgba {
+    pub const fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    pub const fn opaque(red: u8, green: u8, blue: u8) -> Self {
+        Self::new(red, green, blue, 0xff)
+    }
+
+    pub const fn rgb(self) -> Rgb {
+        Rgb::new(self.red, self.green, self.blue)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The data ends before the image
+    Truncated,
+    /// The data isn't a BMP nor a TGA image
+    InvalidHeader,
+    /// The image uses a compression or a pixel depth that isn't supported
+    Unsupported,
+    /// There isn't enough memory left to hold the pixels
+    TooLarge,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "truncated image"),
+            Self::InvalidHeader => write!(f, "invalid image header"),
+            Self::Unsupported => write!(f, "unsupported image format"),
+            Self::TooLarge => write!(f, "image too large"),
+        }
+    }
+}
+
+/// A decoded image, whose pixels are stored from the top left corner, line by line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgba>,
+}
+
+impl Image {
+    /// Creates an image of `width` by `height` pixels from its lines, from the top.
+    pub fn new(width: usize, height: usize, pixels: Vec<Rgba>) -> Self {
+        assert_eq!(pixels.len(), width * height, "wrong number of pixels");
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Decodes a BMP or a TGA image, told apart by the signature of BMP images.
+    pub fn decode(data: &[u8]) -> Result<Self, Error> {
+        if data.starts_with(b"BM") {
+            Self::from_bmp(data)
+        } else {
+            Self::from_tga(data)
+        }
+    }
+
+    /// Decodes an uncompressed BMP image of 8 bits per pixel with a palette, or of 24 or 32
+    /// bits per pixel.
+    pub fn from_bmp(data: &[u8]) -> Result<Self, Error> {
+        const BI_RGB: u32 = 0;
+        const BI_BITFIELDS: u32 = 3;
+        const FILE_HEADER_SIZE: usize = 14;
+
+        if !data.starts_with(b"BM") {
+            return Err(Error::InvalidHeader);
+        }
+        let pixels_offset = read_u32(data, 10)? as usize;
+        let header_size = read_u32(data, 14)? as usize;
+        if header_size < 40 {
+            return Err(Error::Unsupported);
+        }
+        let width = read_u32(data, 18)? as i32;
+        let height = read_u32(data, 22)? as i32;
+        let depth = read_u16(data, 28)?;
+        let compression = read_u32(data, 30)?;
+        if width <= 0 || height == 0 {
+            return Err(Error::InvalidHeader);
+        }
+        // The lines are stored from the bottom, unless the height is negative
+        let top_down = height < 0;
+        let (width, height) = (width as usize, height.unsigned_abs() as usize);
+
+        let masks = match (compression, depth) {
+            (BI_RGB, 32) => [0xff << 16, 0xff << 8, 0xff, 0],
+            (BI_BITFIELDS, 32) => {
+                // The masks follow the header if it is too small to hold them
+                let offset = FILE_HEADER_SIZE + 40;
+                let alpha = if header_size >= 56 {
+                    read_u32(data, offset + 12)?
+                } else {
+                    0
+                };
+                [
+                    read_u32(data, offset)?,
+                    read_u32(data, offset + 4)?,
+                    read_u32(data, offset + 8)?,
+                    alpha,
+                ]
+            }
+            (BI_RGB, 8 | 24) => [0; 4],
+            _ => return Err(Error::Unsupported),
+        };
+        let palette = if depth == 8 {
+            let offset = FILE_HEADER_SIZE + header_size;
+            let colors = match read_u32(data, 46)? {
+                0 => 256,
+                colors => colors.min(256) as usize,
+            };
+            let palette = data
+                .get(offset..offset + 4 * colors)
+                .ok_or(Error::Truncated)?;
+            palette
+                .chunks_exact(4)
+                .map(|color| Rgba::opaque(color[2], color[1], color[0]))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Lines are padded to 4 bytes
+        let line_size = (width * depth as usize).div_ceil(32) * 4;
+        let end = line_size
+            .checked_mul(height)
+            .and_then(|size| size.checked_add(pixels_offset))
+            .ok_or(Error::InvalidHeader)?;
+        let lines = data.get(pixels_offset..end).ok_or(Error::Truncated)?;
+
+        let mut pixels = allocate_pixels(width * height)?;
+        for (index, line) in lines.chunks_exact(line_size).enumerate() {
+            let y = if top_down { index } else { height - 1 - index };
+            let pixels = &mut pixels[y * width..(y + 1) * width];
+            for (x, pixel) in pixels.iter_mut().enumerate() {
+                *pixel = match depth {
+                    8 => *palette.get(line[x] as usize).ok_or(Error::InvalidHeader)?,
+                    24 => Rgba::opaque(line[3 * x + 2], line[3 * x + 1], line[3 * x]),
+                    _ => {
+                        let value = u32::from_le_bytes(line[4 * x..4 * x + 4].try_into().unwrap());
+                        let [red, green, blue, alpha] = masks.map(|mask| extract(value, mask));
+                        // Without an alpha mask the fourth byte is unused
+                        let alpha = if masks[3] == 0 { 0xff } else { alpha };
+                        Rgba::new(red, green, blue, alpha)
+                    }
+                };
+            }
+        }
+
+        Ok(Self::new(width, height, pixels))
+    }
+
+    /// Decodes a true-colour or greyscale TGA image, raw or run-length encoded.
+    pub fn from_tga(data: &[u8]) -> Result<Self, Error> {
+        const HEADER_SIZE: usize = 18;
+        /// Whether the lines are stored from the top, in the image descriptor
+        const TOP_TO_BOTTOM: u8 = 1 << 5;
+        /// Whether the pixels of a line are stored from the right, in the image descriptor
+        const RIGHT_TO_LEFT: u8 = 1 << 4;
+
+        let header = data.get(..HEADER_SIZE).ok_or(Error::Truncated)?;
+        let (id_length, color_map_type, image_type) = (header[0], header[1], header[2]);
+        let color_map_length = read_u16(header, 5)? as usize;
+        let color_map_depth = header[7] as usize;
+        let width = read_u16(header, 12)? as usize;
+        let height = read_u16(header, 14)? as usize;
+        let depth = header[16];
+        let descriptor = header[17];
+
+        let (grayscale, run_length_encoded) = match image_type {
+            2 => (false, false),
+            3 => (true, false),
+            10 => (false, true),
+            11 => (true, true),
+            1 | 9 => return Err(Error::Unsupported),
+            _ => return Err(Error::InvalidHeader),
+        };
+        let bytes_per_pixel = match (grayscale, depth) {
+            (true, 8) => 1,
+            (false, 24) => 3,
+            (false, 32) => 4,
+            _ => return Err(Error::Unsupported),
+        };
+        if color_map_type > 1 || width == 0 || height == 0 {
+            return Err(Error::InvalidHeader);
+        }
+
+        // The colour map of a true-colour image is unused
+        let color_map_size = match color_map_type {
+            1 => (color_map_length * color_map_depth).div_ceil(8),
+            _ => 0,
+        };
+        let mut data = data
+            .get(HEADER_SIZE + id_length as usize + color_map_size..)
+            .ok_or(Error::Truncated)?;
+        let decode = |bytes: &[u8]| match *bytes {
+            [gray] => Rgba::opaque(gray, gray, gray),
+            [blue, green, red] => Rgba::opaque(red, green, blue),
+            [blue, green, red, alpha] => Rgba::new(red, green, blue, alpha),
+            _ => unreachable!(),
+        };
+
+        // The data must be able to hold the pixels before they are allocated, a packet of a
+        // run-length encoded image holding at most 128 pixels
+        let count = width * height;
+        let fits = if run_length_encoded {
+            count <= data.len() * 128
+        } else {
+            count * bytes_per_pixel <= data.len()
+        };
+        if !fits {
+            return Err(Error::Truncated);
+        }
+
+        let mut pixels = allocate_pixels(count)?;
+        let mut index = 0;
+        while index < count {
+            let (repeat, raw) = if run_length_encoded {
+                let (&packet, rest) = data.split_first().ok_or(Error::Truncated)?;
+                data = rest;
+                let length = (packet & 0x7f) as usize + 1;
+                if packet & 0x80 != 0 {
+                    (length, 1)
+                } else {
+                    (1, length)
+                }
+            } else {
+                (1, count)
+            };
+            let size = raw * bytes_per_pixel;
+            let bytes = data.get(..size).ok_or(Error::Truncated)?;
+            data = &data[size..];
+
+            for pixel in bytes.chunks_exact(bytes_per_pixel) {
+                let pixel = decode(pixel);
+                // A packet may run past the last pixel, whose excess is ignored
+                for _ in 0..repeat.min(count - index) {
+                    let (mut x, mut y) = (index % width, index / width);
+                    if descriptor & TOP_TO_BOTTOM == 0 {
+                        y = height - 1 - y;
+                    }
+                    if descriptor & RIGHT_TO_LEFT != 0 {
+                        x = width - 1 - x;
+                    }
+                    pixels[y * width + x] = pixel;
+                    index += 1;
+                }
+            }
+        }
+
+        Ok(Self::new(width, height, pixels))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the pixel at `x` and `y`, or `None` if it is out of the image.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Rgba> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.pixels[y * self.width + x])
+    }
+}
+
+/// Allocates `count` transparent pixels, without panicking if the heap can't hold them.
+fn allocate_pixels(count: usize) -> Result<Vec<Rgba>, Error> {
+    let mut pixels = Vec::new();
+    pixels
+        .try_reserve_exact(count)
+        .map_err(|_| Error::TooLarge)?;
+    pixels.resize(count, Rgba::default());
+    Ok(pixels)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Error> {
+    let bytes = data.get(offset..offset + 2).ok_or(Error::Truncated)?;
+    Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    let bytes = data.get(offset..offset + 4).ok_or(Error::Truncated)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Returns the 8-bit channel of `value` selected by `mask`.
+fn extract(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+    let channel = (value & mask) >> mask.trailing_zeros();
+    let max = mask >> mask.trailing_zeros();
+
+    ((channel as u64 * 0xff + max as u64 / 2) / max as u64) as u8
+}
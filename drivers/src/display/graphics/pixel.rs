@@ -0,0 +1,51 @@
+use bootloader_api::info::PixelFormat;
+
+use crate::display::frame_buffer::Rgb;
+
+/// Maximum number of bytes of a pixel that carry its colour, the next ones are padding.
+pub const MAX_COLOR_BYTES: usize = 4;
+
+/// Returns the bytes of `color` in `format`.
+pub fn encode(format: PixelFormat, color: Rgb) -> [u8; MAX_COLOR_BYTES] {
+    let Rgb { red, green, blue } = color;
+    match format {
+        PixelFormat::Bgr => [blue, green, red, 0],
+        PixelFormat::U8 => [color.luma(), 0, 0, 0],
+        PixelFormat::Unknown {
+            red_position,
+            green_position,
+            blue_position,
+        } => {
+            let shift =
+                |value: u8, position: u8| (value as u32).checked_shl(position.into()).unwrap_or(0);
+            let value = shift(red, red_position)
+                | shift(green, green_position)
+                | shift(blue, blue_position);
+            value.to_le_bytes()
+        }
+        // `Rgb`, and the formats added to the bootloader later
+        _ => [red, green, blue, 0],
+    }
+}
+
+/// Returns the colour of a pixel made of `bytes` in `format`.
+pub fn decode(format: PixelFormat, bytes: [u8; MAX_COLOR_BYTES]) -> Rgb {
+    match format {
+        PixelFormat::Bgr => Rgb::new(bytes[2], bytes[1], bytes[0]),
+        PixelFormat::U8 => Rgb::new(bytes[0], bytes[0], bytes[0]),
+        PixelFormat::Unknown {
+            red_position,
+            green_position,
+            blue_position,
+        } => {
+            let value = u32::from_le_bytes(bytes);
+            let shift = |position: u8| value.checked_shr(position.into()).unwrap_or(0) as u8;
+            Rgb::new(
+                shift(red_position),
+                shift(green_position),
+                shift(blue_position),
+            )
+        }
+        _ => Rgb::new(bytes[0], bytes[1], bytes[2]),
+    }
+}
@@ -0,0 +1,230 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use bootloader_api::info::{FrameBufferInfo, PixelFormat};
+
+use super::{Canvas, DoubleBuffer, Error, Image, Point, Rect, Rgba};
+use crate::display::frame_buffer::Rgb;
+
+const RED: Rgb = Rgb::new(0xff, 0, 0);
+const BLUE: Rgb = Rgb::new(0, 0, 0xff);
+
+fn info(pixel_format: PixelFormat, bytes_per_pixel: usize) -> FrameBufferInfo {
+    FrameBufferInfo {
+        byte_len: 10 * 8 * bytes_per_pixel,
+        width: 8,
+        height: 8,
+        pixel_format,
+        bytes_per_pixel,
+        stride: 10,
+    }
+}
+
+/// Returns the points of `canvas` of `color`.
+fn points(canvas: &Canvas, color: Rgb) -> Vec<(isize, isize)> {
+    let mut points = Vec::new();
+    for y in 0..canvas.height() as isize {
+        for x in 0..canvas.width() as isize {
+            if canvas.pixel(Point::new(x, y)) == Some(color) {
+                points.push((x, y));
+            }
+        }
+    }
+    points
+}
+
+#[test_case]
+fn test_pixel_formats() {
+    let formats = [
+        (PixelFormat::Rgb, 4, [0x12, 0x34, 0x56, 0]),
+        (PixelFormat::Bgr, 3, [0x56, 0x34, 0x12, 0]),
+        (
+            PixelFormat::Unknown {
+                red_position: 0,
+                green_position: 10,
+                blue_position: 20,
+            },
+            4,
+            [0x12, 0xd0, 0x60, 0x05],
+        ),
+    ];
+    for (format, bytes_per_pixel, expected) in formats {
+        let mut buffer = vec![0; 10 * 8 * bytes_per_pixel];
+        let mut canvas = Canvas::new(&mut buffer, info(format, bytes_per_pixel));
+        let color = Rgb::new(0x12, 0x34, 0x56);
+
+        canvas.set_pixel(Point::new(1, 2), color);
+        assert_eq!(canvas.pixel(Point::new(1, 2)), Some(color));
+        // the stride is larger than the width
+        let offset = (2 * 10 + 1) * bytes_per_pixel;
+        assert_eq!(
+            buffer[offset..offset + bytes_per_pixel],
+            expected[..bytes_per_pixel]
+        );
+    }
+
+    let mut buffer = vec![0; 10 * 8];
+    let mut canvas = Canvas::new(&mut buffer, info(PixelFormat::U8, 1));
+    canvas.set_pixel(Point::new(0, 0), Rgb::new(0xff, 0xff, 0xff));
+    assert_eq!(buffer[0], 0xff);
+}
+
+#[test_case]
+fn test_clipping() {
+    let mut buffer = vec![0; 10 * 8 * 4];
+    let mut canvas = Canvas::new(&mut buffer, info(PixelFormat::Rgb, 4));
+
+    canvas.set_pixel(Point::new(-1, 0), RED);
+    canvas.set_pixel(Point::new(8, 0), RED);
+    assert_eq!(canvas.pixel(Point::new(8, 0)), None);
+    assert!(points(&canvas, RED).is_empty());
+
+    canvas.fill_rect(Rect::new(-2, 6, 4, 10), RED);
+    assert_eq!(points(&canvas, RED), [(0, 6), (1, 6), (0, 7), (1, 7)]);
+    // the padding at the end of the lines is left as is
+    assert!(buffer[8 * 4..10 * 4].iter().all(|&byte| byte == 0));
+}
+
+#[test_case]
+fn test_shapes() {
+    let mut buffer = vec![0; 10 * 8 * 4];
+    let mut canvas = Canvas::new(&mut buffer, info(PixelFormat::Rgb, 4));
+
+    canvas.draw_line(Point::new(0, 0), Point::new(3, 1), RED);
+    assert_eq!(points(&canvas, RED), [(0, 0), (1, 0), (2, 1), (3, 1)]);
+
+    canvas.clear(Rgb::BLACK);
+    canvas.draw_rect(Rect::new(1, 1, 3, 3), RED);
+    assert_eq!(points(&canvas, RED).len(), 8);
+    assert_eq!(canvas.pixel(Point::new(2, 2)), Some(Rgb::BLACK));
+
+    canvas.clear(Rgb::BLACK);
+    canvas.draw_circle(Point::new(4, 4), 2, RED);
+    assert_eq!(
+        points(&canvas, RED),
+        [
+            (3, 2),
+            (4, 2),
+            (5, 2),
+            (2, 3),
+            (6, 3),
+            (2, 4),
+            (6, 4),
+            (2, 5),
+            (6, 5),
+            (3, 6),
+            (4, 6),
+            (5, 6)
+        ]
+    );
+
+    canvas.fill_circle(Point::new(4, 4), 2, BLUE);
+    assert_eq!(points(&canvas, BLUE).len(), 21);
+    assert!(points(&canvas, RED).is_empty());
+}
+
+#[test_case]
+fn test_blit() {
+    let mut buffer = vec![0; 10 * 8 * 4];
+    let mut canvas = Canvas::new(&mut buffer, info(PixelFormat::Bgr, 4));
+    canvas.clear(BLUE);
+
+    let image = Image::new(
+        2,
+        2,
+        vec![
+            Rgba::opaque(0xff, 0, 0),
+            Rgba::new(0xff, 0, 0, 0),
+            Rgba::new(0xff, 0, 0, 0x80),
+            Rgba::opaque(0xff, 0, 0),
+        ],
+    );
+    // only the first column is visible
+    canvas.blit(&image, Point::new(7, 6));
+    assert_eq!(points(&canvas, RED), [(7, 6)]);
+
+    canvas.blit(&image, Point::new(0, 0));
+    assert_eq!(canvas.pixel(Point::new(1, 0)), Some(BLUE));
+    assert_eq!(
+        canvas.pixel(Point::new(0, 1)),
+        Some(Rgb::new(0x80, 0, 0x7f))
+    );
+}
+
+#[test_case]
+fn test_double_buffer() {
+    let mut front = vec![0; 10 * 8 * 4];
+    let mut back = vec![0xff; DoubleBuffer::back_size(&info(PixelFormat::Rgb, 4))];
+    let mut buffer = DoubleBuffer::new(&mut front, &mut back, info(PixelFormat::Rgb, 4));
+
+    buffer.canvas().fill_rect(Rect::new(0, 0, 8, 2), RED);
+    buffer.mark_dirty(Rect::new(0, 1, 8, 1));
+    buffer.flush();
+    buffer.canvas().fill_rect(Rect::new(0, 2, 8, 1), BLUE);
+    // the lines move up, and the last one keeps its pixels
+    buffer.scroll(1..4, 1);
+    buffer.flush();
+
+    let front = Canvas::new(&mut front, info(PixelFormat::Rgb, 4));
+    // the first line wasn't marked as changed
+    assert_eq!(front.pixel(Point::new(0, 0)), Some(Rgb::BLACK));
+    assert_eq!(front.pixel(Point::new(7, 1)), Some(BLUE));
+    assert_eq!(front.pixel(Point::new(7, 2)), Some(Rgb::BLACK));
+    assert_eq!(front.pixel(Point::new(7, 3)), Some(Rgb::BLACK));
+}
+
+#[test_case]
+fn test_decode_bmp() {
+    // 2x2 pixels of 24 bits, stored from the bottom with lines padded to 8 bytes
+    let mut data = vec![0; 54];
+    data[..2].copy_from_slice(b"BM");
+    data[10] = 54;
+    data[14] = 40;
+    data[18] = 2;
+    data[22] = 2;
+    data[26] = 1;
+    data[28] = 24;
+    data.extend_from_slice(&[0xff, 0, 0, 0, 0xff, 0, 0, 0]);
+    data.extend_from_slice(&[0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0]);
+
+    let image = Image::decode(&data).unwrap();
+    assert_eq!((image.width(), image.height()), (2, 2));
+    assert_eq!(image.pixel(0, 0), Some(Rgba::opaque(0xff, 0, 0)));
+    assert_eq!(image.pixel(1, 0), Some(Rgba::opaque(0xff, 0xff, 0xff)));
+    assert_eq!(image.pixel(0, 1), Some(Rgba::opaque(0, 0, 0xff)));
+    assert_eq!(image.pixel(1, 1), Some(Rgba::opaque(0, 0xff, 0)));
+
+    assert_eq!(Image::decode(&data[..60]), Err(Error::Truncated));
+    data[28] = 16;
+    assert_eq!(Image::decode(&data), Err(Error::Unsupported));
+}
+
+#[test_case]
+fn test_decode_tga() {
+    // 3x1 pixels of 32 bits, run-length encoded from the top
+    let mut data = vec![0; 18];
+    data[2] = 10;
+    data[12] = 3;
+    data[14] = 1;
+    data[16] = 32;
+    data[17] = 1 << 5;
+    // two pixels repeated, then one raw pixel
+    data.extend_from_slice(&[0x81, 0xff, 0, 0, 0xff]);
+    data.extend_from_slice(&[0x00, 0, 0, 0xff, 0x80]);
+
+    let image = Image::decode(&data).unwrap();
+    assert_eq!((image.width(), image.height()), (3, 1));
+    assert_eq!(image.pixel(1, 0), Some(Rgba::opaque(0, 0, 0xff)));
+    assert_eq!(image.pixel(2, 0), Some(Rgba::new(0xff, 0, 0, 0x80)));
+
+    // sizes that the data can't hold are rejected before the pixels are allocated
+    data[12..16].copy_from_slice(&[0xff; 4]);
+    assert_eq!(Image::decode(&data), Err(Error::Truncated));
+    data[2] = 2;
+    data[12..16].copy_from_slice(&[3, 0, 1, 0]);
+    assert_eq!(Image::decode(&data), Err(Error::Truncated));
+
+    data[2] = 1;
+    assert_eq!(Image::decode(&data), Err(Error::Unsupported));
+    assert_eq!(Image::decode(&data[..10]), Err(Error::Truncated));
+}
@@ -0,0 +1,12 @@
+mod cell;
+mod inner;
+mod parser;
+mod scrollback;
+
+pub use cell::{Attributes, Cell, Color, Style};
+pub use inner::{Scroll, Terminal};
+pub use parser::{Action, Csi, Parser};
+pub use scrollback::SCROLLBACK_BYTES;
+
+#[cfg(test)]
+mod tests;
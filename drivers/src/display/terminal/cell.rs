@@ -0,0 +1,57 @@
+use bitflags::bitflags;
+
+/// Colour of the text or of the background of a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+    /// The default colour of the renderer
+    #[default]
+    Default,
+    /// Colour of the 256-colour palette of xterm, whose first 16 colours are the ANSI ones
+    Indexed(u8),
+    /// 24-bit colour
+    Rgb(u8, u8, u8),
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct Attributes: u8 {
+        const BOLD = 1 << 0;
+        const UNDERLINE = 1 << 1;
+        /// Swaps the colours of the text and of the background.
+        const REVERSE = 1 << 2;
+    }
+}
+
+/// How the text of a cell is drawn, set by the Select Graphic Rendition sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub foreground: Color,
+    pub background: Color,
+    pub attributes: Attributes,
+}
+
+/// A char of the grid of a terminal, with its style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub character: char,
+    pub style: Style,
+}
+
+impl Cell {
+    /// Returns an empty cell, which keeps the background colour of `style`.
+    pub fn blank(style: Style) -> Self {
+        Self {
+            character: ' ',
+            style: Style {
+                background: style.background,
+                ..Style::default()
+            },
+        }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::blank(Style::default())
+    }
+}
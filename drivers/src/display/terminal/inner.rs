@@ -0,0 +1,604 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem::size_of;
+use core::ops::Range;
+
+use super::cell::{Attributes, Cell, Color, Style};
+use super::parser::{Action, Csi, Parser};
+use super::scrollback::Scrollback;
+
+/// Distance between the default tab stops.
+const TAB_WIDTH: usize = 8;
+
+/// DEC private modes.
+const AUTO_WRAP_MODE: u16 = 7;
+const CURSOR_VISIBLE_MODE: u16 = 25;
+
+/// Lines of the screen that moved since they were drawn, which the renderer can move instead of
+/// drawing them again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scroll {
+    /// Rows that moved, the lines that left them are lost
+    pub rows: Range<usize>,
+    /// Number of rows by which the lines moved, up if positive, and down if negative
+    pub lines: isize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SavedCursor {
+    row: usize,
+    column: usize,
+    style: Style,
+}
+
+/// A text terminal, which keeps a grid of cells updated by the text written to it, with the
+/// control chars and the escape sequences of the VT100 and of ANSI terminals.
+///
+/// It only keeps the text: a renderer moves the lines that scrolled, see [`Self::take_scroll`],
+/// then draws the cells that changed, see [`Self::take_damage`].
+#[derive(Debug, Clone)]
+pub struct Terminal {
+    columns: usize,
+    rows: usize,
+    grid: Vec<Vec<Cell>>,
+    /// Lines that scrolled off the top of the screen
+    scrollback: Scrollback,
+    /// Number of lines of the scrollback shown above the screen, 0 to show the screen only
+    view_offset: usize,
+    row: usize,
+    /// Column of the cursor, `columns` once a char was written in the last column, until the
+    /// next char wraps to the next line
+    column: usize,
+    /// Style of the chars written
+    style: Style,
+    saved_cursor: Option<SavedCursor>,
+    tab_stops: Vec<bool>,
+    /// Rows that scroll, from the top margin to the bottom margin
+    scroll_region: Range<usize>,
+    cursor_visible: bool,
+    auto_wrap: bool,
+    parser: Parser,
+    /// Columns of each row of the screen that changed since they were drawn
+    damage: Vec<Option<Range<usize>>>,
+    /// Lines that moved since they were drawn
+    scroll: Option<Scroll>,
+}
+
+impl Terminal {
+    /// Creates an empty terminal of `columns` by `rows` cells, entirely damaged.
+    pub fn new(columns: usize, rows: usize) -> Self {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+
+        Self {
+            columns,
+            rows,
+            grid: vec![vec![Cell::default(); columns]; rows],
+            scrollback: Scrollback::default(),
+            view_offset: 0,
+            row: 0,
+            column: 0,
+            style: Style::default(),
+            saved_cursor: None,
+            tab_stops: (0..columns).map(|column| column % TAB_WIDTH == 0).collect(),
+            scroll_region: 0..rows,
+            cursor_visible: true,
+            auto_wrap: true,
+            parser: Parser::new(),
+            damage: vec![Some(0..columns); rows],
+            scroll: None,
+        }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of lines in the scrollback.
+    pub fn scrollback_len(&self) -> usize {
+        self.scrollback.len()
+    }
+
+    /// Returns the number of bytes allocated for the cells of the screen and of the scrollback.
+    pub fn footprint(&self) -> usize {
+        let screen = self.grid.iter().map(Vec::capacity).sum::<usize>() * size_of::<Cell>()
+            + self.grid.capacity() * size_of::<Vec<Cell>>();
+        screen + self.scrollback.footprint()
+    }
+
+    /// Writes `text`, and goes back to showing the screen if the scrollback was shown.
+    ///
+    /// Like the terminals with output processing, a line feed also returns to the start of the
+    /// line.
+    pub fn write(&mut self, text: &str) {
+        if self.view_offset != 0 {
+            self.view_offset = 0;
+            self.damage_all();
+        }
+
+        for c in text.chars() {
+            match self.parser.advance(c) {
+                Some(Action::Print(c)) => self.print(c),
+                Some(Action::Execute(c)) => self.execute(c),
+                Some(Action::Escape {
+                    intermediate: None,
+                    final_byte,
+                }) => self.escape(final_byte),
+                Some(Action::Csi(csi)) => self.csi(&csi),
+                Some(Action::Escape { .. }) | None => {}
+            }
+        }
+    }
+
+    /// Returns the cell shown at `row` and `column`, which is in the scrollback while it is
+    /// shown.
+    pub fn cell(&self, row: usize, column: usize) -> Cell {
+        match row.checked_sub(self.view_offset) {
+            Some(row) => self.grid[row].get(column).copied().unwrap_or_default(),
+            None => self
+                .scrollback
+                .cell(self.scrollback.len() - self.view_offset + row, column),
+        }
+    }
+
+    /// Returns the row and the column of the cursor, unless it is hidden or the scrollback is
+    /// shown.
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        (self.cursor_visible && self.view_offset == 0)
+            .then_some((self.row, self.column.min(self.columns - 1)))
+    }
+
+    /// Returns the lines that moved since the last call, which the renderer must move before
+    /// drawing the cells that changed.
+    pub fn take_scroll(&mut self) -> Option<Scroll> {
+        self.scroll.take()
+    }
+
+    /// Returns the columns of `row` that changed since the last call, which the renderer must
+    /// draw again.
+    pub fn take_damage(&mut self, row: usize) -> Option<Range<usize>> {
+        self.damage[row].take()
+    }
+
+    /// Shows `lines` more lines of the scrollback, as far as the oldest one.
+    pub fn scroll_back(&mut self, lines: usize) {
+        let view_offset = (self.view_offset + lines).min(self.scrollback.len());
+        if view_offset != self.view_offset {
+            self.view_offset = view_offset;
+            self.damage_all();
+        }
+    }
+
+    /// Shows `lines` fewer lines of the scrollback, as far as showing only the screen.
+    pub fn scroll_forward(&mut self, lines: usize) {
+        let view_offset = self.view_offset.saturating_sub(lines);
+        if view_offset != self.view_offset {
+            self.view_offset = view_offset;
+            self.damage_all();
+        }
+    }
+
+    /// Erases the screen, and moves the cursor to its top left corner.
+    pub fn clear(&mut self) {
+        self.erase_rows(0..self.rows);
+        self.row = 0;
+        self.column = 0;
+    }
+
+    /// Changes the size of the screen to `columns` by `rows` cells, which keeps the top left
+    /// of the text and the line of the cursor.
+    ///
+    /// The lines above the line of the cursor go to the scrollback when the screen gets too
+    /// short for it. The scroll region becomes the whole screen.
+    pub fn resize(&mut self, columns: usize, rows: usize) {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+
+        if self.row >= rows {
+            let excess = self.row + 1 - rows;
+            for line in self.grid.drain(..excess) {
+                self.scrollback.push(&line);
+            }
+            self.row -= excess;
+        }
+        self.grid.resize(rows, vec![Cell::default(); columns]);
+        for line in &mut self.grid {
+            line.resize(columns, Cell::default());
+        }
+        self.tab_stops = (0..columns).map(|column| column % TAB_WIDTH == 0).collect();
+
+        self.columns = columns;
+        self.rows = rows;
+        self.column = self.column.min(columns);
+        self.scroll_region = 0..rows;
+        self.view_offset = 0;
+        self.damage = vec![Some(0..columns); rows];
+        self.scroll = None;
+    }
+
+    fn damage(&mut self, row: usize, columns: Range<usize>) {
+        let damage = &mut self.damage[row];
+        *damage = Some(match damage.take() {
+            Some(damaged) => damaged.start.min(columns.start)..damaged.end.max(columns.end),
+            None => columns,
+        });
+    }
+
+    /// Marks every cell as changed, for a renderer that lost what it drew.
+    pub fn damage_all(&mut self) {
+        self.damage.fill(Some(0..self.columns));
+        self.scroll = None;
+    }
+
+    fn print(&mut self, c: char) {
+        if self.column >= self.columns {
+            if self.auto_wrap {
+                self.column = 0;
+                self.index();
+            } else {
+                self.column = self.columns - 1;
+            }
+        }
+
+        self.grid[self.row][self.column] = Cell {
+            character: c,
+            style: self.style,
+        };
+        self.damage(self.row, self.column..self.column + 1);
+        self.column += 1;
+    }
+
+    fn execute(&mut self, c: char) {
+        match c {
+            '\x08' => self.backspace(),
+            '\t' => self.tab_forward(1),
+            '\n' | '\x0b' | '\x0c' => {
+                self.column = 0;
+                self.index();
+            }
+            '\r' => self.column = 0,
+            _ => {}
+        }
+    }
+
+    fn escape(&mut self, final_byte: u8) {
+        match final_byte {
+            b'7' => self.save_cursor(),
+            b'8' => self.restore_cursor(),
+            b'D' => self.index(),
+            b'E' => {
+                self.column = 0;
+                self.index();
+            }
+            b'H' => self.tab_stops[self.column.min(self.columns - 1)] = true,
+            b'M' => self.reverse_index(),
+            b'c' => self.reset(),
+            _ => {}
+        }
+    }
+
+    fn csi(&mut self, csi: &Csi) {
+        let count = csi.parameter(0, 1) as usize;
+        let column = self.column.min(self.columns - 1);
+
+        match (csi.private, csi.intermediate, csi.final_byte) {
+            (None, None, b'A') => self.move_to(self.row.saturating_sub(count), column),
+            (None, None, b'B' | b'e') => self.move_to(self.row + count, column),
+            (None, None, b'C' | b'a') => self.move_to(self.row, column + count),
+            (None, None, b'D') => self.move_to(self.row, column.saturating_sub(count)),
+            (None, None, b'E') => self.move_to(self.row + count, 0),
+            (None, None, b'F') => self.move_to(self.row.saturating_sub(count), 0),
+            (None, None, b'G' | b'`') => self.move_to(self.row, count - 1),
+            (None, None, b'H' | b'f') => self.move_to(count - 1, csi.parameter(1, 1) as usize - 1),
+            (None, None, b'I') => self.tab_forward(count),
+            (None, None, b'J') => self.erase_display(csi.parameter(0, 0)),
+            (None, None, b'K') => self.erase_line(csi.parameter(0, 0)),
+            (None, None, b'L') => self.insert_lines(count),
+            (None, None, b'M') => self.delete_lines(count),
+            (None, None, b'P') => self.delete_chars(count),
+            (None, None, b'S') => self.scroll_up(count),
+            (None, None, b'T') => self.scroll_down(count),
+            (None, None, b'X') => self.erase(self.row, column..column + count),
+            (None, None, b'Z') => self.tab_backward(count),
+            (None, None, b'@') => self.insert_chars(count),
+            (None, None, b'd') => self.move_to(count - 1, column),
+            (None, None, b'g') => match csi.parameter(0, 0) {
+                0 => self.tab_stops[column] = false,
+                3 => self.tab_stops.fill(false),
+                _ => {}
+            },
+            (None, None, b'm') => self.select_graphic_rendition(csi.parameters()),
+            (None, None, b'r') => {
+                self.set_scroll_region(csi.parameter(0, 1), csi.parameter(1, self.rows as u16))
+            }
+            (None, None, b's') => self.save_cursor(),
+            (None, None, b'u') => self.restore_cursor(),
+            (Some(b'?'), None, final_byte @ (b'h' | b'l')) => {
+                for &mode in csi.parameters() {
+                    self.set_private_mode(mode, final_byte == b'h');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the cursor to `row` and `column`, within the screen.
+    fn move_to(&mut self, row: usize, column: usize) {
+        self.row = row.min(self.rows - 1);
+        self.column = column.min(self.columns - 1);
+    }
+
+    /// Moves the cursor back by one column, or to the end of the previous line from the start
+    /// of a line, like the reverse wraparound of xterm.
+    fn backspace(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.column = self.columns - 1;
+        }
+    }
+
+    fn tab_forward(&mut self, count: usize) {
+        for _ in 0..count {
+            let next = (self.column + 1..self.columns).find(|&column| self.tab_stops[column]);
+            self.column = next.unwrap_or(self.columns - 1);
+        }
+    }
+
+    fn tab_backward(&mut self, count: usize) {
+        for _ in 0..count {
+            let column = self.column.min(self.columns);
+            let previous = (0..column).rev().find(|&column| self.tab_stops[column]);
+            self.column = previous.unwrap_or(0);
+        }
+    }
+
+    /// Moves the cursor down, scrolling up at the bottom margin.
+    fn index(&mut self) {
+        if self.row + 1 == self.scroll_region.end {
+            self.scroll_up(1);
+        } else if self.row + 1 < self.rows {
+            self.row += 1;
+        }
+    }
+
+    /// Moves the cursor up, scrolling down at the top margin.
+    fn reverse_index(&mut self) {
+        if self.row == self.scroll_region.start {
+            self.scroll_down(1);
+        } else if self.row > 0 {
+            self.row -= 1;
+        }
+    }
+
+    /// Scrolls the scroll region up by `count` lines, keeping the lines that leave the top of
+    /// the screen in the scrollback.
+    fn scroll_up(&mut self, count: usize) {
+        let region = self.scroll_region.clone();
+        if region.start == 0 {
+            for line in &self.grid[..count.min(region.len())] {
+                self.scrollback.push(line);
+            }
+        }
+        self.rotate_rows(region, count, true);
+    }
+
+    fn scroll_down(&mut self, count: usize) {
+        self.rotate_rows(self.scroll_region.clone(), count, false);
+    }
+
+    /// Inserts `count` blank lines at the cursor, pushing the next ones to the bottom margin.
+    fn insert_lines(&mut self, count: usize) {
+        if self.scroll_region.contains(&self.row) {
+            self.rotate_rows(self.row..self.scroll_region.end, count, false);
+            self.column = 0;
+        }
+    }
+
+    /// Deletes `count` lines at the cursor, pulling the next ones from the bottom margin.
+    fn delete_lines(&mut self, count: usize) {
+        if self.scroll_region.contains(&self.row) {
+            self.rotate_rows(self.row..self.scroll_region.end, count, true);
+            self.column = 0;
+        }
+    }
+
+    /// Moves the lines of `rows` up or down by `count` rows, and blanks the lines that appear.
+    ///
+    /// The damage moves with the lines, which the renderer moves too.
+    fn rotate_rows(&mut self, rows: Range<usize>, count: usize, up: bool) {
+        let count = count.min(rows.len());
+        let lines = &mut self.grid[rows.clone()];
+        let damage = &mut self.damage[rows.clone()];
+
+        let blank = if up {
+            lines.rotate_left(count);
+            damage.rotate_left(count);
+            rows.end - count..rows.end
+        } else {
+            lines.rotate_right(count);
+            damage.rotate_right(count);
+            rows.start..rows.start + count
+        };
+        self.erase_rows(blank);
+
+        let lines = if up {
+            count as isize
+        } else {
+            -(count as isize)
+        };
+        self.scroll = match self.scroll.take() {
+            None => Some(Scroll { rows, lines }),
+            Some(scroll) if scroll.rows == rows => Some(Scroll {
+                rows,
+                lines: scroll.lines + lines,
+            }),
+            // Moves of different regions can't be combined, so both are drawn again
+            Some(scroll) => {
+                let start = scroll.rows.start.min(rows.start);
+                let end = scroll.rows.end.max(rows.end);
+                for row in start..end {
+                    self.damage(row, 0..self.columns);
+                }
+                None
+            }
+        };
+    }
+
+    fn insert_chars(&mut self, count: usize) {
+        let column = self.column.min(self.columns - 1);
+        let count = count.min(self.columns - column);
+
+        self.grid[self.row][column..].rotate_right(count);
+        self.erase(self.row, column..column + count);
+        self.damage(self.row, column..self.columns);
+    }
+
+    fn delete_chars(&mut self, count: usize) {
+        let column = self.column.min(self.columns - 1);
+        let count = count.min(self.columns - column);
+
+        self.grid[self.row][column..].rotate_left(count);
+        self.erase(self.row, self.columns - count..self.columns);
+        self.damage(self.row, column..self.columns);
+    }
+
+    /// Blanks the cells of `row` in `columns`, with the current background colour.
+    fn erase(&mut self, row: usize, columns: Range<usize>) {
+        let columns = columns.start.min(self.columns)..columns.end.min(self.columns);
+        self.grid[row][columns.clone()].fill(Cell::blank(self.style));
+        self.damage(row, columns);
+    }
+
+    fn erase_rows(&mut self, rows: Range<usize>) {
+        for row in rows {
+            self.erase(row, 0..self.columns);
+        }
+    }
+
+    /// Erases from the cursor to the end of the screen (0), from the start of the screen to the
+    /// cursor (1), the screen (2), or the screen and the scrollback (3).
+    fn erase_display(&mut self, mode: u16) {
+        match mode {
+            0 => {
+                self.erase_line(0);
+                self.erase_rows(self.row + 1..self.rows);
+            }
+            1 => {
+                self.erase_rows(0..self.row);
+                self.erase_line(1);
+            }
+            2 => self.erase_rows(0..self.rows),
+            3 => {
+                self.erase_rows(0..self.rows);
+                self.scrollback.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Erases from the cursor to the end of the line (0), from the start of the line to the
+    /// cursor (1), or the line (2).
+    fn erase_line(&mut self, mode: u16) {
+        let column = self.column.min(self.columns - 1);
+        match mode {
+            0 => self.erase(self.row, column..self.columns),
+            1 => self.erase(self.row, 0..column + 1),
+            2 => self.erase(self.row, 0..self.columns),
+            _ => {}
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, parameters: &[u16]) {
+        if parameters.is_empty() {
+            self.style = Style::default();
+            return;
+        }
+
+        let mut parameters = parameters.iter().copied();
+        while let Some(parameter) = parameters.next() {
+            let style = &mut self.style;
+            match parameter {
+                0 => *style = Style::default(),
+                1 => style.attributes.insert(Attributes::BOLD),
+                4 => style.attributes.insert(Attributes::UNDERLINE),
+                7 => style.attributes.insert(Attributes::REVERSE),
+                22 => style.attributes.remove(Attributes::BOLD),
+                24 => style.attributes.remove(Attributes::UNDERLINE),
+                27 => style.attributes.remove(Attributes::REVERSE),
+                30..=37 => style.foreground = Color::Indexed(parameter as u8 - 30),
+                38 => style.foreground = extended_color(&mut parameters).unwrap_or_default(),
+                39 => style.foreground = Color::Default,
+                40..=47 => style.background = Color::Indexed(parameter as u8 - 40),
+                48 => style.background = extended_color(&mut parameters).unwrap_or_default(),
+                49 => style.background = Color::Default,
+                90..=97 => style.foreground = Color::Indexed(parameter as u8 - 90 + 8),
+                100..=107 => style.background = Color::Indexed(parameter as u8 - 100 + 8),
+                _ => {}
+            }
+        }
+    }
+
+    /// Sets the margins, given from 1, and moves the cursor to the top left corner.
+    fn set_scroll_region(&mut self, top: u16, bottom: u16) {
+        let top = top as usize - 1;
+        let bottom = (bottom as usize).min(self.rows);
+        if top + 1 < bottom {
+            self.scroll_region = top..bottom;
+            self.move_to(0, 0);
+        }
+    }
+
+    fn set_private_mode(&mut self, mode: u16, enabled: bool) {
+        match mode {
+            AUTO_WRAP_MODE => self.auto_wrap = enabled,
+            CURSOR_VISIBLE_MODE => self.cursor_visible = enabled,
+            _ => {}
+        }
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved_cursor = Some(SavedCursor {
+            row: self.row,
+            column: self.column.min(self.columns - 1),
+            style: self.style,
+        });
+    }
+
+    /// Restores the cursor saved last, or moves it to the top left corner with the default
+    /// style if none was saved.
+    fn restore_cursor(&mut self) {
+        let saved = self.saved_cursor.unwrap_or(SavedCursor {
+            row: 0,
+            column: 0,
+            style: Style::default(),
+        });
+        self.move_to(saved.row, saved.column);
+        self.style = saved.style;
+    }
+
+    /// Goes back to the state of a new terminal, but keeps the scrollback.
+    fn reset(&mut self) {
+        let scrollback = core::mem::take(&mut self.scrollback);
+        *self = Self {
+            scrollback,
+            ..Self::new(self.columns, self.rows)
+        };
+    }
+}
+
+/// Returns the colour given by the parameters that follow 38 or 48: 5 and an index in the
+/// 256-colour palette, or 2 and the red, green and blue components.
+fn extended_color(parameters: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    let mut component = || parameters.next().map(|value| value.min(255) as u8);
+    match component()? {
+        5 => Some(Color::Indexed(component()?)),
+        2 => Some(Color::Rgb(component()?, component()?, component()?)),
+        _ => None,
+    }
+}
@@ -0,0 +1,199 @@
+/// Maximum number of parameters of a control sequence, the next ones are ignored.
+const MAX_PARAMETERS: usize = 16;
+
+const ESCAPE: char = '\x1b';
+/// Cancel and Substitute, which abort the sequence being parsed.
+const CANCEL: char = '\x18';
+const SUBSTITUTE: char = '\x1a';
+const BELL: char = '\x07';
+
+/// A control sequence introduced by `ESC [`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Csi {
+    parameters: [u16; MAX_PARAMETERS],
+    count: usize,
+    /// Private marker before the parameters, like the `?` of the DEC private modes
+    pub private: Option<u8>,
+    /// Intermediate byte between the parameters and the final byte
+    pub intermediate: Option<u8>,
+    pub final_byte: u8,
+}
+
+impl Csi {
+    /// Returns the parameters, 0 for the ones left empty.
+    pub fn parameters(&self) -> &[u16] {
+        &self.parameters[..self.count]
+    }
+
+    /// Returns the parameter at `index`, or `default` if it is missing or 0.
+    pub fn parameter(&self, index: usize, default: u16) -> u16 {
+        match self.parameters().get(index) {
+            None | Some(0) => default,
+            Some(&parameter) => parameter,
+        }
+    }
+}
+
+/// What the text written to a terminal asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Shows a char
+    Print(char),
+    /// Runs a C0 control char, like a line feed
+    Execute(char),
+    /// Runs an escape sequence, `ESC` followed by an optional intermediate byte and a final byte
+    Escape {
+        intermediate: Option<u8>,
+        final_byte: u8,
+    },
+    /// Runs a control sequence
+    Csi(Csi),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    EscapeIntermediate(u8),
+    CsiParameters,
+    /// A malformed control sequence, ignored up to its final byte
+    CsiIgnore,
+    /// An operating system command, ignored up to its terminator
+    OperatingSystemCommand,
+}
+
+/// Splits the text written to a terminal into [`Action`]s, following the state machine of the
+/// VT100 and of ECMA-48.
+#[derive(Debug, Clone)]
+pub struct Parser {
+    state: State,
+    csi: Csi,
+}
+
+impl Parser {
+    pub const fn new() -> Self {
+        Self {
+            state: State::Ground,
+            csi: Csi {
+                parameters: [0; MAX_PARAMETERS],
+                count: 0,
+                private: None,
+                intermediate: None,
+                final_byte: 0,
+            },
+        }
+    }
+
+    /// Parses `c`, and returns the action that it completes.
+    pub fn advance(&mut self, c: char) -> Option<Action> {
+        match c {
+            CANCEL | SUBSTITUTE => {
+                self.state = State::Ground;
+                return None;
+            }
+            ESCAPE => {
+                self.state = State::Escape;
+                return None;
+            }
+            _ => {}
+        }
+
+        match self.state {
+            State::Ground if c.is_control() => Some(Action::Execute(c)),
+            State::Ground => Some(Action::Print(c)),
+            // control chars are run in the middle of sequences
+            State::Escape
+            | State::EscapeIntermediate(_)
+            | State::CsiParameters
+            | State::CsiIgnore
+                if (c as u32) < 0x20 =>
+            {
+                Some(Action::Execute(c))
+            }
+            State::Escape => self.escape(c),
+            State::EscapeIntermediate(intermediate) => {
+                self.state = State::Ground;
+                match c {
+                    '\x30'..='\x7e' => Some(Action::Escape {
+                        intermediate: Some(intermediate),
+                        final_byte: c as u8,
+                    }),
+                    _ => None,
+                }
+            }
+            State::CsiParameters => self.csi_parameter(c),
+            State::CsiIgnore => {
+                if matches!(c, '\x40'..='\x7e') {
+                    self.state = State::Ground;
+                }
+                None
+            }
+            State::OperatingSystemCommand => {
+                if c == BELL {
+                    self.state = State::Ground;
+                }
+                None
+            }
+        }
+    }
+
+    fn escape(&mut self, c: char) -> Option<Action> {
+        self.state = State::Ground;
+        match c {
+            '[' => {
+                self.csi = Csi::default();
+                self.state = State::CsiParameters;
+                None
+            }
+            ']' => {
+                self.state = State::OperatingSystemCommand;
+                None
+            }
+            '\x20'..='\x2f' => {
+                self.state = State::EscapeIntermediate(c as u8);
+                None
+            }
+            '\x30'..='\x7e' => Some(Action::Escape {
+                intermediate: None,
+                final_byte: c as u8,
+            }),
+            _ => None,
+        }
+    }
+
+    fn csi_parameter(&mut self, c: char) -> Option<Action> {
+        let csi = &mut self.csi;
+        match c {
+            '0'..='9' if csi.intermediate.is_none() => {
+                if csi.count == 0 {
+                    csi.count = 1;
+                }
+                if let Some(parameter) = csi.parameters.get_mut(csi.count - 1) {
+                    *parameter = parameter
+                        .saturating_mul(10)
+                        .saturating_add(c as u16 - '0' as u16);
+                }
+            }
+            ';' if csi.intermediate.is_none() => {
+                // an empty first parameter is still a parameter
+                csi.count = (csi.count.max(1) + 1).min(MAX_PARAMETERS + 1);
+            }
+            '<'..='?' if csi.count == 0 && csi.private.is_none() => csi.private = Some(c as u8),
+            '\x20'..='\x2f' if csi.intermediate.is_none() => csi.intermediate = Some(c as u8),
+            '\x40'..='\x7e' => {
+                self.state = State::Ground;
+                csi.count = csi.count.min(MAX_PARAMETERS);
+                csi.final_byte = c as u8;
+                return Some(Action::Csi(*csi));
+            }
+            _ => self.state = State::CsiIgnore,
+        }
+        None
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
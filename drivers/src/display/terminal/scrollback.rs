@@ -0,0 +1,154 @@
+use alloc::collections::VecDeque;
+use core::mem::size_of;
+
+use super::cell::{Attributes, Cell, Color, Style};
+
+/// Maximum number of bytes taken by the lines kept once they scrolled off the top of the
+/// screen.
+pub const SCROLLBACK_BYTES: usize = 32 * 1024;
+
+/// Half of the bytes hold the chars, and the rest the styles and the starts of the lines.
+const MAX_CHARS: usize = SCROLLBACK_BYTES / 2 / size_of::<char>();
+const MAX_RUNS: usize = SCROLLBACK_BYTES / 4 / size_of::<StyleRun>();
+const MAX_LINES: usize = SCROLLBACK_BYTES / 4 / size_of::<usize>();
+
+/// Style of the chars from `start` to the start of the next run.
+#[derive(Debug, Clone, Copy)]
+struct StyleRun {
+    start: usize,
+    style: Style,
+}
+
+/// Lines that scrolled off the top of the screen, the oldest first, which take at most
+/// [`SCROLLBACK_BYTES`].
+///
+/// Lines are kept without their trailing blanks, and the styles as runs of chars that share one.
+/// Chars are indexed from the first one ever pushed, so that dropping the oldest lines does not
+/// move the indices of the others. The oldest lines are dropped to make room for the new ones.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Scrollback {
+    chars: VecDeque<char>,
+    runs: VecDeque<StyleRun>,
+    /// Index of the first char of each line
+    lines: VecDeque<usize>,
+    /// Index of the first char of `chars`
+    first: usize,
+}
+
+impl Scrollback {
+    pub(super) fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the number of bytes allocated for the lines.
+    pub(super) fn footprint(&self) -> usize {
+        self.chars.capacity() * size_of::<char>()
+            + self.runs.capacity() * size_of::<StyleRun>()
+            + self.lines.capacity() * size_of::<usize>()
+    }
+
+    /// Returns the cell at `column` of the line `line`, the oldest being 0.
+    pub(super) fn cell(&self, line: usize, column: usize) -> Cell {
+        let start = self.lines[line];
+        let end = self.lines.get(line + 1).copied().unwrap_or(self.end());
+        if column >= end - start {
+            return Cell::default();
+        }
+
+        let index = start + column;
+        let run = self.runs.partition_point(|run| run.start <= index) - 1;
+        Cell {
+            character: self.chars[index - self.first],
+            style: self.runs[run].style,
+        }
+    }
+
+    /// Adds `line` after the newest line, dropping the oldest ones if it doesn't fit.
+    ///
+    /// A line too long to fit on its own is cut.
+    pub(super) fn push(&mut self, line: &[Cell]) {
+        let length = line
+            .iter()
+            .rposition(|cell| !is_blank(cell))
+            .map_or(0, |last| last + 1);
+        let line = &line[..length.min(MAX_CHARS)];
+
+        let mut style = self.runs.back().map(|run| run.style);
+        let mut runs = 0;
+        for cell in line {
+            if style != Some(cell.style) {
+                style = Some(cell.style);
+                runs += 1;
+            }
+        }
+        while !self.lines.is_empty()
+            && (self.lines.len() == MAX_LINES
+                || self.chars.len() + line.len() > MAX_CHARS
+                || self.runs.len() + runs > MAX_RUNS)
+        {
+            self.pop_front();
+        }
+
+        reserve(&mut self.lines, 1, MAX_LINES);
+        reserve(&mut self.chars, line.len(), MAX_CHARS);
+        reserve(&mut self.runs, runs.min(MAX_RUNS), MAX_RUNS);
+
+        self.lines.push_back(self.end());
+        for cell in line {
+            if self.runs.back().map(|run| run.style) != Some(cell.style) {
+                if self.runs.len() == MAX_RUNS {
+                    break;
+                }
+                let start = self.end();
+                self.runs.push_back(StyleRun {
+                    start,
+                    style: cell.style,
+                });
+            }
+            self.chars.push_back(cell.character);
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.first = self.end();
+        self.chars.clear();
+        self.runs.clear();
+        self.lines.clear();
+    }
+
+    /// Returns the index of the char after the last one.
+    fn end(&self) -> usize {
+        self.first + self.chars.len()
+    }
+
+    /// Drops the oldest line, and the styles that only its chars used.
+    fn pop_front(&mut self) {
+        self.lines.pop_front();
+        let first = self.lines.front().copied().unwrap_or(self.end());
+        self.chars.drain(..first - self.first);
+        self.first = first;
+
+        while self.runs.get(1).is_some_and(|run| run.start <= first) {
+            self.runs.pop_front();
+        }
+    }
+}
+
+/// Returns whether `cell` looks like an empty one, with which it is replaced at the end of a
+/// line.
+fn is_blank(cell: &Cell) -> bool {
+    let visible = Attributes::UNDERLINE | Attributes::REVERSE;
+    cell.character == ' '
+        && cell.style.background == Color::Default
+        && !cell.style.attributes.intersects(visible)
+}
+
+/// Makes room for `additional` more items in `ring`, without giving it room for more than
+/// `max` items.
+fn reserve<T>(ring: &mut VecDeque<T>, additional: usize, max: usize) {
+    let required = ring.len() + additional;
+    if required > ring.capacity() {
+        let capacity = required.max(ring.capacity() * 2).min(max);
+        ring.reserve_exact(capacity - ring.len());
+    }
+}
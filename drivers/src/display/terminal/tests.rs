@@ -0,0 +1,208 @@
+use alloc::format;
+use alloc::string::String;
+
+use super::{Action, Attributes, Color, Parser, SCROLLBACK_BYTES, Scroll, Terminal};
+
+/// Returns the text of `row`, without the blanks at its end.
+fn line(terminal: &Terminal, row: usize) -> String {
+    let line: String = (0..terminal.columns())
+        .map(|column| terminal.cell(row, column).character)
+        .collect();
+    String::from(line.trim_end())
+}
+
+#[test_case]
+fn test_parse_csi() {
+    let mut parser = Parser::new();
+    let actions: alloc::vec::Vec<_> = "a\x1b[12;;3H\x1b[?25l"
+        .chars()
+        .filter_map(|c| parser.advance(c))
+        .collect();
+
+    assert_eq!(actions[0], Action::Print('a'));
+    let Action::Csi(position) = actions[1] else {
+        panic!("not a control sequence: {:?}", actions[1]);
+    };
+    assert_eq!(position.parameters(), [12, 0, 3]);
+    assert_eq!(position.parameter(1, 1), 1);
+    assert_eq!(position.final_byte, b'H');
+    let Action::Csi(mode) = actions[2] else {
+        panic!("not a control sequence: {:?}", actions[2]);
+    };
+    assert_eq!(mode.private, Some(b'?'));
+    assert_eq!(mode.parameters(), [25]);
+}
+
+#[test_case]
+fn test_wrap_and_scroll() {
+    let mut terminal = Terminal::new(4, 2);
+    terminal.write("abcdef\nghi");
+
+    assert_eq!(line(&terminal, 0), "ef");
+    assert_eq!(line(&terminal, 1), "ghi");
+    assert_eq!(terminal.scrollback_len(), 1);
+
+    terminal.scroll_back(5);
+    assert_eq!(line(&terminal, 0), "abcd");
+    assert_eq!(line(&terminal, 1), "ef");
+    assert_eq!(terminal.cursor(), None);
+
+    // writing shows the screen again
+    terminal.write("j");
+    assert_eq!(line(&terminal, 1), "ghij");
+}
+
+#[test_case]
+fn test_scrollback_styles() {
+    let mut terminal = Terminal::new(8, 1);
+    terminal.write("a\x1b[31mb\n\x1b[44m \x1b[0m\nc");
+    terminal.scroll_back(2);
+
+    assert_eq!(line(&terminal, 0), "ab");
+    assert_eq!(terminal.cell(0, 0).style, Default::default());
+    assert_eq!(terminal.cell(0, 1).style.foreground, Color::Indexed(1));
+    // a blank with a background colour is kept, unlike the blanks after it
+    assert_eq!(terminal.cell(1, 0).style.background, Color::Indexed(4));
+    assert_eq!(terminal.cell(1, 1).style, Default::default());
+}
+
+#[test_case]
+fn test_memory_footprint() {
+    // the screen of a 1280x800 framebuffer with the default font
+    let mut terminal = Terminal::new(182, 44);
+    let screen = terminal.footprint();
+    assert!(screen <= 182 * 44 * 16 + 44 * 24);
+
+    for line in 0..5000 {
+        terminal.write(&format!("\x1b[3{}mline {line}: {:100}\n", line % 8, ""));
+    }
+    assert!(terminal.scrollback_len() > 100);
+    assert!(terminal.footprint() <= screen + SCROLLBACK_BYTES);
+
+    // the oldest lines were dropped, but the kept ones are intact
+    terminal.scroll_back(terminal.scrollback_len());
+    let last = 5000 - 44;
+    let first = last + 1 - terminal.scrollback_len();
+    assert_eq!(line(&terminal, 0), format!("line {first}:"));
+    assert_eq!(
+        terminal.cell(0, 0).style.foreground,
+        Color::Indexed(first as u8 % 8)
+    );
+}
+
+#[test_case]
+fn test_backspace_and_tabs() {
+    let mut terminal = Terminal::new(20, 2);
+    terminal.write("ab\tc\x08d");
+    assert_eq!(line(&terminal, 0), "ab      d");
+
+    terminal.write("\r\nx\x08\x08y");
+    // backspace at the start of a line goes to the end of the previous one
+    assert_eq!(terminal.cell(0, 19).character, 'y');
+}
+
+#[test_case]
+fn test_cursor_and_erase() {
+    let mut terminal = Terminal::new(10, 3);
+    terminal.write("0123456789\x1b[2;3Habc\x1b[1;5H\x1b[K\x1b[3;1Hxyz\x1b[2D\x1b[1P");
+
+    assert_eq!(line(&terminal, 0), "0123");
+    assert_eq!(line(&terminal, 1), "  abc");
+    assert_eq!(line(&terminal, 2), "xz");
+    assert_eq!(terminal.cursor(), Some((2, 1)));
+
+    terminal.write("\x1b[2J");
+    assert_eq!(line(&terminal, 1), "");
+}
+
+#[test_case]
+fn test_graphic_rendition() {
+    let mut terminal = Terminal::new(10, 1);
+    terminal.write("\x1b[1;31;44ma\x1b[38;5;200;48;2;1;2;3mb\x1b[0mc");
+
+    let a = terminal.cell(0, 0).style;
+    assert_eq!(a.attributes, Attributes::BOLD);
+    assert_eq!(a.foreground, Color::Indexed(1));
+    assert_eq!(a.background, Color::Indexed(4));
+
+    let b = terminal.cell(0, 1).style;
+    assert_eq!(b.foreground, Color::Indexed(200));
+    assert_eq!(b.background, Color::Rgb(1, 2, 3));
+
+    assert_eq!(terminal.cell(0, 2).style, Default::default());
+}
+
+#[test_case]
+fn test_scroll_region() {
+    let mut terminal = Terminal::new(4, 4);
+    terminal.write("a\nb\nc\nd\x1b[2;3r\x1b[3;1H\n");
+
+    assert_eq!(line(&terminal, 0), "a");
+    assert_eq!(line(&terminal, 1), "c");
+    assert_eq!(line(&terminal, 2), "");
+    assert_eq!(line(&terminal, 3), "d");
+    // the lines scrolled out of a region are not kept
+    assert_eq!(terminal.scrollback_len(), 0);
+}
+
+#[test_case]
+fn test_damage() {
+    let mut terminal = Terminal::new(8, 2);
+    for row in 0..2 {
+        assert_eq!(terminal.take_damage(row), Some(0..8));
+    }
+
+    terminal.write("\x1b[2;3Hab");
+    assert_eq!(terminal.take_damage(0), None);
+    assert_eq!(terminal.take_damage(1), Some(2..4));
+    assert_eq!(terminal.take_damage(1), None);
+}
+
+#[test_case]
+fn test_scroll_moves_damage() {
+    let mut terminal = Terminal::new(8, 3);
+    for row in 0..3 {
+        terminal.take_damage(row);
+    }
+
+    terminal.write("\x1b[3;3Hab\nc");
+    assert_eq!(
+        terminal.take_scroll(),
+        Some(Scroll {
+            rows: 0..3,
+            lines: 1
+        })
+    );
+    assert_eq!(terminal.take_scroll(), None);
+    // the written cells moved up, and the new row is blank
+    assert_eq!(terminal.take_damage(0), None);
+    assert_eq!(terminal.take_damage(1), Some(2..4));
+    assert_eq!(terminal.take_damage(2), Some(0..8));
+
+    // moves of different regions are drawn again
+    terminal.write("\x1b[2;3r\x1b[3;1H\n\x1b[r\x1b[3;1H\n");
+    assert_eq!(terminal.take_scroll(), None);
+    for row in 0..3 {
+        assert_eq!(terminal.take_damage(row), Some(0..8));
+    }
+}
+
+#[test_case]
+fn test_resize() {
+    let mut terminal = Terminal::new(4, 3);
+    terminal.write("ab\ncd\nef");
+    terminal.resize(2, 2);
+
+    // the line of the cursor stays on the screen
+    assert_eq!(line(&terminal, 0), "cd");
+    assert_eq!(line(&terminal, 1), "ef");
+    assert_eq!(terminal.scrollback_len(), 1);
+    assert_eq!(terminal.cursor(), Some((1, 1)));
+
+    terminal.resize(6, 3);
+    assert_eq!(terminal.columns(), 6);
+    assert_eq!(line(&terminal, 2), "");
+    for row in 0..3 {
+        assert_eq!(terminal.take_damage(row), Some(0..6));
+    }
+}
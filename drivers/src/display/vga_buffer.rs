@@ -1,9 +1,13 @@
 mod char;
 mod color;
+mod console;
 mod constants;
+mod init;
 pub mod macros;
 mod writer;
 
+pub use console::VgaConsole;
+pub use init::init;
 pub use writer::WRITER;
 
 #[cfg(test)]
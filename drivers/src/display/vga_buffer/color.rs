@@ -20,6 +20,32 @@ pub enum Color {
     White = 15,
 }
 
+impl Color {
+    /// Returns the colour with the given index among the 16 ANSI colours, which are ordered
+    /// differently from the VGA ones.
+    pub fn from_ansi(index: u16) -> Color {
+        const COLORS: [Color; 16] = [
+            Color::Black,
+            Color::Red,
+            Color::Green,
+            Color::Brown,
+            Color::Blue,
+            Color::Magenta,
+            Color::Cyan,
+            Color::LightGray,
+            Color::DarkGray,
+            Color::LightRed,
+            Color::LightGreen,
+            Color::Yellow,
+            Color::LightBlue,
+            Color::Pink,
+            Color::LightCyan,
+            Color::White,
+        ];
+        COLORS[usize::from(index) % COLORS.len()]
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct ColorCode(u8);
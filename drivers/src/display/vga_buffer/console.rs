@@ -0,0 +1,16 @@
+use super::WRITER;
+use crate::console::Console;
+
+/// Shows the text on the screen in VGA text mode.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VgaConsole;
+
+impl Console for VgaConsole {
+    fn name(&self) -> &'static str {
+        "vga"
+    }
+
+    fn write_str(&self, s: &str) {
+        WRITER.lock().write_string(s);
+    }
+}
@@ -1,2 +1,4 @@
+/// Physical address of the buffer of the VGA text mode.
+pub const BUFFER_ADDRESS: u64 = 0xb8000;
 pub const BUFFER_HEIGHT: usize = 25;
 pub const BUFFER_WIDTH: usize = 80;
@@ -0,0 +1,16 @@
+use x86_64::VirtAddr;
+
+use super::{WRITER, console::VgaConsole, constants::BUFFER_ADDRESS, writer::Buffer};
+use crate::console;
+
+/// Shows the text printed by the kernel in VGA text mode, for when there is no framebuffer.
+///
+/// # Safety
+///
+/// `physical_memory_offset` must be the address where the whole physical memory is mapped.
+pub unsafe fn init(physical_memory_offset: VirtAddr) {
+    let buffer = physical_memory_offset + BUFFER_ADDRESS;
+    WRITER.lock().buffer = unsafe { &mut *buffer.as_mut_ptr::<Buffer>() };
+
+    console::register(&VgaConsole).expect("VGA console registration failed");
+}
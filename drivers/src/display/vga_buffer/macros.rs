@@ -1,15 +1,11 @@
 use core::fmt;
 use core::fmt::Write;
 
-use x86_64::instructions::interrupts;
-
 use super::WRITER;
 
 #[doc(hidden)]
 pub fn _print(args: fmt::Arguments) {
-    interrupts::without_interrupts(|| {
-        WRITER.lock().write_fmt(args).unwrap();
-    });
+    WRITER.lock().write_fmt(args).unwrap();
 }
 
 #[macro_export]
@@ -1,12 +1,16 @@
 use core::fmt;
 
 use lazy_static::lazy_static;
-use spin::Mutex;
+use utils::sync::IrqSpinlock;
 use volatile::Volatile;
 
 use super::char::ScreenChar;
 use super::color::{Color, ColorCode};
-use super::constants::{BUFFER_HEIGHT, BUFFER_WIDTH};
+use super::constants::{BUFFER_ADDRESS, BUFFER_HEIGHT, BUFFER_WIDTH};
+use crate::display::terminal::{Action, Csi, Parser};
+
+const DEFAULT_FOREGROUND: Color = Color::White;
+const DEFAULT_BACKGROUND: Color = Color::Black;
 
 #[repr(transparent)]
 pub struct Buffer {
@@ -15,7 +19,11 @@ pub struct Buffer {
 
 pub struct Writer {
     column_position: usize,
+    foreground: Color,
+    background: Color,
     color_code: ColorCode,
+    /// Strips the escape sequences, of which only the colours are shown
+    parser: Parser,
     pub buffer: &'static mut Buffer,
 }
 
@@ -39,14 +47,44 @@ impl Writer {
     }
 
     pub fn write_string(&mut self, s: &str) {
-        for byte in s.bytes() {
-            match byte {
-                // printable ASCII byte or newline
-                0x20..=0x7e | b'\n' => self.write_byte(byte),
+        for c in s.chars() {
+            match self.parser.advance(c) {
+                // printable ASCII char
+                Some(Action::Print(c @ ' '..='~')) => self.write_byte(c as u8),
                 // not part of printable ASCII range
-                _ => self.write_byte(0xfe),
+                Some(Action::Print(_)) => self.write_byte(0xfe),
+                Some(Action::Execute('\n')) => self.new_line(),
+                Some(Action::Execute('\r')) => self.column_position = 0,
+                Some(Action::Execute('\x08')) => {
+                    self.column_position = self.column_position.saturating_sub(1)
+                }
+                Some(Action::Csi(csi)) if csi.final_byte == b'm' && csi.private.is_none() => {
+                    self.select_graphic_rendition(&csi)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Sets the colours of the next chars, other attributes can't be shown in text mode.
+    fn select_graphic_rendition(&mut self, csi: &Csi) {
+        let parameters = match csi.parameters() {
+            [] => &[0],
+            parameters => parameters,
+        };
+        for &parameter in parameters {
+            match parameter {
+                0 => (self.foreground, self.background) = (DEFAULT_FOREGROUND, DEFAULT_BACKGROUND),
+                30..=37 => self.foreground = Color::from_ansi(parameter - 30),
+                39 => self.foreground = DEFAULT_FOREGROUND,
+                40..=47 => self.background = Color::from_ansi(parameter - 40),
+                49 => self.background = DEFAULT_BACKGROUND,
+                90..=97 => self.foreground = Color::from_ansi(parameter - 90 + 8),
+                100..=107 => self.background = Color::from_ansi(parameter - 100 + 8),
+                _ => {}
             }
         }
+        self.color_code = ColorCode::new(self.foreground, self.background);
     }
 
     fn new_line(&mut self) {
@@ -76,9 +114,15 @@ impl fmt::Write for Writer {
 }
 
 lazy_static! {
-    pub static ref WRITER: Mutex<Writer> = Mutex::new(Writer {
-        column_position: 0,
-        color_code: ColorCode::new(Color::White, Color::Black),
-        buffer: unsafe { &mut *(0xb8000 as *mut Buffer) },
-    });
+    pub static ref WRITER: IrqSpinlock<Writer> = IrqSpinlock::new(
+        "VGA WRITER",
+        Writer {
+            column_position: 0,
+            foreground: DEFAULT_FOREGROUND,
+            background: DEFAULT_BACKGROUND,
+            color_code: ColorCode::new(DEFAULT_FOREGROUND, DEFAULT_BACKGROUND),
+            parser: Parser::new(),
+            buffer: unsafe { &mut *(BUFFER_ADDRESS as *mut Buffer) },
+        }
+    );
 }
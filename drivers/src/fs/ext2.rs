@@ -2,6 +2,7 @@ mod structs;
 mod inner;
 
 pub use inner::Ext2;
+pub use structs::inode::{Inode, Permissions, Type};
 
 #[cfg(test)]
 mod tests;
@@ -1,3 +1,6 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use utils::posix::path::PathBuf;
 use x86_64::VirtAddr;
 
@@ -59,4 +62,73 @@ impl FileSystem for Ext2 {
         // the path, so we return `None`.
         path_iter.next().is_none().then_some(current_inode)
     }
+
+    fn contents(&self, file: &Self::File) -> Option<Vec<u8>> {
+        let Type::File = file.file_type() else {
+            return None;
+        };
+
+        // The size comes from the disk, so the contents grow with the blocks actually read
+        // rather than being allocated up front.
+        let size = file.size() as usize;
+        let mut contents = Vec::new();
+
+        // Holes of sparse files are not supported, as the iterator skips empty block pointers.
+        for block_pointer in file.block_pointers.iter(self.superblock) {
+            if contents.len() == size {
+                break;
+            }
+
+            let block = self.superblock.block(*block_pointer)?;
+            let remaining = size - contents.len();
+            contents.extend_from_slice(&block[..remaining.min(block.len())]);
+        }
+
+        (contents.len() == size).then_some(contents)
+    }
+
+    fn read_at(&self, file: &Self::File, offset: usize, buffer: &mut [u8]) -> Option<usize> {
+        let Type::File = file.file_type() else {
+            return None;
+        };
+
+        let end = (file.size() as usize).min(offset.saturating_add(buffer.len()));
+        if offset >= end {
+            return Some(0);
+        }
+
+        // Only the blocks that hold the bytes are read, the file is never copied whole.
+        let block_size = self.superblock.block_size() as usize;
+        let mut position = offset;
+        let block_pointers = file.block_pointers.iter(self.superblock);
+        for (index, block_pointer) in block_pointers.enumerate().skip(offset / block_size) {
+            if position == end {
+                break;
+            }
+
+            let block = self.superblock.block(*block_pointer)?;
+            let start = position - index * block_size;
+            let count = (block.len() - start).min(end - position);
+            buffer[position - offset..][..count].copy_from_slice(&block[start..start + count]);
+            position += count;
+        }
+
+        (position == end).then_some(position - offset)
+    }
+
+    fn entries<'a>(&'a self, directory: &'a Self::File) -> Option<Vec<(String, &'a Self::File)>> {
+        let entries = self.read_directory(directory)?;
+
+        // Entries with inode 0 are unused.
+        let entries = entries
+            .filter(|entry| entry.inode != 0)
+            .map(|entry| {
+                (
+                    entry.name().into_owned(),
+                    self.superblock.inode(entry.inode),
+                )
+            })
+            .collect();
+        Some(entries)
+    }
 }
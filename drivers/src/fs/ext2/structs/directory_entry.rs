@@ -56,9 +56,8 @@ pub enum DirectoryEntryType {
 
 impl DirectoryEntry {
     pub fn name(&self) -> Cow<str> {
-        let start_ptr = self as *const Self;
-        let ptr_offset = size_of::<Self>();
-        let name_ptr = unsafe { start_ptr.add(ptr_offset) as *const u8 };
+        let start_ptr = self as *const Self as *const u8;
+        let name_ptr = unsafe { start_ptr.add(size_of::<Self>()) };
         let name_bytes = unsafe { slice::from_raw_parts(name_ptr, self.name_length_low as usize) };
         let (name, _) = WINDOWS_1252.decode_without_bom_handling(name_bytes);
 
@@ -66,9 +65,9 @@ impl DirectoryEntry {
     }
 
     fn next_ptr(&self) -> *const DirectoryEntry {
-        let start_ptr = self as *const Self;
+        let start_ptr = self as *const Self as *const u8;
 
-        unsafe { start_ptr.add(self.size as usize) }
+        unsafe { start_ptr.add(self.size as usize) as *const DirectoryEntry }
     }
 }
 
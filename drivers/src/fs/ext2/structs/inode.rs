@@ -190,4 +190,13 @@ impl Inode {
         let type_permissions = self.type_permissions;
         type_permissions.split().1
     }
+
+    /// Size of the contents in bytes, the upper 32 bits are only used by regular files
+    pub fn size(&self) -> u64 {
+        let size_low = self.size_low as u64;
+        match self.file_type() {
+            Type::File => size_low | ((self.size_upper_or_directory_acl as u64) << 32),
+            _ => size_low,
+        }
+    }
 }
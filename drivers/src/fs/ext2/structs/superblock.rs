@@ -306,10 +306,11 @@ impl SuperBlock {
             return None;
         }
 
-        let start_ptr = self as *const Self;
-        let block_offset = block_number - self.block_number;
-        let ptr_offset = block_offset * self.block_size();
-        let block_ptr = unsafe { start_ptr.add(ptr_offset as usize) as *const u8 };
+        // The superblock is always `OFFSET` bytes after the start of the volume
+        let volume_start_ptr =
+            unsafe { (self as *const Self as *const u8).sub(Self::OFFSET as usize) };
+        let ptr_offset = block_number as usize * self.block_size() as usize;
+        let block_ptr = unsafe { volume_start_ptr.add(ptr_offset) };
 
         Some(block_ptr)
     }
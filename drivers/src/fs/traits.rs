@@ -1,3 +1,6 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use utils::posix::path::PathBuf;
 
 pub trait FileSystem {
@@ -8,4 +11,15 @@ pub trait FileSystem {
         path: PathBuf,
         current_directory: Option<&'a Self::File>,
     ) -> Option<&'a Self::File>;
+
+    /// Returns the contents of `file`, or [`None`] if it is not a regular file
+    fn contents(&self, file: &Self::File) -> Option<Vec<u8>>;
+
+    /// Copies the bytes of `file` at `offset` into `buffer`, and returns their number, which is
+    /// 0 at the end of the file, or [`None`] if it is not a regular file
+    fn read_at(&self, file: &Self::File, offset: usize, buffer: &mut [u8]) -> Option<usize>;
+
+    /// Returns the names and files of the entries of `directory`, or [`None`] if it is not a
+    /// directory
+    fn entries<'a>(&'a self, directory: &'a Self::File) -> Option<Vec<(String, &'a Self::File)>>;
 }
@@ -6,5 +6,7 @@
 
 extern crate alloc;
 
+pub mod console;
 pub mod display;
 pub mod fs;
+pub mod serial;
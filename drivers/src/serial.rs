@@ -0,0 +1,9 @@
+mod inner;
+mod tty;
+
+pub use inner::{COM1, COM2, Serial, port};
+pub use tty::{LineDiscipline, Mode};
+pub use utils::serial::{ComPort, DEFAULT_BAUD_RATE, Error, SerialPort};
+
+#[cfg(test)]
+mod tests;
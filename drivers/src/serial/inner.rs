@@ -0,0 +1,99 @@
+use utils::serial::{self, ComPort, Error, SerialPort};
+use utils::sync::IrqSpinlock;
+
+use super::tty::{LineDiscipline, Mode};
+
+/// Maximum number of bytes moved from the UART to the line discipline by an interrupt, in case
+/// the UART keeps reporting data.
+const MAX_RECEIVED: usize = 64;
+
+pub static COM1: IrqSpinlock<Serial> = IrqSpinlock::new("COM1", Serial::new(ComPort::Com1));
+pub static COM2: IrqSpinlock<Serial> = IrqSpinlock::new("COM2", Serial::new(ComPort::Com2));
+
+/// Returns the serial terminal of `port`.
+pub fn port(port: ComPort) -> &'static IrqSpinlock<Serial> {
+    match port {
+        ComPort::Com1 => &COM1,
+        ComPort::Com2 => &COM2,
+    }
+}
+
+/// A terminal on a serial port, whose received bytes go through a line discipline.
+///
+/// The UART is shared with the output of the tests, see [`serial::uart`], and is locked after
+/// the terminal.
+#[derive(Debug)]
+pub struct Serial {
+    port: ComPort,
+    tty: LineDiscipline,
+}
+
+impl Serial {
+    pub const fn new(port: ComPort) -> Self {
+        Self {
+            port,
+            tty: LineDiscipline::new(),
+        }
+    }
+
+    /// Sets up the UART at `baud_rate`, see [`SerialPort::init`].
+    pub fn init(&mut self, baud_rate: u32) -> Result<(), Error> {
+        serial::uart(self.port).lock().init(baud_rate)
+    }
+
+    /// Returns whether the UART passed its test, nothing is sent nor received otherwise.
+    pub fn is_ready(&self) -> bool {
+        serial::uart(self.port).lock().is_ready()
+    }
+
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Error> {
+        serial::uart(self.port).lock().set_baud_rate(baud_rate)
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.tty.mode()
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.tty.set_mode(mode);
+    }
+
+    /// Sends `bytes`, with a carriage return before every line feed for the terminal to go to
+    /// the start of the line.
+    pub fn write(&mut self, bytes: &[u8]) {
+        let mut uart = serial::uart(self.port).lock();
+        if uart.is_ready() {
+            send(&mut uart, bytes);
+        }
+    }
+
+    /// Moves as many received bytes as fit into `buffer`, and returns their number.
+    pub fn read(&mut self, buffer: &mut [u8]) -> usize {
+        self.tty.read(buffer)
+    }
+
+    /// Passes the bytes that the UART received to the line discipline, which must be done when
+    /// it raises its IRQ.
+    pub fn handle_interrupt(&mut self) {
+        let mut uart = serial::uart(self.port).lock();
+        if !uart.is_ready() {
+            return;
+        }
+
+        for _ in 0..MAX_RECEIVED {
+            let Some(byte) = uart.receive() else {
+                break;
+            };
+            self.tty.receive(byte, &mut |echo| send(&mut uart, echo));
+        }
+    }
+}
+
+fn send(port: &mut SerialPort, bytes: &[u8]) {
+    for &byte in bytes {
+        if byte == b'\n' {
+            port.send(b'\r');
+        }
+        port.send(byte);
+    }
+}
@@ -0,0 +1,46 @@
+use alloc::vec::Vec;
+
+use super::{LineDiscipline, Mode};
+
+/// Passes `bytes` to `tty`, and returns what it echoes.
+fn receive(tty: &mut LineDiscipline, bytes: &[u8]) -> Vec<u8> {
+    let mut echoed = Vec::new();
+    for &byte in bytes {
+        tty.receive(byte, &mut |echo| echoed.extend_from_slice(echo));
+    }
+    echoed
+}
+
+fn read_all(tty: &mut LineDiscipline) -> Vec<u8> {
+    let mut buffer = [0; 64];
+    let count = tty.read(&mut buffer);
+    buffer[..count].to_vec()
+}
+
+#[test_case]
+fn test_canonical_mode() {
+    let mut tty = LineDiscipline::new();
+
+    assert_eq!(receive(&mut tty, b"lx\x7fs"), b"lx\x08 \x08s");
+    // nothing can be read before the end of the line
+    assert_eq!(tty.available(), 0);
+    assert_eq!(receive(&mut tty, b" -l\r"), b" -l\n");
+    assert_eq!(read_all(&mut tty), b"ls -l\n");
+
+    receive(&mut tty, b"cat /boot\x17/etc\n");
+    assert_eq!(read_all(&mut tty), b"cat /etc\n");
+
+    receive(&mut tty, "é\x08a\x15b\x03c\x04".as_bytes());
+    assert_eq!(read_all(&mut tty), b"c");
+}
+
+#[test_case]
+fn test_raw_mode() {
+    let mut tty = LineDiscipline::new();
+    receive(&mut tty, b"ab");
+
+    // the line being edited can be read in raw mode
+    tty.set_mode(Mode::Raw);
+    assert_eq!(receive(&mut tty, b"\x7f\r\x1b[A"), b"");
+    assert_eq!(read_all(&mut tty), b"ab\x7f\r\x1b[A");
+}
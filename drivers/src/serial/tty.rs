@@ -0,0 +1,145 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// Maximum number of bytes received and not read yet, the next ones are dropped.
+const INPUT_SIZE: usize = 1024;
+/// Maximum length of a line edited in canonical mode.
+const LINE_SIZE: usize = 256;
+
+/// Control chars of the canonical mode.
+const INTERRUPT: u8 = 0x03;
+const END_OF_FILE: u8 = 0x04;
+const BACKSPACE: u8 = 0x08;
+const KILL: u8 = 0x15;
+const WORD_ERASE: u8 = 0x17;
+const DELETE: u8 = 0x7f;
+
+/// How the bytes received by a terminal are given to its reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// The line is edited and echoed by the terminal, and only read once it is complete
+    #[default]
+    Canonical,
+    /// The bytes are read as they are received, without echo, for readers that edit the line
+    /// themselves
+    Raw,
+}
+
+/// Turns the bytes received by a serial terminal into its input, like the line discipline of
+/// a Unix TTY.
+///
+/// In canonical mode a carriage return ends the line like a line feed, Backspace and Delete
+/// erase a char, ^W a word and ^U the line, ^C discards the line and ^D sends it without a
+/// line feed.
+#[derive(Debug, Clone)]
+pub struct LineDiscipline {
+    mode: Mode,
+    /// Line being edited in canonical mode
+    line: Vec<u8>,
+    /// Bytes ready to be read
+    input: VecDeque<u8>,
+}
+
+impl LineDiscipline {
+    pub const fn new() -> Self {
+        Self {
+            mode: Mode::Canonical,
+            line: Vec::new(),
+            input: VecDeque::new(),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Switches to `mode`, the line being edited can be read once in raw mode.
+    pub fn set_mode(&mut self, mode: Mode) {
+        if mode == Mode::Raw {
+            self.send_line();
+        }
+        self.mode = mode;
+    }
+
+    /// Returns the number of bytes that can be read.
+    pub fn available(&self) -> usize {
+        self.input.len()
+    }
+
+    /// Moves as many bytes of the input as fit into `buffer`, and returns their number.
+    pub fn read(&mut self, buffer: &mut [u8]) -> usize {
+        let count = buffer.len().min(self.input.len());
+        for (byte, received) in buffer.iter_mut().zip(self.input.drain(..count)) {
+            *byte = received;
+        }
+        count
+    }
+
+    /// Handles `byte` received from the terminal, and passes what it echoes to `echo`.
+    pub fn receive(&mut self, byte: u8, echo: &mut impl FnMut(&[u8])) {
+        if self.mode == Mode::Raw {
+            if self.input.len() < INPUT_SIZE {
+                self.input.push_back(byte);
+            }
+            return;
+        }
+
+        match byte {
+            b'\r' | b'\n' => {
+                self.line.push(b'\n');
+                self.send_line();
+                echo(b"\n");
+            }
+            BACKSPACE | DELETE => {
+                if self.erase_char() {
+                    echo(b"\x08 \x08");
+                }
+            }
+            WORD_ERASE => {
+                while self.line.last() == Some(&b' ') && self.erase_char() {
+                    echo(b"\x08 \x08");
+                }
+                while self.line.last().is_some_and(|&byte| byte != b' ') && self.erase_char() {
+                    echo(b"\x08 \x08");
+                }
+            }
+            KILL => {
+                while self.erase_char() {
+                    echo(b"\x08 \x08");
+                }
+            }
+            INTERRUPT => {
+                self.line.clear();
+                echo(b"^C\n");
+            }
+            END_OF_FILE => self.send_line(),
+            byte if self.line.len() < LINE_SIZE - 1 => {
+                self.line.push(byte);
+                echo(&[byte]);
+            }
+            // The line is full, only its end can be typed
+            _ => {}
+        }
+    }
+
+    /// Erases the last char of the line, and returns whether there was one.
+    fn erase_char(&mut self) -> bool {
+        // The continuation bytes of UTF-8 start with 0b10
+        while self.line.pop_if(|byte| *byte & 0xc0 == 0x80).is_some() {}
+        self.line.pop().is_some()
+    }
+
+    /// Makes the line being edited readable, unless there is no room for it.
+    fn send_line(&mut self) {
+        if self.input.len() + self.line.len() <= INPUT_SIZE {
+            self.input.extend(&self.line);
+        }
+        self.line.clear();
+    }
+}
+
+impl Default for LineDiscipline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
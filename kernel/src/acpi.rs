@@ -0,0 +1,14 @@
+pub mod address;
+pub mod aml;
+pub mod fadt;
+pub mod hpet;
+mod inner;
+pub mod madt;
+pub mod mcfg;
+pub mod rsdp;
+pub mod sdt;
+
+pub use inner::{find_table, init, tables};
+
+#[cfg(test)]
+mod tests;
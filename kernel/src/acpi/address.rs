@@ -0,0 +1,139 @@
+use x86_64::instructions::port::Port;
+use x86_64::PhysAddr;
+
+use crate::memory::global::phys_to_virt;
+
+/// Generic Address Structure, which locates a register in one of the address spaces.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct GenericAddress {
+    /// Address space of the register, see [`AddressSpace`]
+    ///
+    /// - Byte 0
+    pub address_space: u8,
+    /// Size of the register in bits
+    ///
+    /// - Byte 1
+    pub bit_width: u8,
+    /// Offset of the register in bits
+    ///
+    /// - Byte 2
+    pub bit_offset: u8,
+    /// 0 for legacy reasons, 1 to 4 for byte, word, dword and qword accesses
+    ///
+    /// - Byte 3
+    pub access_size: u8,
+    /// Address of the register in its address space
+    ///
+    /// - Bytes 4-11
+    pub address: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressSpace {
+    SystemMemory,
+    SystemIo,
+    PciConfiguration,
+    EmbeddedController,
+    SmBus,
+    /// A space that is not supported yet
+    Other(u8),
+}
+
+impl GenericAddress {
+    pub fn address_space(&self) -> AddressSpace {
+        match self.address_space {
+            0 => AddressSpace::SystemMemory,
+            1 => AddressSpace::SystemIo,
+            2 => AddressSpace::PciConfiguration,
+            3 => AddressSpace::EmbeddedController,
+            4 => AddressSpace::SmBus,
+            other => AddressSpace::Other(other),
+        }
+    }
+
+    /// Returns `true` if the structure does not describe any register.
+    pub fn is_null(&self) -> bool {
+        self.address == 0
+    }
+
+    /// Size of the accesses to the register, in bits.
+    pub fn access_width(&self) -> u8 {
+        match self.access_size {
+            1..=4 => 8 << (self.access_size - 1),
+            // legacy structures only give the size of the register
+            _ => self.bit_width.clamp(8, 64).next_power_of_two(),
+        }
+    }
+
+    /// Reads the register, or returns `None` if its address space is not supported.
+    ///
+    /// # Safety
+    ///
+    /// The structure must describe a register that can be read without side effects on memory
+    /// safety.
+    pub unsafe fn read(&self) -> Option<u64> {
+        let address = self.address;
+
+        let value = match (self.address_space(), self.access_width()) {
+            (AddressSpace::SystemIo, 8) => unsafe { Port::<u8>::new(address as u16).read() as u64 },
+            (AddressSpace::SystemIo, 16) => unsafe {
+                Port::<u16>::new(address as u16).read() as u64
+            },
+            (AddressSpace::SystemIo, _) => unsafe {
+                Port::<u32>::new(address as u16).read() as u64
+            },
+            (AddressSpace::SystemMemory, width) => {
+                let ptr = phys_to_virt(PhysAddr::new(address)).as_ptr::<u8>();
+                unsafe {
+                    match width {
+                        8 => ptr.read_volatile() as u64,
+                        16 => (ptr as *const u16).read_volatile() as u64,
+                        32 => (ptr as *const u32).read_volatile() as u64,
+                        _ => (ptr as *const u64).read_volatile(),
+                    }
+                }
+            }
+            _ => return None,
+        };
+
+        Some(value >> self.bit_offset)
+    }
+
+    /// Writes `value` to the register, or returns `None` if its address space is not supported.
+    ///
+    /// # Safety
+    ///
+    /// The structure must describe a register that can be written without side effects on memory
+    /// safety.
+    pub unsafe fn write(&self, value: u64) -> Option<()> {
+        let address = self.address;
+        let value = value << self.bit_offset;
+
+        match (self.address_space(), self.access_width()) {
+            (AddressSpace::SystemIo, 8) => unsafe {
+                Port::<u8>::new(address as u16).write(value as u8)
+            },
+            (AddressSpace::SystemIo, 16) => unsafe {
+                Port::<u16>::new(address as u16).write(value as u16)
+            },
+            (AddressSpace::SystemIo, _) => unsafe {
+                Port::<u32>::new(address as u16).write(value as u32)
+            },
+            (AddressSpace::SystemMemory, width) => {
+                let ptr = phys_to_virt(PhysAddr::new(address)).as_mut_ptr::<u8>();
+                unsafe {
+                    match width {
+                        8 => ptr.write_volatile(value as u8),
+                        16 => (ptr as *mut u16).write_volatile(value as u16),
+                        32 => (ptr as *mut u32).write_volatile(value as u32),
+                        _ => (ptr as *mut u64).write_volatile(value),
+                    }
+                }
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
@@ -0,0 +1,51 @@
+//! Minimal lookups in AML bytecode, without a full interpreter.
+
+const NAME_OP: u8 = 0x08;
+const PACKAGE_OP: u8 = 0x12;
+const ZERO_OP: u8 = 0x00;
+const ONE_OP: u8 = 0x01;
+const BYTE_PREFIX: u8 = 0x0a;
+const ROOT_CHAR: u8 = b'\\';
+
+/// Returns the `SLP_TYPa` and `SLP_TYPb` values of the sleep state package `name`, such as
+/// `_S5_`, defined in `aml`.
+///
+/// Only packages defined with `Name` and whose first elements are integer constants are found.
+pub fn sleep_type(aml: &[u8], name: &[u8; 4]) -> Option<(u8, u8)> {
+    let is_definition = |index: usize| match index {
+        0 => false,
+        1 => aml[0] == NAME_OP,
+        _ => {
+            aml[index - 1] == NAME_OP || (aml[index - 1] == ROOT_CHAR && aml[index - 2] == NAME_OP)
+        }
+    };
+    let position = (0..aml.len().saturating_sub(3))
+        .find(|&index| &aml[index..(index + 4)] == name && is_definition(index))?;
+
+    let mut bytes = aml.get(position + 4..)?.iter().copied();
+    if bytes.next()? != PACKAGE_OP {
+        return None;
+    }
+
+    // the 2 high bits of the first byte tell how many bytes follow it in the package length
+    let lead = bytes.next()?;
+    for _ in 0..(lead >> 6) {
+        bytes.next()?;
+    }
+
+    let _element_count = bytes.next()?;
+    let slp_typ_a = integer(&mut bytes)?;
+    let slp_typ_b = integer(&mut bytes)?;
+
+    Some((slp_typ_a, slp_typ_b))
+}
+
+/// Decodes an integer constant of at most one byte.
+fn integer(bytes: &mut impl Iterator<Item = u8>) -> Option<u8> {
+    match bytes.next()? {
+        ZERO_OP => Some(0),
+        ONE_OP => Some(1),
+        BYTE_PREFIX => bytes.next(),
+        _ => None,
+    }
+}
@@ -0,0 +1,360 @@
+use bitflags::bitflags;
+
+use super::address::GenericAddress;
+use super::sdt::{AcpiTable, SdtHeader};
+
+/// Fixed ACPI Description Table, which describes the fixed power management hardware.
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct Fadt {
+    /// Header, with the signature `"FACP"`
+    ///
+    /// - Bytes 0-35
+    pub header: SdtHeader,
+    /// Physical address of the FACS
+    ///
+    /// - Bytes 36-39
+    pub firmware_ctrl: u32,
+    /// Physical address of the DSDT
+    ///
+    /// - Bytes 40-43
+    pub dsdt: u32,
+    /// Reserved, used by ACPI 1.0 to select the interrupt model
+    ///
+    /// - Byte 44
+    _reserved0: u8,
+    /// Preferred power management profile, such as desktop or mobile
+    ///
+    /// - Byte 45
+    pub preferred_pm_profile: u8,
+    /// Interrupt of the System Control Interrupt, in the 8259 mode
+    ///
+    /// - Bytes 46-47
+    pub sci_interrupt: u16,
+    /// I/O port of the SMI command port, 0 if the system has no System Management mode
+    ///
+    /// - Bytes 48-51
+    pub smi_command_port: u32,
+    /// Value to write to `smi_command_port` to give ACPI control of the hardware
+    ///
+    /// - Byte 52
+    pub acpi_enable: u8,
+    /// Value to write to `smi_command_port` to give back control of the hardware
+    ///
+    /// - Byte 53
+    pub acpi_disable: u8,
+    /// Value to write to `smi_command_port` to enter the S4BIOS state
+    ///
+    /// - Byte 54
+    pub s4bios_request: u8,
+    /// Value to write to `smi_command_port` to control the processor performance state
+    ///
+    /// - Byte 55
+    pub pstate_control: u8,
+    /// I/O port of the PM1a event register block
+    ///
+    /// - Bytes 56-59
+    pub pm1a_event_block: u32,
+    /// I/O port of the PM1b event register block, 0 if not supported
+    ///
+    /// - Bytes 60-63
+    pub pm1b_event_block: u32,
+    /// I/O port of the PM1a control register block
+    ///
+    /// - Bytes 64-67
+    pub pm1a_control_block: u32,
+    /// I/O port of the PM1b control register block, 0 if not supported
+    ///
+    /// - Bytes 68-71
+    pub pm1b_control_block: u32,
+    /// I/O port of the PM2 control register block, 0 if not supported
+    ///
+    /// - Bytes 72-75
+    pub pm2_control_block: u32,
+    /// I/O port of the power management timer register block
+    ///
+    /// - Bytes 76-79
+    pub pm_timer_block: u32,
+    /// I/O port of the General Purpose Event 0 register block
+    ///
+    /// - Bytes 80-83
+    pub gpe0_block: u32,
+    /// I/O port of the General Purpose Event 1 register block
+    ///
+    /// - Bytes 84-87
+    pub gpe1_block: u32,
+    /// Length of the PM1 event register blocks
+    ///
+    /// - Byte 88
+    pub pm1_event_length: u8,
+    /// Length of the PM1 control register blocks
+    ///
+    /// - Byte 89
+    pub pm1_control_length: u8,
+    /// Length of the PM2 control register block
+    ///
+    /// - Byte 90
+    pub pm2_control_length: u8,
+    /// Length of the power management timer register block
+    ///
+    /// - Byte 91
+    pub pm_timer_length: u8,
+    /// Length of the General Purpose Event 0 register block
+    ///
+    /// - Byte 92
+    pub gpe0_block_length: u8,
+    /// Length of the General Purpose Event 1 register block
+    ///
+    /// - Byte 93
+    pub gpe1_block_length: u8,
+    /// First General Purpose Event handled by the GPE1 block
+    ///
+    /// - Byte 94
+    pub gpe1_base: u8,
+    /// Value to write to `smi_command_port` to support the C states notifications
+    ///
+    /// - Byte 95
+    pub cstate_control: u8,
+    /// Worst case latency to enter and exit the C2 state, in microseconds
+    ///
+    /// - Bytes 96-97
+    pub p_level2_latency: u16,
+    /// Worst case latency to enter and exit the C3 state, in microseconds
+    ///
+    /// - Bytes 98-99
+    pub p_level3_latency: u16,
+    /// Number of flush strides to read to flush the processor caches
+    ///
+    /// - Bytes 100-101
+    pub flush_size: u16,
+    /// Size of the cache lines to read to flush the processor caches
+    ///
+    /// - Bytes 102-103
+    pub flush_stride: u16,
+    /// Index of the duty cycle setting in the processor control register
+    ///
+    /// - Byte 104
+    pub duty_offset: u8,
+    /// Width of the duty cycle setting in the processor control register
+    ///
+    /// - Byte 105
+    pub duty_width: u8,
+    /// Index of the day of month alarm in the RTC CMOS RAM
+    ///
+    /// - Byte 106
+    pub day_alarm: u8,
+    /// Index of the month of year alarm in the RTC CMOS RAM
+    ///
+    /// - Byte 107
+    pub month_alarm: u8,
+    /// Index of the century in the RTC CMOS RAM
+    ///
+    /// - Byte 108
+    pub century: u8,
+    /// IA-PC boot architecture flags
+    ///
+    /// - Bytes 109-110
+    pub boot_architecture_flags: IaPcBootArchitecture,
+    /// Reserved
+    ///
+    /// - Byte 111
+    _reserved1: u8,
+    /// Fixed feature flags
+    ///
+    /// - Bytes 112-115
+    pub flags: FadtFlags,
+
+    /*******************************************************************
+     * These fields are only present if the table is long enough, see *
+     * `Fadt::has_field`.                                             *
+     *******************************************************************/
+    /// Address of the reset register
+    ///
+    /// - Bytes 116-127
+    pub reset_register: GenericAddress,
+    /// Value to write to the reset register to reset the system
+    ///
+    /// - Byte 128
+    pub reset_value: u8,
+    /// ARM boot architecture flags
+    ///
+    /// - Bytes 129-130
+    pub arm_boot_architecture_flags: u16,
+    /// Minor version of the FADT
+    ///
+    /// - Byte 131
+    pub minor_version: u8,
+    /// 64-bit physical address of the FACS
+    ///
+    /// - Bytes 132-139
+    pub x_firmware_ctrl: u64,
+    /// 64-bit physical address of the DSDT
+    ///
+    /// - Bytes 140-147
+    pub x_dsdt: u64,
+    /// Extended address of the PM1a event register block
+    ///
+    /// - Bytes 148-159
+    pub x_pm1a_event_block: GenericAddress,
+    /// Extended address of the PM1b event register block
+    ///
+    /// - Bytes 160-171
+    pub x_pm1b_event_block: GenericAddress,
+    /// Extended address of the PM1a control register block
+    ///
+    /// - Bytes 172-183
+    pub x_pm1a_control_block: GenericAddress,
+    /// Extended address of the PM1b control register block
+    ///
+    /// - Bytes 184-195
+    pub x_pm1b_control_block: GenericAddress,
+    /// Extended address of the PM2 control register block
+    ///
+    /// - Bytes 196-207
+    pub x_pm2_control_block: GenericAddress,
+    /// Extended address of the power management timer register block
+    ///
+    /// - Bytes 208-219
+    pub x_pm_timer_block: GenericAddress,
+    /// Extended address of the General Purpose Event 0 register block
+    ///
+    /// - Bytes 220-231
+    pub x_gpe0_block: GenericAddress,
+    /// Extended address of the General Purpose Event 1 register block
+    ///
+    /// - Bytes 232-243
+    pub x_gpe1_block: GenericAddress,
+    /// Address of the sleep control register, on hardware-reduced systems
+    ///
+    /// - Bytes 244-255
+    pub sleep_control_register: GenericAddress,
+    /// Address of the sleep status register, on hardware-reduced systems
+    ///
+    /// - Bytes 256-267
+    pub sleep_status_register: GenericAddress,
+    /// Identity of the hypervisor
+    ///
+    /// - Bytes 268-275
+    pub hypervisor_vendor_id: u64,
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct FadtFlags: u32 {
+        /// The WBINVD instruction flushes the processor caches
+        const WBINVD = 1 << 0;
+        /// The C1 state is supported on every processor
+        const PROC_C1 = 1 << 2;
+        /// The power button is a control method device instead of a fixed feature
+        const PWR_BUTTON = 1 << 4;
+        /// The sleep button is a control method device instead of a fixed feature
+        const SLP_BUTTON = 1 << 5;
+        /// The RTC can wake the system from the S4 state
+        const RTC_S4 = 1 << 7;
+        /// The power management timer is 32 bits wide instead of 24
+        const TMR_VAL_EXT = 1 << 8;
+        /// `Fadt::reset_register` is supported
+        const RESET_REG_SUP = 1 << 10;
+        /// The system has no fixed ACPI hardware, such as the PM1 register blocks
+        const HW_REDUCED_ACPI = 1 << 20;
+        /// The system is as efficient in the S0 idle state as in the S3 state
+        const LOW_POWER_S0_IDLE_CAPABLE = 1 << 21;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct IaPcBootArchitecture: u16 {
+        /// The system has legacy devices, such as a parallel port, on the LPC or ISA bus
+        const LEGACY_DEVICES = 1 << 0;
+        /// The system has a PS/2 compatible 8042 keyboard controller
+        const I8042 = 1 << 1;
+        /// VGA probing is unsafe
+        const VGA_NOT_PRESENT = 1 << 2;
+        /// MSIs must not be enabled
+        const MSI_NOT_SUPPORTED = 1 << 3;
+        /// The PCIe ASPM controls must not be enabled
+        const PCIE_ASPM_CONTROLS = 1 << 4;
+        /// The CMOS RTC is not implemented
+        const CMOS_RTC_NOT_PRESENT = 1 << 5;
+    }
+}
+
+unsafe impl AcpiTable for Fadt {
+    const SIGNATURE: &'static [u8; 4] = b"FACP";
+    const MIN_LENGTH: usize = 116;
+}
+
+/// Returns the offset of the end of `$field` in the FADT.
+macro_rules! field_end {
+    ($field:ident: $ty:ty) => {
+        core::mem::offset_of!(Fadt, $field) + size_of::<$ty>()
+    };
+}
+
+impl Fadt {
+    /// Returns `true` if the table is long enough to hold a field that ends at `end`.
+    fn has_field(&self, end: usize) -> bool {
+        self.header.length as usize >= end
+    }
+
+    /// Physical address of the DSDT, preferably the 64-bit one.
+    pub fn dsdt_address(&self) -> u64 {
+        if self.has_field(field_end!(x_dsdt: u64)) && self.x_dsdt != 0 {
+            self.x_dsdt
+        } else {
+            self.dsdt as u64
+        }
+    }
+
+    /// Address of the PM1a control register block, preferably the extended one.
+    pub fn pm1a_control_block(&self) -> Option<GenericAddress> {
+        self.extended_block(
+            field_end!(x_pm1a_control_block: GenericAddress),
+            self.x_pm1a_control_block,
+        )
+        .or_else(|| Self::io_block(self.pm1a_control_block, self.pm1_control_length))
+    }
+
+    /// Address of the PM1b control register block, preferably the extended one.
+    pub fn pm1b_control_block(&self) -> Option<GenericAddress> {
+        self.extended_block(
+            field_end!(x_pm1b_control_block: GenericAddress),
+            self.x_pm1b_control_block,
+        )
+        .or_else(|| Self::io_block(self.pm1b_control_block, self.pm1_control_length))
+    }
+
+    /// Address of the power management timer register block, preferably the extended one.
+    pub fn pm_timer_block(&self) -> Option<GenericAddress> {
+        self.extended_block(
+            field_end!(x_pm_timer_block: GenericAddress),
+            self.x_pm_timer_block,
+        )
+        .or_else(|| Self::io_block(self.pm_timer_block, self.pm_timer_length))
+    }
+
+    /// Address of the reset register and the value to write to it, if supported.
+    pub fn reset_register(&self) -> Option<(GenericAddress, u8)> {
+        let flags = self.flags;
+        let supported = flags.contains(FadtFlags::RESET_REG_SUP)
+            && self.has_field(field_end!(reset_value: u8))
+            && !{ self.reset_register }.is_null();
+
+        supported.then_some((self.reset_register, self.reset_value))
+    }
+
+    fn extended_block(&self, end: usize, block: GenericAddress) -> Option<GenericAddress> {
+        (self.has_field(end) && !block.is_null()).then_some(block)
+    }
+
+    fn io_block(port: u32, length: u8) -> Option<GenericAddress> {
+        (port != 0).then_some(GenericAddress {
+            address_space: 1,
+            bit_width: length * 8,
+            bit_offset: 0,
+            access_size: 0,
+            address: port as u64,
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use super::address::GenericAddress;
+use super::sdt::{AcpiTable, SdtHeader};
+
+/// High Precision Event Timer description table.
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct Hpet {
+    /// Header, with the signature `"HPET"`
+    ///
+    /// - Bytes 0-35
+    pub header: SdtHeader,
+    /// Copy of the capabilities register of the event timer block
+    ///
+    /// - Bytes 36-39
+    pub event_timer_block_id: u32,
+    /// Address of the event timer block registers, in system memory
+    ///
+    /// - Bytes 40-51
+    pub base_address: GenericAddress,
+    /// Sequence number of the event timer block
+    ///
+    /// - Byte 52
+    pub hpet_number: u8,
+    /// Minimum number of ticks for periodic interrupts without losing any
+    ///
+    /// - Bytes 53-54
+    pub minimum_tick: u16,
+    /// Page protection and OEM attributes
+    ///
+    /// - Byte 55
+    pub page_protection: u8,
+}
+
+unsafe impl AcpiTable for Hpet {
+    const SIGNATURE: &'static [u8; 4] = b"HPET";
+}
+
+impl Hpet {
+    pub fn hardware_revision(&self) -> u8 {
+        self.event_timer_block_id as u8
+    }
+
+    /// Number of comparators, that is of timers, of the event timer block.
+    pub fn comparator_count(&self) -> u8 {
+        ((self.event_timer_block_id >> 8) & 0b1_1111) as u8 + 1
+    }
+
+    /// Returns `true` if the main counter is 64 bits wide.
+    pub fn has_64_bit_counter(&self) -> bool {
+        self.event_timer_block_id & (1 << 13) != 0
+    }
+
+    /// Returns `true` if the HPET can replace the PIT and RTC interrupts.
+    pub fn supports_legacy_replacement(&self) -> bool {
+        self.event_timer_block_id & (1 << 15) != 0
+    }
+
+    pub fn pci_vendor_id(&self) -> u16 {
+        (self.event_timer_block_id >> 16) as u16
+    }
+}
@@ -0,0 +1,57 @@
+use spin::Once;
+use x86_64::PhysAddr;
+
+use super::rsdp::Rsdp;
+use super::sdt::{AcpiTable, SdtHeader};
+use crate::memory::global::phys_to_virt;
+
+/// The RSDT or the XSDT, which lists the addresses of every other table.
+struct RootTable {
+    header: &'static SdtHeader,
+    /// The XSDT holds 64-bit addresses, the RSDT 32-bit ones.
+    is_xsdt: bool,
+}
+
+static ROOT_TABLE: Once<RootTable> = Once::new();
+
+/// Locates the ACPI tables from the RSDP given by the bootloader.
+///
+/// Must be called once memory management is initialized.
+pub fn init(rsdp_address: PhysAddr) {
+    let rsdp = unsafe { &*phys_to_virt(rsdp_address).as_ptr::<Rsdp>() };
+    assert!(rsdp.has_valid_signature(), "invalid RSDP signature");
+    assert!(rsdp.has_valid_checksum(), "invalid RSDP checksum");
+
+    let (root_table_address, is_xsdt) = rsdp.root_table();
+    let header = unsafe { &*phys_to_virt(root_table_address).as_ptr::<SdtHeader>() };
+    assert!(header.has_valid_checksum(), "invalid root table checksum");
+
+    ROOT_TABLE.call_once(|| RootTable { header, is_xsdt });
+}
+
+/// Returns an iterator over the headers of every table listed in the RSDT or the XSDT, including
+/// the ones with an invalid checksum.
+pub fn tables() -> impl Iterator<Item = &'static SdtHeader> {
+    let (data, entry_size): (&[u8], usize) = match ROOT_TABLE.r#try() {
+        Some(root) if root.is_xsdt => (root.header.data(), 8),
+        Some(root) => (root.header.data(), 4),
+        None => (&[], 4),
+    };
+
+    data.chunks_exact(entry_size).map(|entry| {
+        let address = match *entry {
+            [a, b, c, d] => u32::from_le_bytes([a, b, c, d]) as u64,
+            _ => u64::from_le_bytes(entry.try_into().unwrap()),
+        };
+
+        unsafe { &*phys_to_virt(PhysAddr::new(address)).as_ptr::<SdtHeader>() }
+    })
+}
+
+/// Returns the first valid table with the signature of `T`.
+pub fn find_table<T: AcpiTable>() -> Option<&'static T> {
+    tables()
+        .filter(|header| &header.signature == T::SIGNATURE)
+        .find(|header| header.has_valid_checksum() && header.length as usize >= T::MIN_LENGTH)
+        .map(|header| unsafe { &*(header as *const SdtHeader as *const T) })
+}
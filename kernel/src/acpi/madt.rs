@@ -0,0 +1,272 @@
+use bitflags::bitflags;
+
+use super::sdt::{AcpiTable, SdtHeader};
+
+/// Multiple APIC Description Table, which lists the interrupt controllers of the system.
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct Madt {
+    /// Header, with the signature `"APIC"`
+    ///
+    /// - Bytes 0-35
+    pub header: SdtHeader,
+    /// Physical address of the local APIC of each processor
+    ///
+    /// - Bytes 36-39
+    pub local_apic_address: u32,
+    /// Flags
+    ///
+    /// - Bytes 40-43
+    pub flags: MadtFlags,
+    // /// Variable length interrupt controller structures
+    // ///
+    // /// - Bytes 44-N
+    // pub entries: [EntryHeader],
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct MadtFlags: u32 {
+        /// The system also has dual 8259 PICs, which must be masked before using the APICs
+        const PCAT_COMPAT = 1 << 0;
+    }
+}
+
+unsafe impl AcpiTable for Madt {
+    const SIGNATURE: &'static [u8; 4] = b"APIC";
+}
+
+impl Madt {
+    pub fn entries(&self) -> Iter<'_> {
+        let data = self.header.data();
+        // `local_apic_address` and `flags` are part of the data that follows the header
+        Iter {
+            data: data.get(8..).unwrap_or_default(),
+            next: 0,
+        }
+    }
+}
+
+/// Header of every interrupt controller structure.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct EntryHeader {
+    /// Type of the structure
+    ///
+    /// - Byte 0
+    pub entry_type: u8,
+    /// Length of the structure, header included
+    ///
+    /// - Byte 1
+    pub length: u8,
+}
+
+/// Processor Local APIC (type 0)
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct LocalApic {
+    /// - Bytes 0-1
+    pub header: EntryHeader,
+    /// ACPI processor UID
+    ///
+    /// - Byte 2
+    pub processor_id: u8,
+    /// Local APIC ID of the processor
+    ///
+    /// - Byte 3
+    pub apic_id: u8,
+    /// Flags
+    ///
+    /// - Bytes 4-7
+    pub flags: LocalApicFlags,
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug)]
+    pub struct LocalApicFlags: u32 {
+        /// The processor is ready to use
+        const ENABLED = 1 << 0;
+        /// The processor can be enabled at runtime, if it is not already
+        const ONLINE_CAPABLE = 1 << 1;
+    }
+}
+
+/// I/O APIC (type 1)
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct IoApic {
+    /// - Bytes 0-1
+    pub header: EntryHeader,
+    /// I/O APIC ID
+    ///
+    /// - Byte 2
+    pub io_apic_id: u8,
+    /// Reserved
+    ///
+    /// - Byte 3
+    _reserved: u8,
+    /// Physical address of the I/O APIC registers
+    ///
+    /// - Bytes 4-7
+    pub address: u32,
+    /// First Global System Interrupt handled by this I/O APIC
+    ///
+    /// - Bytes 8-11
+    pub global_system_interrupt_base: u32,
+}
+
+/// Interrupt Source Override (type 2), which describes how an ISA IRQ is wired to the I/O APIC
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct InterruptSourceOverride {
+    /// - Bytes 0-1
+    pub header: EntryHeader,
+    /// Always 0 (ISA)
+    ///
+    /// - Byte 2
+    pub bus: u8,
+    /// ISA IRQ number
+    ///
+    /// - Byte 3
+    pub source: u8,
+    /// Global System Interrupt the IRQ is connected to
+    ///
+    /// - Bytes 4-7
+    pub global_system_interrupt: u32,
+    /// Polarity and trigger mode
+    ///
+    /// - Bytes 8-9
+    pub flags: MpsInterruptFlags,
+}
+
+/// Local APIC NMI (type 4), which tells which LINT pin of a processor is connected to the NMI
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct LocalApicNmi {
+    /// - Bytes 0-1
+    pub header: EntryHeader,
+    /// ACPI processor UID, 0xff for every processor
+    ///
+    /// - Byte 2
+    pub processor_id: u8,
+    /// Polarity and trigger mode
+    ///
+    /// - Bytes 3-4
+    pub flags: MpsInterruptFlags,
+    /// LINT pin (0 or 1)
+    ///
+    /// - Byte 5
+    pub lint: u8,
+}
+
+/// Local APIC Address Override (type 5), the 64-bit address of the local APICs
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct LocalApicAddressOverride {
+    /// - Bytes 0-1
+    pub header: EntryHeader,
+    /// Reserved
+    ///
+    /// - Bytes 2-3
+    _reserved: u16,
+    /// Physical address of the local APIC of each processor
+    ///
+    /// - Bytes 4-11
+    pub address: u64,
+}
+
+/// Polarity and trigger mode of an interrupt, as defined by the MultiProcessor Specification.
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct MpsInterruptFlags(u16);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Polarity {
+    /// Conforms to the specification of the bus
+    BusDefault,
+    ActiveHigh,
+    ActiveLow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerMode {
+    /// Conforms to the specification of the bus
+    BusDefault,
+    Edge,
+    Level,
+}
+
+impl MpsInterruptFlags {
+    pub fn polarity(&self) -> Polarity {
+        match self.0 & 0b11 {
+            0b01 => Polarity::ActiveHigh,
+            0b11 => Polarity::ActiveLow,
+            _ => Polarity::BusDefault,
+        }
+    }
+
+    pub fn trigger_mode(&self) -> TriggerMode {
+        match (self.0 >> 2) & 0b11 {
+            0b01 => TriggerMode::Edge,
+            0b11 => TriggerMode::Level,
+            _ => TriggerMode::BusDefault,
+        }
+    }
+}
+
+/// An interrupt controller structure of the MADT.
+#[derive(Debug)]
+pub enum Entry<'a> {
+    LocalApic(&'a LocalApic),
+    IoApic(&'a IoApic),
+    InterruptSourceOverride(&'a InterruptSourceOverride),
+    LocalApicNmi(&'a LocalApicNmi),
+    LocalApicAddressOverride(&'a LocalApicAddressOverride),
+    /// A structure that is not parsed yet
+    Other(&'a EntryHeader),
+}
+
+pub struct Iter<'a> {
+    data: &'a [u8],
+    next: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Entry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.data.get(self.next..)?;
+        if remaining.len() < size_of::<EntryHeader>() {
+            return None;
+        }
+
+        let header = unsafe { &*(remaining.as_ptr() as *const EntryHeader) };
+        let length = header.length as usize;
+        if length < size_of::<EntryHeader>() || length > remaining.len() {
+            return None;
+        }
+
+        self.next += length;
+
+        let ptr = remaining.as_ptr();
+        // Safety: the structures are only cast when they fit in the entry length
+        let entry = unsafe {
+            match header.entry_type {
+                0 if length >= size_of::<LocalApic>() => Entry::LocalApic(&*(ptr as *const _)),
+                1 if length >= size_of::<IoApic>() => Entry::IoApic(&*(ptr as *const _)),
+                2 if length >= size_of::<InterruptSourceOverride>() => {
+                    Entry::InterruptSourceOverride(&*(ptr as *const _))
+                }
+                4 if length >= size_of::<LocalApicNmi>() => {
+                    Entry::LocalApicNmi(&*(ptr as *const _))
+                }
+                5 if length >= size_of::<LocalApicAddressOverride>() => {
+                    Entry::LocalApicAddressOverride(&*(ptr as *const _))
+                }
+                _ => Entry::Other(header),
+            }
+        };
+
+        Some(entry)
+    }
+}
@@ -0,0 +1,76 @@
+use core::slice;
+
+use super::sdt::{AcpiTable, SdtHeader};
+
+/// PCI Express memory mapped configuration space description table.
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct Mcfg {
+    /// Header, with the signature `"MCFG"`
+    ///
+    /// - Bytes 0-35
+    pub header: SdtHeader,
+    /// Reserved
+    ///
+    /// - Bytes 36-43
+    _reserved: u64,
+    // /// Configuration space base address allocation structures
+    // ///
+    // /// - Bytes 44-N
+    // pub entries: [ConfigurationSpace],
+}
+
+unsafe impl AcpiTable for Mcfg {
+    const SIGNATURE: &'static [u8; 4] = b"MCFG";
+}
+
+/// Configuration space of a range of PCI buses of a segment group.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct ConfigurationSpace {
+    /// Physical address of the configuration space of the bus 0 of the segment group
+    ///
+    /// - Bytes 0-7
+    pub base_address: u64,
+    /// PCI segment group number
+    ///
+    /// - Bytes 8-9
+    pub segment_group: u16,
+    /// First bus decoded by this configuration space
+    ///
+    /// - Byte 10
+    pub start_bus: u8,
+    /// Last bus decoded by this configuration space
+    ///
+    /// - Byte 11
+    pub end_bus: u8,
+    /// Reserved
+    ///
+    /// - Bytes 12-15
+    _reserved: u32,
+}
+
+impl Mcfg {
+    pub fn entries(&self) -> &[ConfigurationSpace] {
+        let data = self.header.data();
+        // the reserved field is part of the data that follows the header
+        let entries = data.get(8..).unwrap_or_default();
+        let count = entries.len() / size_of::<ConfigurationSpace>();
+
+        // Safety: `ConfigurationSpace` is packed, so it has no alignment requirement
+        unsafe { slice::from_raw_parts(entries.as_ptr() as *const ConfigurationSpace, count) }
+    }
+}
+
+impl ConfigurationSpace {
+    /// Returns the physical address of the configuration space of a PCI function, if its bus is
+    /// decoded by this configuration space.
+    pub fn function_address(&self, bus: u8, device: u8, function: u8) -> Option<u64> {
+        if !(self.start_bus..=self.end_bus).contains(&bus) {
+            return None;
+        }
+
+        let offset = ((bus as u64) << 20) | ((device as u64) << 15) | ((function as u64) << 12);
+        Some(self.base_address + offset)
+    }
+}
@@ -0,0 +1,88 @@
+use core::slice;
+
+use x86_64::PhysAddr;
+
+use super::sdt::checksum;
+
+/// Root System Description Pointer, which locates the RSDT or the XSDT.
+#[derive(Debug)]
+#[repr(C, packed)]
+pub struct Rsdp {
+    /// `"RSD PTR "`
+    ///
+    /// - Bytes 0-7
+    pub signature: [u8; 8],
+    /// Checksum of the first 20 bytes
+    ///
+    /// - Byte 8
+    pub checksum: u8,
+    /// OEM identifier
+    ///
+    /// - Bytes 9-14
+    pub oem_id: [u8; 6],
+    /// 0 for ACPI 1.0, 2 for ACPI 2.0 and later
+    ///
+    /// - Byte 15
+    pub revision: u8,
+    /// Physical address of the RSDT
+    ///
+    /// - Bytes 16-19
+    pub rsdt_address: u32,
+
+    /*********************************************************
+     * These fields are only present if `revision` is >= 2. *
+     *********************************************************/
+    /// Length of the whole structure
+    ///
+    /// - Bytes 20-23
+    pub length: u32,
+    /// Physical address of the XSDT
+    ///
+    /// - Bytes 24-31
+    pub xsdt_address: u64,
+    /// Checksum of the whole structure
+    ///
+    /// - Byte 32
+    pub extended_checksum: u8,
+    /// Reserved
+    ///
+    /// - Bytes 33-35
+    _reserved: [u8; 3],
+}
+
+impl Rsdp {
+    const SIGNATURE: &'static [u8; 8] = b"RSD PTR ";
+    /// Size of the ACPI 1.0 structure, covered by `checksum`.
+    const V1_SIZE: usize = 20;
+
+    pub fn has_valid_signature(&self) -> bool {
+        &self.signature == Self::SIGNATURE
+    }
+
+    /// Checks `checksum`, and `extended_checksum` for ACPI 2.0 and later.
+    pub fn has_valid_checksum(&self) -> bool {
+        let ptr = self as *const Self as *const u8;
+
+        let v1 = unsafe { slice::from_raw_parts(ptr, Self::V1_SIZE) };
+        if checksum(v1) != 0 {
+            return false;
+        }
+
+        match self.revision {
+            0 => true,
+            _ => {
+                let length = (self.length as usize).max(Self::V1_SIZE);
+                checksum(unsafe { slice::from_raw_parts(ptr, length) }) == 0
+            }
+        }
+    }
+
+    /// Returns the address of the XSDT if there is one, or the address of the RSDT otherwise,
+    /// and whether it is the XSDT.
+    pub fn root_table(&self) -> (PhysAddr, bool) {
+        match self.revision {
+            0 => (PhysAddr::new(self.rsdt_address as u64), false),
+            _ => (PhysAddr::new(self.xsdt_address), true),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use core::{mem, slice};
+
+/// A System Description Table that starts with an [`SdtHeader`].
+///
+/// # Safety
+///
+/// The type must be `#[repr(C, packed)]`, start with an [`SdtHeader`], and describe the table
+/// with the signature `SIGNATURE`. Fields past `MIN_LENGTH` must only be read after checking the
+/// length of the table.
+pub unsafe trait AcpiTable: Sized {
+    const SIGNATURE: &'static [u8; 4];
+    /// Length of the oldest revision of the table.
+    const MIN_LENGTH: usize = mem::size_of::<Self>();
+}
+
+/// Header shared by every System Description Table.
+#[derive(Clone, Copy, Debug)]
+#[repr(C, packed)]
+pub struct SdtHeader {
+    /// Table signature, such as `"APIC"` for the MADT
+    ///
+    /// - Bytes 0-3
+    pub signature: [u8; 4],
+    /// Length of the table, header included
+    ///
+    /// - Bytes 4-7
+    pub length: u32,
+    /// Revision of the table structure
+    ///
+    /// - Byte 8
+    pub revision: u8,
+    /// The sum of all the bytes of the table must be 0
+    ///
+    /// - Byte 9
+    pub checksum: u8,
+    /// OEM identifier
+    ///
+    /// - Bytes 10-15
+    pub oem_id: [u8; 6],
+    /// OEM table identifier
+    ///
+    /// - Bytes 16-23
+    pub oem_table_id: [u8; 8],
+    /// OEM revision
+    ///
+    /// - Bytes 24-27
+    pub oem_revision: u32,
+    /// Vendor ID of the utility that created the table
+    ///
+    /// - Bytes 28-31
+    pub creator_id: u32,
+    /// Revision of the utility that created the table
+    ///
+    /// - Bytes 32-35
+    pub creator_revision: u32,
+}
+
+impl SdtHeader {
+    pub const SIZE: usize = 36;
+
+    /// Returns the bytes of the whole table, header included.
+    pub fn bytes(&self) -> &[u8] {
+        let length = (self.length as usize).max(Self::SIZE);
+        unsafe { slice::from_raw_parts(self as *const Self as *const u8, length) }
+    }
+
+    pub fn has_valid_checksum(&self) -> bool {
+        checksum(self.bytes()) == 0
+    }
+
+    /// Returns the bytes that follow the header, up to the length of the table.
+    pub fn data(&self) -> &[u8] {
+        let length = self.length as usize;
+        let start_ptr = self as *const Self as *const u8;
+
+        unsafe {
+            slice::from_raw_parts(
+                start_ptr.add(mem::size_of::<Self>()),
+                length.saturating_sub(Self::SIZE),
+            )
+        }
+    }
+}
+
+/// Returns the sum of `bytes`, which is 0 for the bytes covered by a valid ACPI checksum.
+pub fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |sum, &byte| sum.wrapping_add(byte))
+}
@@ -0,0 +1,112 @@
+use super::aml;
+use super::fadt::Fadt;
+use super::hpet::Hpet;
+use super::madt::{Entry, Madt, Polarity, TriggerMode};
+use super::mcfg::Mcfg;
+use super::sdt::{checksum, SdtHeader};
+
+/// Builds a table with `signature` from its data, with a valid length and checksum.
+fn table(signature: &[u8; 4], data: &[u8]) -> [u8; 128] {
+    let mut bytes = [0; 128];
+    let length = SdtHeader::SIZE + data.len();
+
+    bytes[0..4].copy_from_slice(signature);
+    bytes[4..8].copy_from_slice(&(length as u32).to_le_bytes());
+    bytes[SdtHeader::SIZE..length].copy_from_slice(data);
+    bytes[9] = 0u8.wrapping_sub(checksum(&bytes[..length]));
+
+    bytes
+}
+
+#[test_case]
+fn test_table_sizes() {
+    assert_eq!(size_of::<SdtHeader>(), SdtHeader::SIZE);
+    assert_eq!(size_of::<Fadt>(), 276);
+    assert_eq!(size_of::<Hpet>(), 56);
+    assert_eq!(size_of::<Mcfg>(), 44);
+}
+
+#[test_case]
+fn test_checksum() {
+    let bytes = table(b"TEST", &[1, 2, 3]);
+    let header = unsafe { &*(bytes.as_ptr() as *const SdtHeader) };
+    assert!(header.has_valid_checksum());
+    assert_eq!(header.data(), &[1, 2, 3]);
+
+    let mut corrupted = bytes;
+    corrupted[SdtHeader::SIZE] = 2;
+    let header = unsafe { &*(corrupted.as_ptr() as *const SdtHeader) };
+    assert!(!header.has_valid_checksum());
+}
+
+#[test_case]
+fn test_madt_entries() {
+    #[rustfmt::skip]
+    let data = [
+        // local APIC address and flags
+        0x00, 0x00, 0xe0, 0xfe, 1, 0, 0, 0,
+        // local APIC: processor 0, APIC ID 0, enabled
+        0, 8, 0, 0, 1, 0, 0, 0,
+        // I/O APIC: ID 1, address 0xfec00000, GSI base 0
+        1, 12, 1, 0, 0x00, 0x00, 0xc0, 0xfe, 0, 0, 0, 0,
+        // interrupt source override: IRQ 0 to GSI 2, active high, edge triggered
+        2, 10, 0, 0, 2, 0, 0, 0, 0b0101, 0,
+        // unknown structure
+        0x7f, 2,
+    ];
+    let bytes = table(b"APIC", &data);
+    let madt = unsafe { &*(bytes.as_ptr() as *const Madt) };
+
+    assert_eq!({ madt.local_apic_address }, 0xfee0_0000);
+
+    let mut entries = madt.entries();
+    assert!(
+        matches!(entries.next(), Some(Entry::LocalApic(local_apic)) if local_apic.apic_id == 0)
+    );
+    assert!(matches!(
+        entries.next(),
+        Some(Entry::IoApic(io_apic)) if { io_apic.address } == 0xfec0_0000
+    ));
+    let Some(Entry::InterruptSourceOverride(source_override)) = entries.next() else {
+        panic!("expected an interrupt source override");
+    };
+    assert_eq!({ source_override.global_system_interrupt }, 2);
+    let flags = source_override.flags;
+    assert_eq!(flags.polarity(), Polarity::ActiveHigh);
+    assert_eq!(flags.trigger_mode(), TriggerMode::Edge);
+    assert!(matches!(entries.next(), Some(Entry::Other(_))));
+    assert!(entries.next().is_none());
+}
+
+#[test_case]
+fn test_mcfg_entries() {
+    #[rustfmt::skip]
+    let data = [
+        // reserved
+        0, 0, 0, 0, 0, 0, 0, 0,
+        // base address 0xb0000000, segment group 0, buses 0 to 255
+        0x00, 0x00, 0x00, 0xb0, 0, 0, 0, 0, 0, 0, 0, 255, 0, 0, 0, 0,
+    ];
+    let bytes = table(b"MCFG", &data);
+    let mcfg = unsafe { &*(bytes.as_ptr() as *const Mcfg) };
+
+    let entries = mcfg.entries();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].function_address(0, 0, 0), Some(0xb000_0000));
+    assert_eq!(entries[0].function_address(1, 2, 3), Some(0xb011_3000));
+}
+
+#[test_case]
+fn test_aml_sleep_type() {
+    // Name (\_S5, Package (0x04) { 0x05, Zero, Zero, Zero })
+    let aml = [
+        0x10, 0x08, 0x08, b'\\', b'_', b'S', b'5', b'_', 0x12, 0x08, 0x04, 0x0a, 0x05, 0x00, 0x00,
+        0x00,
+    ];
+    assert_eq!(aml::sleep_type(&aml, b"_S5_"), Some((5, 0)));
+    assert_eq!(aml::sleep_type(&aml, b"_S4_"), None);
+
+    // a reference to the name is not a definition
+    let aml = [0x70, b'_', b'S', b'5', b'_', 0x12, 0x04, 0x02, 0x01, 0x01];
+    assert_eq!(aml::sleep_type(&aml, b"_S5_"), None);
+}
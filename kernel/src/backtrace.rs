@@ -0,0 +1,8 @@
+mod inner;
+pub mod symbols;
+mod unwind;
+
+pub use inner::Backtrace;
+
+#[cfg(test)]
+mod tests;
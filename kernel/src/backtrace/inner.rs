@@ -0,0 +1,94 @@
+use core::arch::asm;
+use core::fmt;
+
+use super::symbols;
+use super::unwind::FramePointerIter;
+
+/// A stack trace, printed as one `function+offset` line per frame.
+#[derive(Debug, Clone, Copy)]
+pub struct Backtrace {
+    /// Address of the faulting instruction, when the trace starts at an exception.
+    instruction_pointer: Option<u64>,
+    rbp: u64,
+}
+
+impl Backtrace {
+    /// Captures the stack of the caller.
+    #[inline(always)]
+    pub fn capture() -> Self {
+        let rbp: u64;
+        unsafe { asm!("mov {}, rbp", out(reg) rbp, options(nomem, nostack, preserves_flags)) };
+
+        Self {
+            instruction_pointer: None,
+            rbp,
+        }
+    }
+
+    /// Creates the stack trace of code interrupted at `instruction_pointer`, with the
+    /// frame pointer `rbp`.
+    pub fn from_registers(instruction_pointer: u64, rbp: u64) -> Self {
+        Self {
+            instruction_pointer: Some(instruction_pointer),
+            rbp,
+        }
+    }
+
+    fn addresses(&self) -> impl Iterator<Item = Address> {
+        let faulting = self.instruction_pointer.map(Address::Instruction);
+        let returns = FramePointerIter::new(self.rbp).map(Address::Return);
+
+        faulting.into_iter().chain(returns)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Address {
+    /// The address of an instruction that was being executed.
+    Instruction(u64),
+    /// A return address, which points after the call instruction.
+    Return(u64),
+}
+
+impl Address {
+    fn value(self) -> u64 {
+        match self {
+            Self::Instruction(address) | Self::Return(address) => address,
+        }
+    }
+
+    /// Address used to look up the symbol. For return addresses, this is inside the call
+    /// instruction, because a call to a diverging function may be the last instruction of
+    /// the caller.
+    fn lookup(self) -> u64 {
+        match self {
+            Self::Instruction(address) => address,
+            Self::Return(address) => address - 1,
+        }
+    }
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Backtrace:")?;
+
+        if !symbols::is_available() {
+            write!(f, " (no symbol table)")?;
+        }
+
+        for (index, address) in self.addresses().enumerate() {
+            write!(f, "\n  #{index:<2} {:#018x}", address.value())?;
+
+            if let Some(symbol) = symbols::resolve(address.lookup()) {
+                write!(
+                    f,
+                    " {}+{:#x}",
+                    symbol.name,
+                    address.value() - symbol.address
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
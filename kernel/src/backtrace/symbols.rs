@@ -0,0 +1,120 @@
+//! Symbol table embedded in the kernel image.
+//!
+//! The table is generated by the root `build.rs` from the linked kernel ELF and written into
+//! the `.ksyms` section, which is reserved here and left empty by the compiler. Its layout is
+//! (all integers little-endian):
+//!
+//! - `b"KSYM"`
+//! - number of symbols: `u32`
+//! - link-time address of the `.ksyms` section: `u64`
+//! - the symbols, sorted by address: address `u64`, size `u32`, name offset `u32` and name
+//!   length `u16`
+//! - the names, as UTF-8 strings
+
+use core::hint;
+
+/// Space reserved for the symbol table in the kernel image.
+pub const SYMBOL_TABLE_SIZE: usize = 512 * 1024;
+
+const MAGIC: &[u8; 4] = b"KSYM";
+const HEADER_SIZE: usize = 16;
+const ENTRY_SIZE: usize = 18;
+
+#[used]
+#[link_section = ".ksyms"]
+static SYMBOL_TABLE: [u8; SYMBOL_TABLE_SIZE] = [0; SYMBOL_TABLE_SIZE];
+
+/// A function symbol of the kernel.
+#[derive(Debug, Clone, Copy)]
+pub struct Symbol {
+    pub name: &'static str,
+    /// Runtime address of the start of the function.
+    pub address: u64,
+    pub size: u64,
+}
+
+struct Table {
+    bytes: &'static [u8],
+    count: usize,
+    /// Difference between runtime and link-time addresses.
+    load_offset: u64,
+}
+
+impl Table {
+    fn get() -> Option<Self> {
+        // The compiler only sees zeros in the table, the actual content is written by `build.rs`.
+        let bytes: &'static [u8] = hint::black_box(&SYMBOL_TABLE);
+
+        if &bytes[..4] != MAGIC {
+            return None;
+        }
+
+        let count = read_u32(bytes, 4) as usize;
+        let section_address = read_u64(bytes, 8);
+        let load_offset = (bytes.as_ptr() as u64).wrapping_sub(section_address);
+
+        Some(Self {
+            bytes,
+            count,
+            load_offset,
+        })
+    }
+
+    fn symbol(&self, index: usize) -> Symbol {
+        let entry = HEADER_SIZE + index * ENTRY_SIZE;
+        let address = read_u64(self.bytes, entry).wrapping_add(self.load_offset);
+        let size = read_u32(self.bytes, entry + 8) as u64;
+        let name_offset = read_u32(self.bytes, entry + 12) as usize;
+        let name_length = read_u16(self.bytes, entry + 16) as usize;
+
+        let name = self
+            .bytes
+            .get(name_offset..(name_offset + name_length))
+            .and_then(|name| core::str::from_utf8(name).ok())
+            .unwrap_or("<invalid symbol name>");
+
+        Symbol {
+            name,
+            address,
+            size,
+        }
+    }
+}
+
+/// Returns `true` if `build.rs` embedded a symbol table into the kernel image.
+pub fn is_available() -> bool {
+    Table::get().is_some()
+}
+
+/// Returns the function containing `address`.
+pub fn resolve(address: u64) -> Option<Symbol> {
+    let table = Table::get()?;
+
+    // index of the first symbol starting after `address`
+    let (mut low, mut high) = (0, table.count);
+    while low < high {
+        let middle = (low + high) / 2;
+        if table.symbol(middle).address <= address {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    let symbol = table.symbol(low.checked_sub(1)?);
+    // Symbols without a size (mostly assembly) are assumed to extend up to the next one.
+    let contains = symbol.size == 0 || address < symbol.address + symbol.size;
+    contains.then_some(symbol)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..(offset + 2)].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..(offset + 4)].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..(offset + 8)].try_into().unwrap())
+}
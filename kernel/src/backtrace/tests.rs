@@ -0,0 +1,26 @@
+use alloc::format;
+
+use super::symbols;
+use super::Backtrace;
+
+#[test_case]
+fn test_capture_walks_frames() {
+    let backtrace = format!("{}", Backtrace::capture());
+    assert!(backtrace.contains("#0"));
+}
+
+#[inline(never)]
+fn some_function() {}
+
+#[test_case]
+fn test_resolve_function_address() {
+    // The symbol table is only embedded in the images built by the root crate.
+    if !symbols::is_available() {
+        return;
+    }
+
+    let address = some_function as usize as u64;
+    let symbol = symbols::resolve(address).expect("symbol not found");
+    assert_eq!(symbol.address, address);
+    assert!(symbol.name.ends_with("some_function"));
+}
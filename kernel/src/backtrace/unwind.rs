@@ -0,0 +1,58 @@
+use x86_64::structures::paging::Translate;
+use x86_64::VirtAddr;
+
+use crate::memory::global;
+
+/// Maximum number of frames that are walked, in case the frame pointer chain loops.
+const MAX_DEPTH: usize = 64;
+
+/// Iterator over the return addresses found by following the chain of saved frame pointers.
+///
+/// This relies on the kernel being compiled with `-C force-frame-pointers=yes`: every function
+/// pushes `rbp` right after its return address, and points `rbp` to that saved value.
+pub struct FramePointerIter {
+    rbp: u64,
+    depth: usize,
+}
+
+impl FramePointerIter {
+    pub fn new(rbp: u64) -> Self {
+        Self { rbp, depth: 0 }
+    }
+}
+
+impl Iterator for FramePointerIter {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.depth >= MAX_DEPTH || self.rbp == 0 || !self.rbp.is_multiple_of(8) {
+            return None;
+        }
+
+        let frame = VirtAddr::try_new(self.rbp).ok()?;
+        if !is_readable(frame) || !is_readable(frame + 8_u64) {
+            return None;
+        }
+
+        let saved_rbp = unsafe { frame.as_ptr::<u64>().read() };
+        let return_address = unsafe { (frame + 8_u64).as_ptr::<u64>().read() };
+
+        // The stack grows downwards, so the caller's frame must be above this one.
+        self.rbp = if saved_rbp > self.rbp { saved_rbp } else { 0 };
+        self.depth += 1;
+
+        (return_address != 0).then_some(return_address)
+    }
+}
+
+/// Checks that `addr` is mapped, when the page table is available.
+///
+/// If the page table is locked, for example because the crash happened while mapping memory,
+/// the address is trusted: the worst case is a page fault while printing the backtrace.
+fn is_readable(addr: VirtAddr) -> bool {
+    global::MAPPER
+        .r#try()
+        .and_then(|mapper| mapper.try_lock())
+        .map(|mapper| mapper.translate_addr(addr).is_some())
+        .unwrap_or(true)
+}
@@ -0,0 +1,6 @@
+mod inner;
+
+pub use inner::{get, init, parse, PATH};
+
+#[cfg(test)]
+mod tests;
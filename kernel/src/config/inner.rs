@@ -0,0 +1,43 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+use spin::Once;
+
+use crate::fs::{self, WorkingDirectory};
+
+/// Path of the boot configuration on the root file system.
+pub const PATH: &str = "/boot/config";
+
+static OPTIONS: Once<BTreeMap<String, String>> = Once::new();
+
+/// Reads the boot configuration from [`PATH`], see [`parse`] for its format.
+///
+/// Every option keeps its default value if the file is missing, so this must be called once the
+/// root file system is mounted.
+pub fn init() {
+    let options = fs::lookup(PATH, &WorkingDirectory::root())
+        .and_then(fs::contents)
+        .map(|contents| parse(&String::from_utf8_lossy(&contents)))
+        .unwrap_or_default();
+
+    OPTIONS.call_once(|| options);
+}
+
+/// Returns the value of the option `key`, if the boot configuration sets it.
+pub fn get(key: &str) -> Option<&'static str> {
+    OPTIONS.r#try()?.get(key).map(String::as_str)
+}
+
+/// Returns the options set by `text`, one `key = value` option per line.
+///
+/// Blank lines, comments that start with `#` and lines without `=` are ignored, and the last
+/// value of an option set several times wins.
+pub fn parse(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (String::from(key), String::from(value)))
+        .collect()
+}
@@ -0,0 +1,18 @@
+use super::parse;
+
+#[test_case]
+fn test_parse() {
+    let options = parse(
+        "# keyboard\n\
+         keyboard.layout = us  # on the laptop\n\
+         \n\
+         invalid line\n\
+         = no key\n\
+         console.font=large\n\
+         console.font = small\n",
+    );
+
+    assert_eq!(options.len(), 2);
+    assert_eq!(options["keyboard.layout"], "us");
+    assert_eq!(options["console.font"], "small");
+}
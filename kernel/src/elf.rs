@@ -0,0 +1,16 @@
+mod error;
+mod executable;
+mod header;
+mod loader;
+mod source;
+mod stack;
+
+pub use error::Error;
+pub use executable::{Executable, Segment};
+pub use header::{segment_flags, segment_type, FileHeader, ProgramHeader};
+pub use loader::load;
+pub use source::Source;
+pub use stack::{auxiliary, initial_stack, InitialStack};
+
+#[cfg(test)]
+pub(crate) mod tests;
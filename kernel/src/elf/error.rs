@@ -0,0 +1,29 @@
+use x86_64::structures::paging::mapper::MapToError;
+use x86_64::structures::paging::Size4KiB;
+
+/// Reasons why an executable cannot be run.
+#[derive(Debug)]
+pub enum Error {
+    /// The file is shorter than its headers say.
+    Truncated,
+    /// The file is not an ELF file.
+    BadMagic,
+    /// The file is not a 64-bit little endian x86-64 executable.
+    Unsupported,
+    /// The executable is dynamically linked, which needs an interpreter.
+    Interpreter,
+    /// A segment is malformed, overlaps another one, or is outside of the memory of programs.
+    BadSegment,
+    /// The entry point is not in an executable segment.
+    BadEntry,
+    /// The arguments and the environment do not fit in the stack.
+    ArgumentsTooLong,
+    /// The memory of the program could not be mapped.
+    Map(MapToError<Size4KiB>),
+}
+
+impl From<MapToError<Size4KiB>> for Error {
+    fn from(error: MapToError<Size4KiB>) -> Self {
+        Self::Map(error)
+    }
+}
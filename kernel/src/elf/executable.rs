@@ -0,0 +1,205 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use x86_64::structures::paging::PageTableFlags;
+use x86_64::VirtAddr;
+
+use super::header::{
+    self, segment_flags, segment_type, FileHeader, ProgramHeader, CLASS_64, DATA_LITTLE_ENDIAN,
+    MACHINE_X86_64, MAGIC, TYPE_DYNAMIC, TYPE_EXECUTABLE, VERSION_CURRENT,
+};
+use super::{Error, Source};
+use crate::memory::constants::{PAGE_SIZE, USER_REGION_END, USER_REGION_START, USER_STACK_SIZE};
+
+/// End of the memory in which segments are loaded, below the stack and its guard page.
+const SEGMENTS_END: u64 = USER_REGION_END - USER_STACK_SIZE - PAGE_SIZE;
+
+/// A `PT_LOAD` segment, at the address where it is loaded.
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub start: VirtAddr,
+    pub memory_size: u64,
+    /// Contents of the segment in the file, followed by zeros up to its memory size
+    pub file_range: Range<usize>,
+    /// Permissions of the pages of the segment
+    pub flags: PageTableFlags,
+}
+
+impl Segment {
+    fn new(
+        program_header: &ProgramHeader,
+        file_size: usize,
+        load_bias: u64,
+    ) -> Result<Self, Error> {
+        let ProgramHeader {
+            offset,
+            virtual_address,
+            file_size: segment_file_size,
+            memory_size,
+            align,
+            flags,
+            ..
+        } = *program_header;
+
+        if segment_file_size > memory_size {
+            return Err(Error::BadSegment);
+        }
+        if align > 1 && (!align.is_power_of_two() || virtual_address % align != offset % align) {
+            return Err(Error::BadSegment);
+        }
+
+        let file_end = offset
+            .checked_add(segment_file_size)
+            .ok_or(Error::Truncated)?;
+        if file_end > file_size as u64 {
+            return Err(Error::Truncated);
+        }
+
+        let start = virtual_address
+            .checked_add(load_bias)
+            .ok_or(Error::BadSegment)?;
+        let end = start.checked_add(memory_size).ok_or(Error::BadSegment)?;
+        if start < USER_REGION_START || end > SEGMENTS_END {
+            return Err(Error::BadSegment);
+        }
+
+        let mut page_flags = PageTableFlags::empty();
+        if flags & segment_flags::WRITE != 0 {
+            page_flags |= PageTableFlags::WRITABLE;
+        }
+        if flags & segment_flags::EXECUTE == 0 {
+            page_flags |= PageTableFlags::NO_EXECUTE;
+        }
+
+        Ok(Self {
+            start: VirtAddr::new(start),
+            memory_size,
+            file_range: offset as usize..file_end as usize,
+            flags: page_flags,
+        })
+    }
+
+    pub fn end(&self) -> VirtAddr {
+        self.start + self.memory_size
+    }
+
+    fn contains(&self, addr: VirtAddr) -> bool {
+        (self.start..self.end()).contains(&addr)
+    }
+}
+
+/// A validated ELF64 executable.
+///
+/// Statically linked executables are supported, either loaded at the addresses they are linked
+/// at, which must be in the user region, or position independent ones, which are loaded at the
+/// start of the user region and relocate themselves.
+///
+/// Only its headers are read, the contents of its segments are read by [`load`](super::load).
+#[derive(Debug)]
+pub struct Executable {
+    header: FileHeader,
+    entry: VirtAddr,
+    /// Sorted by address, and never overlapping the same page
+    segments: Vec<Segment>,
+    /// Address of the program headers in memory, if a segment loads them
+    program_headers: Option<VirtAddr>,
+}
+
+impl Executable {
+    /// Checks the headers of the executable file `source`.
+    pub fn parse(source: &(impl Source + ?Sized)) -> Result<Self, Error> {
+        let header: FileHeader = header::read(source, 0).ok_or(Error::Truncated)?;
+
+        if header.ident[..4] != MAGIC {
+            return Err(Error::BadMagic);
+        }
+        if header.ident[4] != CLASS_64
+            || header.ident[5] != DATA_LITTLE_ENDIAN
+            || header.ident[6] != VERSION_CURRENT
+            || header.version != VERSION_CURRENT as u32
+            || header.machine != MACHINE_X86_64
+            || header.program_header_size as usize != ProgramHeader::SIZE
+        {
+            return Err(Error::Unsupported);
+        }
+
+        let load_bias = match header.file_type {
+            TYPE_EXECUTABLE => 0,
+            TYPE_DYNAMIC => USER_REGION_START,
+            _ => return Err(Error::Unsupported),
+        };
+
+        let program_headers_offset = header.program_header_offset as usize;
+        let mut segments = Vec::new();
+        for index in 0..header.program_header_count as usize {
+            let offset = program_headers_offset
+                .checked_add(index * ProgramHeader::SIZE)
+                .ok_or(Error::Truncated)?;
+            let program_header: ProgramHeader =
+                header::read(source, offset).ok_or(Error::Truncated)?;
+
+            match program_header.segment_type {
+                segment_type::INTERPRETER => return Err(Error::Interpreter),
+                segment_type::LOAD if program_header.memory_size > 0 => {
+                    segments.push(Segment::new(&program_header, source.size(), load_bias)?);
+                }
+                _ => {}
+            }
+        }
+
+        segments.sort_by_key(|segment| segment.start);
+        if segments.is_empty() {
+            return Err(Error::BadSegment);
+        }
+        // segments are mapped with their own permissions, so they cannot share a page
+        for pair in segments.windows(2) {
+            if pair[0].end().align_up(PAGE_SIZE) > pair[1].start.align_down(PAGE_SIZE) {
+                return Err(Error::BadSegment);
+            }
+        }
+
+        let entry = header
+            .entry
+            .checked_add(load_bias)
+            .and_then(|entry| VirtAddr::try_new(entry).ok())
+            .ok_or(Error::BadEntry)?;
+        let executable_entry = segments.iter().any(|segment| {
+            segment.contains(entry) && !segment.flags.contains(PageTableFlags::NO_EXECUTE)
+        });
+        if !executable_entry {
+            return Err(Error::BadEntry);
+        }
+
+        let program_headers_size = header.program_header_count as usize * ProgramHeader::SIZE;
+        let program_headers = segments.iter().find_map(|segment| {
+            let range = &segment.file_range;
+            (range.start <= program_headers_offset
+                && program_headers_offset + program_headers_size <= range.end)
+                .then(|| segment.start + (program_headers_offset - range.start) as u64)
+        });
+
+        Ok(Self {
+            header,
+            entry,
+            segments,
+            program_headers,
+        })
+    }
+
+    pub fn entry(&self) -> VirtAddr {
+        self.entry
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Returns the address of the program headers in memory, if a segment loads them.
+    pub fn program_headers(&self) -> Option<VirtAddr> {
+        self.program_headers
+    }
+
+    pub fn program_header_count(&self) -> u16 {
+        self.header.program_header_count
+    }
+}
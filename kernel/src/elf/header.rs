@@ -0,0 +1,114 @@
+use core::mem::MaybeUninit;
+use core::slice;
+
+use super::Source;
+
+/// Identification bytes that start every ELF file.
+pub const MAGIC: [u8; 4] = *b"\x7fELF";
+
+/// `e_ident[EI_CLASS]` of 64-bit files.
+pub const CLASS_64: u8 = 2;
+/// `e_ident[EI_DATA]` of little endian files.
+pub const DATA_LITTLE_ENDIAN: u8 = 1;
+/// `e_ident[EI_VERSION]` and `e_version` of the only ELF version.
+pub const VERSION_CURRENT: u8 = 1;
+
+/// `e_type` of executables loaded at the addresses they are linked at.
+pub const TYPE_EXECUTABLE: u16 = 2;
+/// `e_type` of position independent executables (and shared objects).
+pub const TYPE_DYNAMIC: u16 = 3;
+
+/// `e_machine` of x86-64 files.
+pub const MACHINE_X86_64: u16 = 62;
+
+/// ELF64 file header, at the start of the file.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct FileHeader {
+    pub ident: [u8; 16],
+    pub file_type: u16,
+    pub machine: u16,
+    pub version: u32,
+    /// Virtual address of the entry point
+    pub entry: u64,
+    /// File offset of the program header table
+    pub program_header_offset: u64,
+    /// File offset of the section header table
+    pub section_header_offset: u64,
+    pub flags: u32,
+    pub header_size: u16,
+    /// Size of an entry of the program header table
+    pub program_header_size: u16,
+    /// Number of entries of the program header table
+    pub program_header_count: u16,
+    pub section_header_size: u16,
+    pub section_header_count: u16,
+    pub section_names_index: u16,
+}
+
+impl FileHeader {
+    pub const SIZE: usize = 64;
+}
+
+/// Types of program headers (`p_type`).
+pub mod segment_type {
+    /// Loadable segment
+    pub const LOAD: u32 = 1;
+    /// Dynamic linking information
+    pub const DYNAMIC: u32 = 2;
+    /// Path of the interpreter (dynamic linker)
+    pub const INTERPRETER: u32 = 3;
+    /// Location of the program header table itself
+    pub const PROGRAM_HEADERS: u32 = 6;
+}
+
+/// Permissions of segments (`p_flags`).
+pub mod segment_flags {
+    pub const EXECUTE: u32 = 1 << 0;
+    pub const WRITE: u32 = 1 << 1;
+    pub const READ: u32 = 1 << 2;
+}
+
+/// ELF64 program header, describing a segment.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct ProgramHeader {
+    /// See [`segment_type`]
+    pub segment_type: u32,
+    /// See [`segment_flags`]
+    pub flags: u32,
+    /// File offset of the contents of the segment
+    pub offset: u64,
+    /// Virtual address of the segment
+    pub virtual_address: u64,
+    pub physical_address: u64,
+    /// Size of the contents of the segment in the file
+    pub file_size: u64,
+    /// Size of the segment in memory, the bytes after its contents are zeros
+    pub memory_size: u64,
+    pub align: u64,
+}
+
+impl ProgramHeader {
+    pub const SIZE: usize = 56;
+}
+
+/// Plain data structures that can be read from any bytes.
+///
+/// # Safety
+///
+/// Every bit pattern must be a valid value of the type.
+pub(super) unsafe trait Pod: Copy {}
+
+unsafe impl Pod for FileHeader {}
+unsafe impl Pod for ProgramHeader {}
+
+/// Reads a `T` at `offset` in `source`, or returns [`None`] if `source` is too short.
+pub(super) fn read<T: Pod>(source: &(impl Source + ?Sized), offset: usize) -> Option<T> {
+    let mut value = MaybeUninit::<T>::zeroed();
+    // Safety: the bytes are those of `value`, and any bytes are a valid `T`
+    unsafe {
+        let bytes = slice::from_raw_parts_mut(value.as_mut_ptr().cast::<u8>(), size_of::<T>());
+        source.read_at(offset, bytes).then(|| value.assume_init())
+    }
+}
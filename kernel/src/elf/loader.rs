@@ -0,0 +1,37 @@
+use alloc::vec;
+
+use super::{Error, Executable, Source};
+use crate::memory::constants::PAGE_SIZE;
+use crate::memory::AddressSpace;
+
+/// Number of bytes of the file copied at once into the memory of the program.
+const CHUNK_SIZE: usize = PAGE_SIZE as usize;
+
+/// Maps the segments of `executable` in `address_space` with their permissions, filled with their
+/// contents read from `source` followed by zeros.
+///
+/// The contents are copied a chunk at a time, so that the file is never read whole.
+pub fn load(
+    executable: &Executable,
+    source: &(impl Source + ?Sized),
+    address_space: &mut AddressSpace,
+) -> Result<(), Error> {
+    let mut chunk = vec![0; CHUNK_SIZE];
+
+    for segment in executable.segments() {
+        address_space.map(segment.start, segment.memory_size, segment.flags)?;
+
+        let file_range = segment.file_range.clone();
+        for offset in file_range.clone().step_by(CHUNK_SIZE) {
+            let chunk = &mut chunk[..(file_range.end - offset).min(CHUNK_SIZE)];
+            if !source.read_at(offset, chunk) {
+                return Err(Error::Truncated);
+            }
+            address_space
+                .write(segment.start + (offset - file_range.start) as u64, chunk)
+                .expect("segment not mapped");
+        }
+    }
+
+    Ok(())
+}
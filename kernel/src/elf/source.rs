@@ -0,0 +1,27 @@
+/// Bytes of an executable file, which are read as they are needed so that the file is never
+/// copied whole.
+pub trait Source {
+    /// Returns the size of the file.
+    fn size(&self) -> usize;
+
+    /// Copies the bytes of the file at `offset` into `buffer`, and returns `false` if they are not
+    /// all in the file or cannot be read.
+    fn read_at(&self, offset: usize, buffer: &mut [u8]) -> bool;
+}
+
+impl Source for [u8] {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn read_at(&self, offset: usize, buffer: &mut [u8]) -> bool {
+        let end = offset.checked_add(buffer.len());
+        match end.and_then(|end| self.get(offset..end)) {
+            Some(bytes) => {
+                buffer.copy_from_slice(bytes);
+                true
+            }
+            None => false,
+        }
+    }
+}
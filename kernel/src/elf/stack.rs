@@ -0,0 +1,115 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use x86_64::VirtAddr;
+
+use super::header::ProgramHeader;
+use super::{Error, Executable};
+use crate::memory::constants::{PAGE_SIZE, USER_STACK_SIZE};
+
+/// Types of the entries of the auxiliary vector (`a_type`).
+pub mod auxiliary {
+    /// Ends the vector
+    pub const NULL: u64 = 0;
+    /// Address of the program headers
+    pub const PROGRAM_HEADERS: u64 = 3;
+    /// Size of a program header
+    pub const PROGRAM_HEADER_SIZE: u64 = 4;
+    /// Number of program headers
+    pub const PROGRAM_HEADER_COUNT: u64 = 5;
+    pub const PAGE_SIZE: u64 = 6;
+    /// Address of the interpreter, zero without one
+    pub const BASE: u64 = 7;
+    /// Entry point of the executable
+    pub const ENTRY: u64 = 9;
+    /// Address of 16 random bytes
+    pub const RANDOM: u64 = 25;
+}
+
+/// Largest part of the stack that the arguments and the environment can use.
+const MAX_SIZE: u64 = USER_STACK_SIZE / 2;
+
+/// Top of the stack with which a program starts.
+#[derive(Debug)]
+pub struct InitialStack {
+    /// Stack pointer, 16 bytes aligned
+    pub pointer: VirtAddr,
+    /// Bytes from the stack pointer to the end of the stack
+    pub contents: Vec<u8>,
+}
+
+/// Builds the initial stack of `executable` described by the System V ABI, for a stack that ends
+/// at `stack_end`.
+///
+/// The stack pointer points to the argument count, followed by the pointers to the arguments and
+/// to the environment variables, and by the auxiliary vector, each ending with a null entry. The
+/// strings and the random bytes they point to are at the end of the stack.
+pub fn initial_stack(
+    executable: &Executable,
+    stack_end: VirtAddr,
+    arguments: &[&str],
+    environment: &[&str],
+    random: [u8; 16],
+) -> Result<InitialStack, Error> {
+    let mut strings = Vec::from(random);
+    let mut string_offsets = Vec::new();
+    for string in arguments.iter().chain(environment) {
+        string_offsets.push(strings.len() as u64);
+        strings.extend_from_slice(string.as_bytes());
+        strings.push(0);
+    }
+
+    // argument count, both lists with their null entries, and 8 auxiliary entries of 2 words
+    let word_count = 1 + arguments.len() + 1 + environment.len() + 1 + 8 * 2;
+    if (strings.len() + word_count * 8 + 16) as u64 > MAX_SIZE {
+        return Err(Error::ArgumentsTooLong);
+    }
+
+    let strings_start = stack_end - strings.len() as u64;
+    let pointer = (strings_start - word_count as u64 * 8).align_down(16u64);
+
+    let (argument_offsets, environment_offsets) = string_offsets.split_at(arguments.len());
+    let mut words = Vec::with_capacity(word_count);
+    words.push(arguments.len() as u64);
+    words.extend(
+        argument_offsets
+            .iter()
+            .map(|offset| (strings_start + *offset).as_u64()),
+    );
+    words.push(0);
+    words.extend(
+        environment_offsets
+            .iter()
+            .map(|offset| (strings_start + *offset).as_u64()),
+    );
+    words.push(0);
+
+    let program_headers = executable.program_headers().map_or(0, VirtAddr::as_u64);
+    let auxiliary_vector = [
+        (auxiliary::PROGRAM_HEADERS, program_headers),
+        (auxiliary::PROGRAM_HEADER_SIZE, ProgramHeader::SIZE as u64),
+        (
+            auxiliary::PROGRAM_HEADER_COUNT,
+            executable.program_header_count() as u64,
+        ),
+        (auxiliary::PAGE_SIZE, PAGE_SIZE),
+        (auxiliary::BASE, 0),
+        (auxiliary::ENTRY, executable.entry().as_u64()),
+        (auxiliary::RANDOM, strings_start.as_u64()),
+        (auxiliary::NULL, 0),
+    ];
+    for (entry_type, value) in auxiliary_vector {
+        words.push(entry_type);
+        words.push(value);
+    }
+    debug_assert_eq!(words.len(), word_count);
+
+    let mut contents = vec![0; (stack_end - pointer) as usize];
+    for (chunk, word) in contents.chunks_exact_mut(8).zip(&words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    let strings_offset = contents.len() - strings.len();
+    contents[strings_offset..].copy_from_slice(&strings);
+
+    Ok(InitialStack { pointer, contents })
+}
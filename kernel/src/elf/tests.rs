@@ -0,0 +1,280 @@
+use alloc::vec::Vec;
+
+use x86_64::structures::paging::PageTableFlags;
+use x86_64::VirtAddr;
+
+use super::header::{
+    FileHeader, CLASS_64, DATA_LITTLE_ENDIAN, MACHINE_X86_64, MAGIC, TYPE_DYNAMIC, TYPE_EXECUTABLE,
+    VERSION_CURRENT,
+};
+use super::{
+    auxiliary, initial_stack, segment_flags, segment_type, Error, Executable, ProgramHeader, Source,
+};
+use crate::memory::constants::{USER_REGION_END, USER_REGION_START};
+
+/// Offset of the code in the files built by [`executable_with`], after the headers.
+const CODE_OFFSET: usize = FileHeader::SIZE + 2 * ProgramHeader::SIZE;
+
+fn bytes_of<T: Copy>(value: &T) -> &[u8] {
+    unsafe { core::slice::from_raw_parts((value as *const T).cast(), size_of::<T>()) }
+}
+
+/// Returns a statically linked executable that runs `code`, which must be position independent.
+pub(crate) fn executable(code: &[u8]) -> Vec<u8> {
+    executable_with(code, TYPE_EXECUTABLE, USER_REGION_START, |_| {})
+}
+
+/// Returns an executable of type `file_type` made of a single read-only and executable segment
+/// linked at `address`, that loads the headers followed by `code`.
+///
+/// The second program header is empty, and `edit` can change both before they are written.
+fn executable_with(
+    code: &[u8],
+    file_type: u16,
+    address: u64,
+    edit: impl FnOnce(&mut [ProgramHeader; 2]),
+) -> Vec<u8> {
+    let size = (CODE_OFFSET + code.len()) as u64;
+
+    let mut ident = [0; 16];
+    ident[..4].copy_from_slice(&MAGIC);
+    ident[4] = CLASS_64;
+    ident[5] = DATA_LITTLE_ENDIAN;
+    ident[6] = VERSION_CURRENT;
+    let header = FileHeader {
+        ident,
+        file_type,
+        machine: MACHINE_X86_64,
+        version: VERSION_CURRENT as u32,
+        entry: address + CODE_OFFSET as u64,
+        program_header_offset: FileHeader::SIZE as u64,
+        header_size: FileHeader::SIZE as u16,
+        program_header_size: ProgramHeader::SIZE as u16,
+        program_header_count: 2,
+        ..Default::default()
+    };
+
+    let mut program_headers = [
+        ProgramHeader {
+            segment_type: segment_type::LOAD,
+            flags: segment_flags::READ | segment_flags::EXECUTE,
+            offset: 0,
+            virtual_address: address,
+            physical_address: address,
+            file_size: size,
+            memory_size: size,
+            align: 0x1000,
+        },
+        ProgramHeader::default(),
+    ];
+    edit(&mut program_headers);
+
+    let mut file = Vec::from(bytes_of(&header));
+    for program_header in &program_headers {
+        file.extend_from_slice(bytes_of(program_header));
+    }
+    file.extend_from_slice(code);
+    file
+}
+
+#[test_case]
+fn test_header_sizes() {
+    assert_eq!(size_of::<FileHeader>(), FileHeader::SIZE);
+    assert_eq!(size_of::<ProgramHeader>(), ProgramHeader::SIZE);
+}
+
+#[test_case]
+fn test_parse_executable() {
+    let file = executable(&[0x90; 16]);
+    let executable = Executable::parse(file.as_slice()).unwrap();
+
+    let start = VirtAddr::new(USER_REGION_START);
+    assert_eq!(executable.entry(), start + CODE_OFFSET as u64);
+    assert_eq!(executable.segments().len(), 1);
+    assert_eq!(executable.segments()[0].start, start);
+    assert_eq!(executable.segments()[0].file_range, 0..file.len());
+    assert_eq!(
+        executable.segments()[0].flags,
+        PageTableFlags::empty(),
+        "read-only and executable"
+    );
+    assert_eq!(
+        executable.program_headers(),
+        Some(start + FileHeader::SIZE as u64)
+    );
+}
+
+#[test_case]
+fn test_position_independent_executable_is_relocated() {
+    let file = executable_with(&[0x90; 16], TYPE_DYNAMIC, 0, |_| {});
+    let executable = Executable::parse(file.as_slice()).unwrap();
+
+    let start = VirtAddr::new(USER_REGION_START);
+    assert_eq!(executable.segments()[0].start, start);
+    assert_eq!(executable.entry(), start + CODE_OFFSET as u64);
+}
+
+#[test_case]
+fn test_invalid_headers_are_rejected() {
+    let file = executable(&[0x90; 16]);
+
+    let mut bad_magic = file.clone();
+    bad_magic[0] = 0;
+    assert!(matches!(
+        Executable::parse(bad_magic.as_slice()),
+        Err(Error::BadMagic)
+    ));
+
+    let mut class_32 = file.clone();
+    class_32[4] = 1;
+    assert!(matches!(
+        Executable::parse(class_32.as_slice()),
+        Err(Error::Unsupported)
+    ));
+
+    assert!(matches!(
+        Executable::parse(&file[..FileHeader::SIZE + 8]),
+        Err(Error::Truncated)
+    ));
+}
+
+#[test_case]
+fn test_interpreter_is_rejected() {
+    let file = executable_with(&[0x90; 16], TYPE_EXECUTABLE, USER_REGION_START, |headers| {
+        headers[1].segment_type = segment_type::INTERPRETER;
+    });
+    assert!(matches!(
+        Executable::parse(file.as_slice()),
+        Err(Error::Interpreter)
+    ));
+}
+
+#[test_case]
+fn test_invalid_segments_are_rejected() {
+    // outside of the user region
+    let file = executable_with(&[0x90; 16], TYPE_EXECUTABLE, 0x40_0000, |_| {});
+    assert!(matches!(
+        Executable::parse(file.as_slice()),
+        Err(Error::BadSegment)
+    ));
+
+    let file = executable_with(&[0x90; 16], TYPE_EXECUTABLE, USER_REGION_START, |headers| {
+        headers[0].memory_size = 1;
+    });
+    assert!(matches!(
+        Executable::parse(file.as_slice()),
+        Err(Error::BadSegment)
+    ));
+
+    // sharing a page with the first segment
+    let file = executable_with(&[0x90; 16], TYPE_EXECUTABLE, USER_REGION_START, |headers| {
+        headers[1] = headers[0];
+        headers[1].virtual_address += 0x800;
+        headers[1].offset += 0x800;
+        headers[1].file_size = 0;
+    });
+    assert!(matches!(
+        Executable::parse(file.as_slice()),
+        Err(Error::BadSegment)
+    ));
+}
+
+#[test_case]
+fn test_entry_must_be_executable() {
+    let file = executable_with(&[0x90; 16], TYPE_EXECUTABLE, USER_REGION_START, |headers| {
+        headers[0].flags = segment_flags::READ | segment_flags::WRITE;
+    });
+    assert!(matches!(
+        Executable::parse(file.as_slice()),
+        Err(Error::BadEntry)
+    ));
+}
+
+#[test_case]
+fn test_initial_stack() {
+    let file = executable(&[0x90; 16]);
+    let executable = Executable::parse(file.as_slice()).unwrap();
+    let stack_end = VirtAddr::new(USER_REGION_END);
+
+    let stack = initial_stack(
+        &executable,
+        stack_end,
+        &["init", "-v"],
+        &["HOME=/"],
+        [7; 16],
+    )
+    .unwrap();
+    assert!(stack.pointer.is_aligned(16u64));
+    assert_eq!(stack.pointer + stack.contents.len() as u64, stack_end);
+
+    let word = |index: usize| {
+        let bytes = &stack.contents[index * 8..index * 8 + 8];
+        u64::from_le_bytes(bytes.try_into().unwrap())
+    };
+    let string = |addr: u64| {
+        let bytes = &stack.contents[(addr - stack.pointer.as_u64()) as usize..];
+        let length = bytes.iter().position(|&byte| byte == 0).unwrap();
+        core::str::from_utf8(&bytes[..length]).unwrap()
+    };
+
+    assert_eq!(word(0), 2);
+    assert_eq!(string(word(1)), "init");
+    assert_eq!(string(word(2)), "-v");
+    assert_eq!(word(3), 0);
+    assert_eq!(string(word(4)), "HOME=/");
+    assert_eq!(word(5), 0);
+
+    let auxiliary_vector: Vec<_> = (6..)
+        .step_by(2)
+        .map(|index| (word(index), word(index + 1)))
+        .take_while(|&(entry_type, _)| entry_type != auxiliary::NULL)
+        .collect();
+    let value = |entry_type| {
+        auxiliary_vector
+            .iter()
+            .find(|&&(other, _)| other == entry_type)
+            .unwrap()
+            .1
+    };
+    assert_eq!(value(auxiliary::ENTRY), executable.entry().as_u64());
+    assert_eq!(
+        value(auxiliary::PROGRAM_HEADERS),
+        executable.program_headers().unwrap().as_u64()
+    );
+    assert_eq!(value(auxiliary::PROGRAM_HEADER_COUNT), 2);
+
+    let random = (value(auxiliary::RANDOM) - stack.pointer.as_u64()) as usize;
+    assert_eq!(stack.contents[random..random + 16], [7; 16]);
+}
+
+#[test_case]
+fn test_too_many_arguments_are_rejected() {
+    let file = executable(&[0x90; 16]);
+    let executable = Executable::parse(file.as_slice()).unwrap();
+    let argument = "a".repeat(4096);
+    let arguments = [argument.as_str(); 16];
+
+    assert!(matches!(
+        initial_stack(
+            &executable,
+            VirtAddr::new(USER_REGION_END),
+            &arguments,
+            &[],
+            [0; 16]
+        ),
+        Err(Error::ArgumentsTooLong)
+    ));
+}
+
+#[test_case]
+fn test_slice_source() {
+    let file = [1, 2, 3, 4];
+    let mut buffer = [0; 2];
+
+    assert!(file.read_at(2, &mut buffer));
+    assert_eq!(buffer, [3, 4]);
+    // reads past the end of the file fail
+    assert!(!file.read_at(3, &mut buffer));
+    assert!(!file.read_at(usize::MAX, &mut buffer));
+    assert_eq!(file.size(), 4);
+}
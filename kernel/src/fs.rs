@@ -0,0 +1,10 @@
+mod file;
+mod inner;
+mod path;
+
+pub use file::{FileTable, OpenFile};
+pub use inner::{contents, entries, init, lookup, read_at, root, Error, WorkingDirectory};
+pub use path::join;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,179 @@
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use drivers::fs::ext2::{Inode, Type};
+use drivers::print;
+
+use super::inner::{lookup, read_at, Error, WorkingDirectory};
+use crate::keyboard;
+use crate::sync::Mutex;
+
+/// Maximum number of bytes written to the screen by one call, the rest being left to the next
+/// ones like a short write.
+const SCREEN_WRITE_MAX: usize = 4096;
+/// Number of bytes of text printed at once.
+const PRINT_CHUNK_SIZE: usize = 256;
+
+/// A file opened by a process, shared by the file descriptors duplicated from the one that
+/// opened it, which also share its offset.
+#[derive(Debug)]
+pub enum OpenFile {
+    /// Standard input, the characters typed on the keyboard.
+    Keyboard,
+    /// Standard output and error, printed on the screen.
+    Screen,
+    /// Regular file of the root file system, opened for reading.
+    ///
+    /// Its blocks are read as the file is, so that it is never copied whole.
+    File {
+        inode: &'static Inode,
+        offset: Mutex<usize>,
+    },
+}
+
+impl OpenFile {
+    /// Opens the regular file `path` for reading, relative to `directory` unless it is absolute.
+    pub fn open(path: &str, directory: &WorkingDirectory) -> Result<Self, Error> {
+        let inode = lookup(path, directory)?;
+        match inode.file_type() {
+            Type::File => {}
+            Type::Directory => return Err(Error::IsADirectory),
+            _ => return Err(Error::NotFound),
+        }
+
+        Ok(Self::File {
+            inode,
+            offset: Mutex::new("file offset", 0),
+        })
+    }
+
+    /// Reads from the file into `buffer`, and returns the number of bytes read, which is 0 at the
+    /// end of the file.
+    ///
+    /// Reading the keyboard waits for characters to be typed.
+    pub fn read(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        match self {
+            Self::Keyboard => Ok(keyboard::read(buffer)),
+            Self::Screen => Err(Error::NotReadable),
+            Self::File { inode, offset } => {
+                let mut offset = offset.lock();
+                let count = read_at(inode, *offset, buffer)?;
+
+                *offset += count;
+                Ok(count)
+            }
+        }
+    }
+
+    /// Writes `buffer` to the file, and returns the number of bytes written.
+    pub fn write(&self, buffer: &[u8]) -> Result<usize, Error> {
+        match self {
+            Self::Screen => {
+                let mut count = buffer.len().min(SCREEN_WRITE_MAX);
+                // A char cut by the limit is left to the next call, as long as it is a char
+                while count < buffer.len()
+                    && count > SCREEN_WRITE_MAX - 3
+                    && is_continuation(buffer[count])
+                {
+                    count -= 1;
+                }
+                print_lossy(&buffer[..count]);
+                Ok(count)
+            }
+            Self::Keyboard => Err(Error::NotWritable),
+            Self::File { .. } => Err(Error::ReadOnly),
+        }
+    }
+
+    /// Returns `true` if the file can be read.
+    pub fn is_readable(&self) -> bool {
+        !matches!(self, Self::Screen)
+    }
+
+    /// Returns `true` if the file can be written.
+    pub fn is_writable(&self) -> bool {
+        matches!(self, Self::Screen)
+    }
+
+    /// Returns the inode of a file of the file system.
+    pub fn inode(&self) -> Option<&'static Inode> {
+        match self {
+            Self::File { inode, .. } => Some(inode),
+            Self::Keyboard | Self::Screen => None,
+        }
+    }
+}
+
+/// Prints `bytes` as UTF-8 in chunks, without allocating, with U+FFFD for the invalid sequences.
+fn print_lossy(bytes: &[u8]) {
+    for chunk in bytes.utf8_chunks() {
+        let mut valid = chunk.valid();
+        while !valid.is_empty() {
+            let mut end = valid.len().min(PRINT_CHUNK_SIZE);
+            while !valid.is_char_boundary(end) {
+                end -= 1;
+            }
+            print!("{}", &valid[..end]);
+            valid = &valid[end..];
+        }
+        if !chunk.invalid().is_empty() {
+            print!("{}", char::REPLACEMENT_CHARACTER);
+        }
+    }
+}
+
+/// Returns `true` if `byte` continues a UTF-8 sequence.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+/// Files opened by a process, indexed by their file descriptor.
+///
+/// Cloning the table duplicates the file descriptors, which keep sharing their open files.
+#[derive(Debug, Clone, Default)]
+pub struct FileTable {
+    files: Vec<Option<Arc<OpenFile>>>,
+}
+
+impl FileTable {
+    /// Maximum number of file descriptors of a process.
+    pub const MAX_FILES: usize = 64;
+
+    /// Returns a table with the standard streams: the keyboard as file descriptor 0, and the
+    /// screen as 1 and 2.
+    pub fn standard() -> Self {
+        let screen = Arc::new(OpenFile::Screen);
+        Self {
+            files: vec![
+                Some(Arc::new(OpenFile::Keyboard)),
+                Some(screen.clone()),
+                Some(screen),
+            ],
+        }
+    }
+
+    /// Returns the file opened as `fd`.
+    pub fn get(&self, fd: usize) -> Option<Arc<OpenFile>> {
+        self.files.get(fd)?.clone()
+    }
+
+    /// Adds `file` as the lowest file descriptor that is free, and returns it.
+    pub fn insert(&mut self, file: Arc<OpenFile>) -> Result<usize, Error> {
+        if let Some(fd) = self.files.iter().position(Option::is_none) {
+            self.files[fd] = Some(file);
+            return Ok(fd);
+        }
+        if self.files.len() == Self::MAX_FILES {
+            return Err(Error::TooManyFiles);
+        }
+
+        self.files.push(Some(file));
+        Ok(self.files.len() - 1)
+    }
+
+    /// Closes `fd`, and returns its file if it was open.
+    pub fn remove(&mut self, fd: usize) -> Option<Arc<OpenFile>> {
+        self.files.get_mut(fd)?.take()
+    }
+}
@@ -0,0 +1,157 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use drivers::fs::ext2::{Ext2, Inode, Type};
+use drivers::fs::traits::FileSystem;
+use spin::Once;
+use utils::posix::path::PathBuf;
+use x86_64::VirtAddr;
+
+use super::path;
+
+/// The root file system, mounted by [`init`].
+static ROOT: Once<Ext2> = Once::new();
+
+/// Errors of the file system operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// No file has this path, or no file system is mounted.
+    NotFound,
+    /// A directory was expected.
+    NotADirectory,
+    /// A regular file was expected.
+    IsADirectory,
+    /// The file system cannot be written to.
+    ReadOnly,
+    /// The file was not opened for reading.
+    NotReadable,
+    /// The file was not opened for writing.
+    NotWritable,
+    /// The file descriptor table is full.
+    TooManyFiles,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotFound => "no such file or directory",
+            Self::NotADirectory => "not a directory",
+            Self::IsADirectory => "is a directory",
+            Self::ReadOnly => "read-only file system",
+            Self::NotReadable | Self::NotWritable => "bad file descriptor",
+            Self::TooManyFiles => "too many open files",
+        })
+    }
+}
+
+/// Mounts the ext2 volume loaded by the bootloader as its ramdisk, at `ramdisk_start`, as the
+/// root file system.
+///
+/// Returns `false` if there is no ramdisk or if it does not hold an ext2 volume, in which case
+/// every lookup fails.
+pub fn init(ramdisk_start: Option<u64>, ramdisk_size: u64) -> bool {
+    let Some(start) = ramdisk_start else {
+        return false;
+    };
+    // the superblock ends 2 KiB after the start of the volume
+    if ramdisk_size < 2048 {
+        return false;
+    }
+
+    // Safety: the bootloader maps the whole ramdisk, and nothing else uses it
+    match unsafe { Ext2::new(VirtAddr::new(start)) } {
+        Some(ext2) => {
+            ROOT.call_once(|| ext2);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the root file system, if one is mounted.
+pub fn root() -> Option<&'static Ext2> {
+    ROOT.r#try()
+}
+
+/// Returns the inode of `path`, relative to `directory` unless it is absolute.
+pub fn lookup(path: &str, directory: &WorkingDirectory) -> Result<&'static Inode, Error> {
+    let root = root().ok_or(Error::NotFound)?;
+    let current_directory = if path.starts_with('/') {
+        None
+    } else {
+        directory.inode
+    };
+
+    root.read(PathBuf::from(path), current_directory)
+        .ok_or(Error::NotFound)
+}
+
+/// Returns the contents of the regular file `inode`.
+pub fn contents(inode: &Inode) -> Result<Vec<u8>, Error> {
+    if let Type::Directory = inode.file_type() {
+        return Err(Error::IsADirectory);
+    }
+
+    let root = root().ok_or(Error::NotFound)?;
+    root.contents(inode).ok_or(Error::NotFound)
+}
+
+/// Copies the bytes of the regular file `inode` at `offset` into `buffer`, and returns their
+/// number, which is 0 at the end of the file.
+///
+/// Only the blocks that hold the bytes are read, so large files can be read without copying them
+/// whole.
+pub fn read_at(inode: &Inode, offset: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+    if let Type::Directory = inode.file_type() {
+        return Err(Error::IsADirectory);
+    }
+
+    let root = root().ok_or(Error::NotFound)?;
+    root.read_at(inode, offset, buffer).ok_or(Error::NotFound)
+}
+
+/// Returns the names and inodes of the entries of the directory `inode`, including `.` and `..`.
+pub fn entries(inode: &'static Inode) -> Result<Vec<(String, &'static Inode)>, Error> {
+    let root = root().ok_or(Error::NotFound)?;
+    root.entries(inode).ok_or(Error::NotADirectory)
+}
+
+/// Directory from which the relative paths of a process are looked up.
+#[derive(Debug, Clone)]
+pub struct WorkingDirectory {
+    /// Absolute path, without `.` and `..` components
+    path: String,
+    /// `None` when no file system is mounted
+    inode: Option<&'static Inode>,
+}
+
+impl WorkingDirectory {
+    /// Returns the root directory.
+    pub fn root() -> Self {
+        Self {
+            path: String::from("/"),
+            inode: root().and_then(|root| root.read(PathBuf::from("/"), None)),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn inode(&self) -> Option<&'static Inode> {
+        self.inode
+    }
+
+    /// Changes to the directory `path`, relative to this one unless it is absolute.
+    pub fn change(&mut self, path: &str) -> Result<(), Error> {
+        let inode = lookup(path, self)?;
+        let Type::Directory = inode.file_type() else {
+            return Err(Error::NotADirectory);
+        };
+
+        self.path = path::join(&self.path, path);
+        self.inode = Some(inode);
+        Ok(())
+    }
+}
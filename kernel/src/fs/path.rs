@@ -0,0 +1,32 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Returns the absolute path of `path` relative to the absolute path `base`, without `.` and
+/// `..` components.
+///
+/// `..` is resolved by removing the previous component, as the file system has no symbolic
+/// links, and `..` of the root is the root.
+pub fn join(base: &str, path: &str) -> String {
+    let mut components = Vec::new();
+    let base = if path.starts_with('/') { "" } else { base };
+
+    for component in base.split('/').chain(path.split('/')) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+
+    let mut joined = String::new();
+    for component in &components {
+        joined.push('/');
+        joined.push_str(component);
+    }
+    if joined.is_empty() {
+        joined.push('/');
+    }
+    joined
+}
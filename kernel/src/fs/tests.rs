@@ -0,0 +1,53 @@
+use alloc::sync::Arc;
+
+use super::{join, Error, FileTable, OpenFile};
+
+#[test_case]
+fn test_join() {
+    assert_eq!(join("/", "bin"), "/bin");
+    assert_eq!(join("/usr", "bin/"), "/usr/bin");
+    assert_eq!(join("/usr/bin", ".."), "/usr");
+    assert_eq!(join("/usr", "./../../etc//passwd"), "/etc/passwd");
+    assert_eq!(join("/usr", "/bin"), "/bin");
+    assert_eq!(join("/", ".."), "/");
+}
+
+#[test_case]
+fn test_standard_files() {
+    let files = FileTable::standard();
+    assert!(files.get(0).unwrap().is_readable());
+    assert!(files.get(1).unwrap().is_writable());
+    assert!(files.get(2).unwrap().is_writable());
+    assert!(files.get(3).is_none());
+}
+
+#[test_case]
+fn test_screen_write_is_bounded() {
+    // a char cut by the limit on the length of a write is left to the next one
+    let mut text = alloc::vec![b'a'];
+    text.extend("é".repeat(4096).bytes());
+    assert_eq!(OpenFile::Screen.write(&text), Ok(4095));
+    assert_eq!(OpenFile::Screen.write(b"\xff\xfe\n"), Ok(3));
+}
+
+#[test_case]
+fn test_lowest_free_descriptor() {
+    let mut files = FileTable::standard();
+    assert!(files.remove(1).is_some());
+    assert!(files.remove(1).is_none());
+
+    assert_eq!(files.insert(Arc::new(OpenFile::Screen)), Ok(1));
+    assert_eq!(files.insert(Arc::new(OpenFile::Screen)), Ok(3));
+}
+
+#[test_case]
+fn test_too_many_files() {
+    let mut files = FileTable::default();
+    for fd in 0..FileTable::MAX_FILES {
+        assert_eq!(files.insert(Arc::new(OpenFile::Screen)), Ok(fd));
+    }
+    assert_eq!(
+        files.insert(Arc::new(OpenFile::Screen)),
+        Err(Error::TooManyFiles)
+    );
+}
@@ -1,4 +1,7 @@
 mod inner;
 pub mod tss;
 
-pub use inner::init;
+pub use inner::{
+    init, init_application_processor, KERNEL_CODE_SELECTOR, KERNEL_DATA_SELECTOR,
+    USER_CODE_SELECTOR, USER_DATA_SELECTOR,
+};
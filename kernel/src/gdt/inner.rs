@@ -1,37 +1,82 @@
+use alloc::boxed::Box;
+use core::ptr::{addr_of, addr_of_mut};
+
 use lazy_static::lazy_static;
 use x86_64::instructions::segmentation::{Segment, CS};
 use x86_64::instructions::tables;
 use x86_64::registers::segmentation::SS;
 use x86_64::structures::gdt::{Descriptor, GlobalDescriptorTable, SegmentSelector};
+use x86_64::structures::paging::mapper::MapToError;
+use x86_64::structures::paging::Size4KiB;
+use x86_64::structures::tss::TaskStateSegment;
+use x86_64::PrivilegeLevel;
 
-use crate::gdt::tss::TSS;
+use crate::gdt::tss::{self, TSS};
 
-struct Selectors {
-    code_selector: SegmentSelector,
-    tss_selector: SegmentSelector,
-}
+// The user segments follow the kernel ones in the order that `sysret` expects: it loads the data
+// segment right after the one in `STAR`, and the code segment after it.
+pub const KERNEL_CODE_SELECTOR: SegmentSelector = SegmentSelector::new(1, PrivilegeLevel::Ring0);
+pub const KERNEL_DATA_SELECTOR: SegmentSelector = SegmentSelector::new(2, PrivilegeLevel::Ring0);
+pub const USER_DATA_SELECTOR: SegmentSelector = SegmentSelector::new(3, PrivilegeLevel::Ring3);
+pub const USER_CODE_SELECTOR: SegmentSelector = SegmentSelector::new(4, PrivilegeLevel::Ring3);
+const TSS_SELECTOR: SegmentSelector = SegmentSelector::new(5, PrivilegeLevel::Ring0);
 
 lazy_static! {
-    static ref GDT: (GlobalDescriptorTable, Selectors) = {
-        let mut gdt = GlobalDescriptorTable::new();
-        let code_selector = gdt.add_entry(Descriptor::kernel_code_segment());
-        let tss_selector = gdt.add_entry(Descriptor::tss_segment(&TSS));
-        (
-            gdt,
-            Selectors {
-                code_selector,
-                tss_selector,
-            },
-        )
-    };
+    /// GDT of the bootstrap processor, the application processors get their own in
+    /// [`init_application_processor`].
+    static ref GDT: GlobalDescriptorTable = new_gdt(unsafe { &*addr_of!(TSS) });
+}
+
+/// Builds a GDT with the kernel and user segments and `tss`, which can only be used by one
+/// processor.
+fn new_gdt(tss: &'static TaskStateSegment) -> GlobalDescriptorTable {
+    let mut gdt = GlobalDescriptorTable::new();
+    let selectors = [
+        gdt.add_entry(Descriptor::kernel_code_segment()),
+        gdt.add_entry(Descriptor::kernel_data_segment()),
+        gdt.add_entry(Descriptor::user_data_segment()),
+        gdt.add_entry(Descriptor::user_code_segment()),
+        gdt.add_entry(Descriptor::tss_segment(tss)),
+    ];
+    debug_assert_eq!(
+        selectors.map(|selector| selector.0),
+        [
+            KERNEL_CODE_SELECTOR.0,
+            KERNEL_DATA_SELECTOR.0,
+            USER_DATA_SELECTOR.0,
+            USER_CODE_SELECTOR.0,
+            TSS_SELECTOR.0
+        ]
+    );
+
+    gdt
 }
 
-pub fn init() {
-    GDT.0.load();
+fn load(gdt: &'static GlobalDescriptorTable) {
+    gdt.load();
 
     unsafe {
-        CS::set_reg(GDT.1.code_selector);
+        CS::set_reg(KERNEL_CODE_SELECTOR);
         SS::set_reg(SegmentSelector::NULL);
-        tables::load_tss(GDT.1.tss_selector);
+        tables::load_tss(TSS_SELECTOR);
     }
 }
+
+/// Loads the GDT and TSS of the bootstrap processor, and returns the TSS.
+pub fn init() -> *mut TaskStateSegment {
+    tss::init();
+    load(&GDT);
+
+    addr_of_mut!(TSS)
+}
+
+/// Loads a new GDT and TSS on the current application processor, with interrupt stacks that have
+/// a guard page, and returns the TSS.
+///
+/// Must be called once memory management is initialized.
+pub fn init_application_processor() -> Result<*mut TaskStateSegment, MapToError<Size4KiB>> {
+    let tss = Box::into_raw(Box::new(tss::new_guarded()?));
+    load(Box::leak(Box::new(new_gdt(unsafe { &*tss }))));
+
+    Ok(tss)
+}
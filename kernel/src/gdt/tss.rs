@@ -1,22 +1,62 @@
-use lazy_static::lazy_static;
+use core::ptr::{addr_of, addr_of_mut};
+
+use x86_64::structures::paging::mapper::MapToError;
+use x86_64::structures::paging::Size4KiB;
 use x86_64::structures::tss::TaskStateSegment;
 use x86_64::VirtAddr;
 
+use crate::memory::{constants::KERNEL_STACK_SIZE, stack};
+
 pub const DOUBLE_FAULT_IST_INDEX: u16 = 0;
+pub const PAGE_FAULT_IST_INDEX: u16 = 1;
+
+const BOOT_STACK_SIZE: usize = 4096 * 5;
+
+/// Stacks used by the IST entries until [`init_stacks`] replaces them with guarded ones.
+static mut BOOT_STACKS: [[u8; BOOT_STACK_SIZE]; 2] = [[0; BOOT_STACK_SIZE]; 2];
+
+/// TSS of the bootstrap processor.
+pub(super) static mut TSS: TaskStateSegment = TaskStateSegment::new();
+
+pub(super) fn init() {
+    for index in [DOUBLE_FAULT_IST_INDEX, PAGE_FAULT_IST_INDEX] {
+        let stack_start = VirtAddr::from_ptr(unsafe { addr_of!(BOOT_STACKS[index as usize]) });
+        let stack_end = stack_start + BOOT_STACK_SIZE;
 
-lazy_static! {
-    pub(super) static ref TSS: TaskStateSegment = {
-        let mut tss = TaskStateSegment::new();
+        unsafe { set_interrupt_stack(index, stack_end) };
+    }
+}
+
+/// Replaces the boot IST stacks with stacks that have a guard page.
+///
+/// Must be called once memory management is initialized.
+pub fn init_stacks() -> Result<(), MapToError<Size4KiB>> {
+    let double_fault_stack = stack::allocate("double fault handler", KERNEL_STACK_SIZE)?;
+    let page_fault_stack = stack::allocate("page fault handler", KERNEL_STACK_SIZE)?;
+
+    x86_64::instructions::interrupts::without_interrupts(|| unsafe {
+        set_interrupt_stack(DOUBLE_FAULT_IST_INDEX, double_fault_stack.end());
+        set_interrupt_stack(PAGE_FAULT_IST_INDEX, page_fault_stack.end());
+    });
 
-        tss.interrupt_stack_table[DOUBLE_FAULT_IST_INDEX as usize] = {
-            const STACK_SIZE: usize = 4096 * 5;
-            static mut STACK: [u8; STACK_SIZE] = [0; STACK_SIZE];
+    Ok(())
+}
+
+/// Creates a TSS for an application processor, with its own guarded interrupt stacks.
+pub(super) fn new_guarded() -> Result<TaskStateSegment, MapToError<Size4KiB>> {
+    let mut tss = TaskStateSegment::new();
+    tss.interrupt_stack_table[DOUBLE_FAULT_IST_INDEX as usize] =
+        stack::allocate("double fault handler", KERNEL_STACK_SIZE)?.end();
+    tss.interrupt_stack_table[PAGE_FAULT_IST_INDEX as usize] =
+        stack::allocate("page fault handler", KERNEL_STACK_SIZE)?.end();
 
-            let stack_start = VirtAddr::from_ptr(&raw const STACK);
-            let stack_end = stack_start + STACK_SIZE;
-            stack_end
-        };
+    Ok(tss)
+}
 
-        tss
-    };
+/// Safety: the stack must stay valid for as long as the IST entry is used, and it must not be
+/// replaced while an interrupt is running on it.
+unsafe fn set_interrupt_stack(index: u16, stack_end: VirtAddr) {
+    unsafe {
+        (*addr_of_mut!(TSS)).interrupt_stack_table[index as usize] = stack_end;
+    }
 }
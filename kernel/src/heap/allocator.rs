@@ -1,4 +1,52 @@
+use core::alloc::{GlobalAlloc, Layout};
+use core::ops::Deref;
+
 use linked_list_allocator::LockedHeap;
+use x86_64::instructions::interrupts;
 
 #[global_allocator]
-pub static ALLOCATOR: LockedHeap = LockedHeap::empty();
+pub static ALLOCATOR: InterruptSafeHeap = InterruptSafeHeap(LockedHeap::empty());
+
+/// A [`LockedHeap`] that disables interrupts while it is locked, so that interrupt handlers can
+/// allocate without deadlocking on the code they interrupted.
+pub struct InterruptSafeHeap(LockedHeap);
+
+impl Deref for InterruptSafeHeap {
+    type Target = LockedHeap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Sizes in bytes of the parts of the heap.
+#[derive(Debug, Clone, Copy)]
+pub struct HeapUsage {
+    pub size: usize,
+    pub used: usize,
+    pub free: usize,
+}
+
+impl InterruptSafeHeap {
+    /// Returns how much of the heap is allocated.
+    pub fn usage(&self) -> HeapUsage {
+        interrupts::without_interrupts(|| {
+            let heap = self.0.lock();
+            HeapUsage {
+                size: heap.size(),
+                used: heap.used(),
+                free: heap.free(),
+            }
+        })
+    }
+}
+
+unsafe impl GlobalAlloc for InterruptSafeHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        interrupts::without_interrupts(|| unsafe { self.0.alloc(layout) })
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        interrupts::without_interrupts(|| unsafe { self.0.dealloc(ptr, layout) })
+    }
+}
@@ -1,2 +1,4 @@
 pub const HEAP_START: usize = 0x_4444_4444_0000;
-pub const HEAP_SIZE: usize = 100 * 1024; // 100 KiB
+/// Holds the cells of the terminal, about 260 KiB on a 1920x1080 framebuffer, with room for the
+/// rest of the kernel.
+pub const HEAP_SIZE: usize = 1024 * 1024; // 1 MiB
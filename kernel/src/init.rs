@@ -1,14 +1,24 @@
 use bootloader_api::info::BootInfo;
-use drivers::display::frame_buffer;
-use x86_64::VirtAddr;
+use drivers::console::{self, SerialConsole};
+use drivers::display::frame_buffer::{self, Font, FrameBufferConsole, WRITER};
+use drivers::display::graphics::DoubleBuffer;
+use drivers::display::vga_buffer::{self, VgaConsole};
+use drivers::serial::{COM1, DEFAULT_BAUD_RATE};
+use x86_64::{PhysAddr, VirtAddr};
 
-use crate::{gdt, heap, interrupts, memory};
+use crate::{
+    acpi, config, fs, gdt, heap, interrupts, keyboard, logger, memory, ps2, serial, smp, syscall,
+    task, thread, time,
+};
 
 pub fn init(boot_info: &'static mut BootInfo) {
-    gdt::init();
+    // The boot is logged on the first serial port, if there is one
+    if COM1.lock().init(DEFAULT_BAUD_RATE).is_ok() {
+        console::register(&SerialConsole).expect("serial console registration failed");
+    }
+    logger::init();
+    let tss = gdt::init();
     interrupts::idt::init();
-    interrupts::pics::init();
-    x86_64::instructions::interrupts::enable();
 
     let phys_mem_offset = VirtAddr::new(boot_info.physical_memory_offset.into_option().unwrap());
     let mut mapper = unsafe { memory::init(phys_mem_offset) };
@@ -16,8 +26,67 @@ pub fn init(boot_info: &'static mut BootInfo) {
         unsafe { memory::BootInfoFrameAllocator::init(&boot_info.memory_regions) };
 
     heap::init(&mut mapper, &mut frame_allocator).expect("heap initialization failed");
+    memory::global::init(mapper, frame_allocator);
+    fs::init(boot_info.ramdisk_addr.into_option(), boot_info.ramdisk_len);
+    config::init();
+    logger::configure();
+    gdt::tss::init_stacks().expect("interrupt stacks initialization failed");
+    smp::init(tss);
+    syscall::init();
+
+    let rsdp_address = boot_info.rsdp_addr.into_option().expect("no RSDP found");
+    acpi::init(PhysAddr::new(rsdp_address));
+    interrupts::init();
+    time::init();
+    ps2::init();
+    serial::init();
+    thread::init();
+    task::executor::init();
+    task::spawn(keyboard::decode_keypresses());
+    x86_64::instructions::interrupts::enable();
 
     if let Some(frame_buffer) = boot_info.framebuffer.take() {
-        frame_buffer::init(frame_buffer);
+        let back_buffer = memory::buffer::allocate(DoubleBuffer::back_size(&frame_buffer.info()))
+            .expect("back buffer allocation failed");
+        frame_buffer::init(frame_buffer, back_buffer);
+        logger::replay(&FrameBufferConsole);
+        configure_font();
+    } else {
+        // The bootloader leaves the screen in text mode, as in BIOS text boots
+        unsafe { vga_buffer::init(phys_mem_offset) };
+        logger::replay(&VgaConsole);
     }
+
+    smp::start_application_processors();
+}
+
+/// Options of the boot configuration that set the font of the screen, in pixels and by name.
+const FONT_SIZE_KEY: &str = "console.font.size";
+const FONT_WEIGHT_KEY: &str = "console.font.weight";
+
+/// Draws the text on the screen in the font set by the boot configuration.
+fn configure_font() {
+    let Some(writer) = WRITER.r#try() else {
+        return;
+    };
+    let (size, weight) = (config::get(FONT_SIZE_KEY), config::get(FONT_WEIGHT_KEY));
+    if size.is_none() && weight.is_none() {
+        return;
+    }
+    let mut font = writer.lock().font();
+
+    if let Some(size) = size {
+        match size.parse().ok().and_then(Font::height_from_pixels) {
+            Some(height) => font.height = height,
+            None => log::warn!("unknown font size `{size}`, using the default one"),
+        }
+    }
+    if let Some(weight) = weight {
+        match Font::weight_from_name(weight) {
+            Some(weight) => font.weight = weight,
+            None => log::warn!("unknown font weight `{weight}`, using the default one"),
+        }
+    }
+
+    writer.lock().set_font(font);
 }
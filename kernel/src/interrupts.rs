@@ -1,7 +1,15 @@
-mod handlers;
+pub mod apic;
+pub mod crash_report;
+pub mod exception;
+pub(crate) mod handlers;
 pub mod idt;
-mod index;
+mod init;
+pub mod irq;
 pub mod pics;
+pub mod trap_frame;
+pub mod vectors;
+
+pub use init::init;
 
 #[cfg(test)]
 mod tests;
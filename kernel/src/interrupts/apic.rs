@@ -0,0 +1,6 @@
+mod inner;
+mod io_apic;
+mod local_apic;
+
+pub use inner::{end_of_interrupt, init, init_application_processor, local_apic, route_isa_irq};
+pub use local_apic::LocalApic;
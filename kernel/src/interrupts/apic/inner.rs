@@ -0,0 +1,152 @@
+use alloc::vec::Vec;
+
+use spin::{Mutex, Once};
+use x86_64::PhysAddr;
+
+use super::io_apic::{IoApic, RedirectionEntry};
+use super::local_apic::LocalApic;
+use crate::acpi::find_table;
+use crate::acpi::madt::{Entry, Madt, Polarity, TriggerMode};
+use crate::interrupts::vectors::LOCAL_TIMER_VECTOR;
+use crate::memory::global::phys_to_virt;
+use crate::time::constants::TICK_FREQUENCY;
+use crate::time::pit;
+
+/// Number of timer measurements per second during the calibration of the local APIC timer.
+const CALIBRATION_FREQUENCY: u32 = 100;
+
+static LOCAL_APIC: Once<LocalApic> = Once::new();
+static IO_APICS: Once<Vec<Mutex<IoApic>>> = Once::new();
+/// How each ISA IRQ is wired to the I/O APICs.
+static ISA_ROUTES: Once<[IsaRoute; 16]> = Once::new();
+/// LINT pin on which every processor receives non-maskable interrupts, as described by the MADT.
+static NMI_LINT: Once<Option<(u8, Polarity, TriggerMode)>> = Once::new();
+/// Initial count of the local APIC timer that makes it fire at [`TICK_FREQUENCY`].
+static TIMER_INITIAL_COUNT: Once<u32> = Once::new();
+
+/// Global System Interrupt, polarity and trigger mode of an ISA IRQ.
+#[derive(Debug, Clone, Copy)]
+struct IsaRoute {
+    gsi: u32,
+    polarity: Polarity,
+    trigger_mode: TriggerMode,
+}
+
+/// Enables the local APIC of the bootstrap processor and masks every I/O APIC input, using the
+/// interrupt controllers described by the MADT.
+///
+/// The local APIC timer is calibrated against the PIT, for the application processors.
+///
+/// Must be called once the ACPI tables are located and the legacy PICs are masked.
+pub fn init() {
+    let madt = find_table::<Madt>().expect("no MADT in the ACPI tables");
+
+    let mut local_apic_address = madt.local_apic_address as u64;
+    let mut io_apics = Vec::new();
+    // ISA IRQs are identity mapped to GSIs, active high and edge triggered, unless overridden
+    let mut isa_routes = core::array::from_fn(|irq| IsaRoute {
+        gsi: irq as u32,
+        polarity: Polarity::ActiveHigh,
+        trigger_mode: TriggerMode::Edge,
+    });
+    let mut nmi_lint = None;
+
+    for entry in madt.entries() {
+        match entry {
+            Entry::IoApic(io_apic) => {
+                let base = phys_to_virt(PhysAddr::new(io_apic.address as u64));
+                let io_apic = unsafe { IoApic::new(base, io_apic.global_system_interrupt_base) };
+                io_apics.push(Mutex::new(io_apic));
+            }
+            Entry::InterruptSourceOverride(source_override) if source_override.bus == 0 => {
+                let Some(route) = isa_routes.get_mut(source_override.source as usize) else {
+                    continue;
+                };
+
+                let flags = source_override.flags;
+                route.gsi = source_override.global_system_interrupt;
+                if flags.polarity() != Polarity::BusDefault {
+                    route.polarity = flags.polarity();
+                }
+                if flags.trigger_mode() != TriggerMode::BusDefault {
+                    route.trigger_mode = flags.trigger_mode();
+                }
+            }
+            Entry::LocalApicNmi(nmi) => {
+                let flags = nmi.flags;
+                nmi_lint = Some((nmi.lint, flags.polarity(), flags.trigger_mode()));
+            }
+            Entry::LocalApicAddressOverride(address_override) => {
+                local_apic_address = address_override.address;
+            }
+            _ => {}
+        }
+    }
+
+    for io_apic in &io_apics {
+        io_apic.lock().mask_all();
+    }
+
+    let local_apic = LOCAL_APIC
+        .call_once(|| unsafe { LocalApic::new(phys_to_virt(PhysAddr::new(local_apic_address))) });
+    local_apic.enable(nmi_lint);
+
+    let elapsed = local_apic
+        .measure_timer(|| pit::wait((pit::BASE_FREQUENCY / CALIBRATION_FREQUENCY) as u16));
+    let initial_count = elapsed as u64 * CALIBRATION_FREQUENCY as u64 / TICK_FREQUENCY as u64;
+    TIMER_INITIAL_COUNT.call_once(|| initial_count.clamp(1, u32::MAX as u64) as u32);
+
+    NMI_LINT.call_once(|| nmi_lint);
+    IO_APICS.call_once(|| io_apics);
+    ISA_ROUTES.call_once(|| isa_routes);
+}
+
+/// Enables the local APIC of an application processor like the one of the bootstrap processor,
+/// and starts its timer at [`TICK_FREQUENCY`] to preempt the threads it runs.
+///
+/// Must be called on the application processor, once [`init`] is called on the bootstrap one.
+pub fn init_application_processor() {
+    let local_apic = local_apic().expect("APIC not initialized");
+    local_apic.enable(*NMI_LINT.r#try().expect("APIC not initialized"));
+
+    let initial_count = *TIMER_INITIAL_COUNT.r#try().expect("APIC not initialized");
+    local_apic.start_periodic_timer(LOCAL_TIMER_VECTOR, initial_count);
+}
+
+/// Returns the local APIC, once [`init`] is called.
+pub fn local_apic() -> Option<&'static LocalApic> {
+    LOCAL_APIC.r#try()
+}
+
+/// Signals the end of the current interrupt to the local APIC.
+pub fn end_of_interrupt() {
+    if let Some(local_apic) = local_apic() {
+        local_apic.end_of_interrupt();
+    }
+}
+
+/// Unmasks the ISA IRQ `irq` and delivers it to the current processor on `vector`.
+pub fn route_isa_irq(irq: u8, vector: u8) {
+    let route = ISA_ROUTES.r#try().expect("APIC not initialized")[irq as usize];
+    let local_apic = local_apic().expect("APIC not initialized");
+
+    let io_apic = IO_APICS
+        .r#try()
+        .and_then(|io_apics| {
+            io_apics
+                .iter()
+                .find(|io_apic| io_apic.lock().handles(route.gsi))
+        })
+        .unwrap_or_else(|| panic!("no I/O APIC handles GSI {}", route.gsi));
+
+    io_apic.lock().set_redirection(
+        route.gsi,
+        RedirectionEntry {
+            vector,
+            polarity: route.polarity,
+            trigger_mode: route.trigger_mode,
+            masked: false,
+            destination: local_apic.id(),
+        },
+    );
+}
@@ -0,0 +1,106 @@
+use x86_64::VirtAddr;
+
+use crate::acpi::madt::{Polarity, TriggerMode};
+
+const IOREGSEL: u64 = 0x00;
+const IOWIN: u64 = 0x10;
+
+const VERSION_REGISTER: u32 = 0x01;
+const REDIRECTION_TABLE: u32 = 0x10;
+
+/// An I/O APIC, which routes Global System Interrupts to local APICs.
+#[derive(Debug)]
+pub struct IoApic {
+    base: VirtAddr,
+    /// First Global System Interrupt handled by this I/O APIC.
+    gsi_base: u32,
+    redirection_entries: u32,
+}
+
+/// Where and how to deliver an interrupt.
+#[derive(Debug, Clone, Copy)]
+pub struct RedirectionEntry {
+    pub vector: u8,
+    pub polarity: Polarity,
+    pub trigger_mode: TriggerMode,
+    pub masked: bool,
+    /// Local APIC ID of the processor that receives the interrupt.
+    pub destination: u8,
+}
+
+impl RedirectionEntry {
+    fn bits(&self) -> u64 {
+        let mut bits = self.vector as u64;
+        // delivery mode (bits 8-10) is fixed and destination mode (bit 11) is physical
+        if self.polarity == Polarity::ActiveLow {
+            bits |= 1 << 13;
+        }
+        if self.trigger_mode == TriggerMode::Level {
+            bits |= 1 << 15;
+        }
+        if self.masked {
+            bits |= 1 << 16;
+        }
+        bits | (self.destination as u64) << 56
+    }
+}
+
+impl IoApic {
+    /// # Safety
+    ///
+    /// `base` must be the virtual address of the I/O APIC registers.
+    pub unsafe fn new(base: VirtAddr, gsi_base: u32) -> Self {
+        let mut io_apic = Self {
+            base,
+            gsi_base,
+            redirection_entries: 0,
+        };
+
+        // bits 16-23 hold the index of the last redirection entry
+        io_apic.redirection_entries = ((io_apic.read(VERSION_REGISTER) >> 16) & 0xff) + 1;
+        io_apic
+    }
+
+    fn read(&mut self, register: u32) -> u32 {
+        unsafe {
+            (self.base + IOREGSEL)
+                .as_mut_ptr::<u32>()
+                .write_volatile(register);
+            (self.base + IOWIN).as_ptr::<u32>().read_volatile()
+        }
+    }
+
+    fn write(&mut self, register: u32, value: u32) {
+        unsafe {
+            (self.base + IOREGSEL)
+                .as_mut_ptr::<u32>()
+                .write_volatile(register);
+            (self.base + IOWIN)
+                .as_mut_ptr::<u32>()
+                .write_volatile(value);
+        }
+    }
+
+    /// Returns `true` if this I/O APIC handles the Global System Interrupt `gsi`.
+    pub fn handles(&self, gsi: u32) -> bool {
+        (self.gsi_base..(self.gsi_base + self.redirection_entries)).contains(&gsi)
+    }
+
+    pub fn set_redirection(&mut self, gsi: u32, entry: RedirectionEntry) {
+        let register = REDIRECTION_TABLE + 2 * (gsi - self.gsi_base);
+        let bits = entry.bits();
+
+        // mask the entry while it is being changed
+        self.write(register, (1 << 16) | (bits as u32));
+        self.write(register + 1, (bits >> 32) as u32);
+        self.write(register, bits as u32);
+    }
+
+    pub fn mask_all(&mut self) {
+        for index in 0..self.redirection_entries {
+            let register = REDIRECTION_TABLE + 2 * index;
+            let low = self.read(register);
+            self.write(register, low | (1 << 16));
+        }
+    }
+}
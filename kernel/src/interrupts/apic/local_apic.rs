@@ -0,0 +1,194 @@
+use x86_64::registers::model_specific::Msr;
+use x86_64::VirtAddr;
+
+use crate::acpi::madt::{Polarity, TriggerMode};
+use crate::interrupts::vectors::SPURIOUS_VECTOR;
+
+/// `IA32_APIC_BASE`, which holds the physical address of the local APIC and its enable bit.
+const APIC_BASE_MSR: Msr = Msr::new(0x1b);
+const APIC_BASE_ENABLE: u64 = 1 << 11;
+
+/// Offsets of the local APIC registers.
+#[derive(Debug, Clone, Copy)]
+#[repr(usize)]
+pub enum Register {
+    Id = 0x20,
+    Version = 0x30,
+    TaskPriority = 0x80,
+    EndOfInterrupt = 0xb0,
+    SpuriousInterruptVector = 0xf0,
+    ErrorStatus = 0x280,
+    InterruptCommandLow = 0x300,
+    InterruptCommandHigh = 0x310,
+    LvtTimer = 0x320,
+    LvtLint0 = 0x350,
+    LvtLint1 = 0x360,
+    LvtError = 0x370,
+    TimerInitialCount = 0x380,
+    TimerCurrentCount = 0x390,
+    TimerDivideConfiguration = 0x3e0,
+}
+
+/// Bits of the local vector table entries.
+pub mod lvt {
+    pub const MASKED: u32 = 1 << 16;
+    pub const DELIVERY_MODE_NMI: u32 = 0b100 << 8;
+    pub const ACTIVE_LOW: u32 = 1 << 13;
+    pub const LEVEL_TRIGGERED: u32 = 1 << 15;
+    pub const TIMER_PERIODIC: u32 = 1 << 17;
+}
+
+/// Bits of the interrupt command register.
+pub mod icr {
+    pub const DELIVERY_MODE_INIT: u32 = 0b101 << 8;
+    pub const DELIVERY_MODE_STARTUP: u32 = 0b110 << 8;
+    pub const DELIVERY_PENDING: u32 = 1 << 12;
+    pub const LEVEL_ASSERT: u32 = 1 << 14;
+}
+
+/// Value of the divide configuration register that clocks the timer at the bus frequency
+/// divided by 16.
+const TIMER_DIVIDE_BY_16: u32 = 0b0011;
+
+/// The local APIC of the current processor.
+///
+/// Every processor accesses its own local APIC through the same physical address, so a single
+/// instance is shared by all of them.
+#[derive(Debug)]
+pub struct LocalApic {
+    base: VirtAddr,
+}
+
+impl LocalApic {
+    /// # Safety
+    ///
+    /// `base` must be the virtual address of the local APIC registers.
+    pub unsafe fn new(base: VirtAddr) -> Self {
+        Self { base }
+    }
+
+    pub fn read(&self, register: Register) -> u32 {
+        let ptr = (self.base + register as usize).as_ptr::<u32>();
+        unsafe { ptr.read_volatile() }
+    }
+
+    /// # Safety
+    ///
+    /// Writing some registers has side effects, such as sending inter-processor interrupts.
+    pub unsafe fn write(&self, register: Register, value: u32) {
+        let ptr = (self.base + register as usize).as_mut_ptr::<u32>();
+        unsafe { ptr.write_volatile(value) };
+    }
+
+    pub fn id(&self) -> u8 {
+        (self.read(Register::Id) >> 24) as u8
+    }
+
+    /// Enables the local APIC of the current processor and lets it accept every interrupt.
+    ///
+    /// LINT0, which is connected to the 8259 PIC in virtual wire mode, is masked. If `nmi_lint`
+    /// is given, that LINT pin is configured to deliver non-maskable interrupts.
+    pub fn enable(&self, nmi_lint: Option<(u8, Polarity, TriggerMode)>) {
+        unsafe {
+            let mut apic_base = APIC_BASE_MSR;
+            let value = apic_base.read();
+            apic_base.write(value | APIC_BASE_ENABLE);
+
+            self.write(Register::TaskPriority, 0);
+            self.write(Register::LvtLint0, lvt::MASKED);
+            self.write(Register::LvtLint1, lvt::MASKED);
+            self.write(Register::LvtError, lvt::MASKED);
+
+            if let Some((lint, polarity, trigger_mode)) = nmi_lint {
+                let mut entry = lvt::DELIVERY_MODE_NMI;
+                if polarity == Polarity::ActiveLow {
+                    entry |= lvt::ACTIVE_LOW;
+                }
+                if trigger_mode == TriggerMode::Level {
+                    entry |= lvt::LEVEL_TRIGGERED;
+                }
+
+                let register = match lint {
+                    0 => Register::LvtLint0,
+                    _ => Register::LvtLint1,
+                };
+                self.write(register, entry);
+            }
+
+            // bit 8 enables the APIC
+            self.write(
+                Register::SpuriousInterruptVector,
+                (1 << 8) | SPURIOUS_VECTOR as u32,
+            );
+        }
+    }
+
+    pub fn end_of_interrupt(&self) {
+        unsafe { self.write(Register::EndOfInterrupt, 0) };
+    }
+
+    /// Makes the timer of the current processor raise `vector` every `initial_count` ticks of
+    /// the bus clock divided by 16.
+    pub fn start_periodic_timer(&self, vector: u8, initial_count: u32) {
+        unsafe {
+            self.write(Register::TimerDivideConfiguration, TIMER_DIVIDE_BY_16);
+            self.write(Register::LvtTimer, lvt::TIMER_PERIODIC | vector as u32);
+            self.write(Register::TimerInitialCount, initial_count);
+        }
+    }
+
+    /// Returns how many ticks of the bus clock divided by 16 elapse while `wait` runs.
+    ///
+    /// The timer of the current processor is stopped afterwards.
+    pub fn measure_timer(&self, wait: impl FnOnce()) -> u32 {
+        unsafe {
+            self.write(Register::TimerDivideConfiguration, TIMER_DIVIDE_BY_16);
+            self.write(Register::LvtTimer, lvt::MASKED);
+            self.write(Register::TimerInitialCount, u32::MAX);
+        }
+
+        wait();
+        let elapsed = u32::MAX - self.read(Register::TimerCurrentCount);
+
+        unsafe { self.write(Register::TimerInitialCount, 0) };
+        elapsed
+    }
+
+    /// Sends an INIT inter-processor interrupt, which resets the processor `apic_id` and makes
+    /// it wait for a startup interrupt.
+    ///
+    /// # Safety
+    ///
+    /// The processor must not be running kernel code.
+    pub unsafe fn send_init(&self, apic_id: u8) {
+        unsafe { self.send_ipi(apic_id, icr::DELIVERY_MODE_INIT | icr::LEVEL_ASSERT) };
+    }
+
+    /// Sends a startup inter-processor interrupt, which makes the processor `apic_id` start in
+    /// real mode at the physical address `page * 4096`.
+    ///
+    /// # Safety
+    ///
+    /// The processor must be waiting for a startup interrupt, and the page must hold code that
+    /// can run there.
+    pub unsafe fn send_startup(&self, apic_id: u8, page: u8) {
+        unsafe {
+            self.send_ipi(
+                apic_id,
+                icr::DELIVERY_MODE_STARTUP | icr::LEVEL_ASSERT | page as u32,
+            )
+        };
+    }
+
+    /// Safety: see [`LocalApic::write`].
+    unsafe fn send_ipi(&self, apic_id: u8, command: u32) {
+        unsafe {
+            self.write(Register::InterruptCommandHigh, (apic_id as u32) << 24);
+            self.write(Register::InterruptCommandLow, command);
+        }
+
+        while self.read(Register::InterruptCommandLow) & icr::DELIVERY_PENDING != 0 {
+            core::hint::spin_loop();
+        }
+    }
+}
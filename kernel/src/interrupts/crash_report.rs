@@ -0,0 +1,106 @@
+use core::fmt;
+
+use drivers::println;
+use utils::hlt::hlt_loop;
+use x86_64::registers::control::{Cr0, Cr2, Cr3, Cr4};
+use x86_64::registers::rflags::RFlags;
+
+use super::exception::Exception;
+use super::trap_frame::TrapFrame;
+use crate::backtrace::Backtrace;
+use crate::process::{self, ExitStatus};
+
+/// Prints a crash report for `exception`: the exception name, the decoded error code if
+/// `details` is given, the registers saved in `frame` and the interrupted call stack.
+pub fn report(exception: Exception, frame: &TrapFrame, details: Option<fmt::Arguments>) {
+    println!(
+        "EXCEPTION: {} ({}, vector {})",
+        exception.name(),
+        exception.mnemonic(),
+        exception.vector()
+    );
+
+    if let Some(details) = details {
+        println!("{details}");
+    }
+
+    println!("{}", RegisterDump(frame));
+
+    let backtrace = Backtrace::from_registers(
+        frame.stack_frame.instruction_pointer.as_u64(),
+        frame.registers.rbp,
+    );
+    println!("{backtrace}");
+}
+
+/// Prints a crash report for `exception`, and halts the CPU or ends the program that caused it.
+pub fn fatal(exception: Exception, frame: &TrapFrame, details: Option<fmt::Arguments>) -> ! {
+    report(exception, frame, details);
+    stop(frame);
+}
+
+/// Halts the CPU after a fatal exception, or only kills the current process if the exception
+/// happened in user mode.
+pub fn stop(frame: &TrapFrame) -> ! {
+    if frame.stack_frame.code_segment & 3 == 3 && process::current().is_some() {
+        process::exit(ExitStatus::Killed);
+    }
+    hlt_loop();
+}
+
+struct RegisterDump<'a>(&'a TrapFrame);
+
+impl fmt::Display for RegisterDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stack_frame = &self.0.stack_frame;
+        let r = &self.0.registers;
+        let rflags = RFlags::from_bits_truncate(stack_frame.cpu_flags);
+
+        writeln!(
+            f,
+            "RIP: {:#018x}  CS: {:#06x}  RFLAGS: {:#010x} {rflags:?}",
+            stack_frame.instruction_pointer.as_u64(),
+            stack_frame.code_segment,
+            stack_frame.cpu_flags,
+        )?;
+        writeln!(
+            f,
+            "RSP: {:#018x}  SS: {:#06x}",
+            stack_frame.stack_pointer.as_u64(),
+            stack_frame.stack_segment,
+        )?;
+        writeln!(
+            f,
+            "RAX: {:#018x}  RBX: {:#018x}  RCX: {:#018x}",
+            r.rax, r.rbx, r.rcx
+        )?;
+        writeln!(
+            f,
+            "RDX: {:#018x}  RSI: {:#018x}  RDI: {:#018x}",
+            r.rdx, r.rsi, r.rdi
+        )?;
+        writeln!(
+            f,
+            "RBP: {:#018x}  R8:  {:#018x}  R9:  {:#018x}",
+            r.rbp, r.r8, r.r9
+        )?;
+        writeln!(
+            f,
+            "R10: {:#018x}  R11: {:#018x}  R12: {:#018x}",
+            r.r10, r.r11, r.r12
+        )?;
+        writeln!(
+            f,
+            "R13: {:#018x}  R14: {:#018x}  R15: {:#018x}",
+            r.r13, r.r14, r.r15
+        )?;
+        write!(
+            f,
+            "CR0: {:#018x}  CR2: {:#018x}  CR3: {:#018x}  CR4: {:#018x}",
+            Cr0::read_raw(),
+            Cr2::read().as_u64(),
+            Cr3::read().0.start_address().as_u64(),
+            Cr4::read_raw()
+        )
+    }
+}
@@ -0,0 +1,91 @@
+/// Architectural exceptions, identified by their interrupt vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Exception {
+    DivideError = 0,
+    Debug = 1,
+    NonMaskableInterrupt = 2,
+    Breakpoint = 3,
+    Overflow = 4,
+    BoundRangeExceeded = 5,
+    InvalidOpcode = 6,
+    DeviceNotAvailable = 7,
+    DoubleFault = 8,
+    InvalidTss = 10,
+    SegmentNotPresent = 11,
+    StackSegmentFault = 12,
+    GeneralProtectionFault = 13,
+    PageFault = 14,
+    X87FloatingPoint = 16,
+    AlignmentCheck = 17,
+    MachineCheck = 18,
+    SimdFloatingPoint = 19,
+    Virtualization = 20,
+    ControlProtection = 21,
+    HypervisorInjection = 28,
+    VmmCommunication = 29,
+    Security = 30,
+}
+
+impl Exception {
+    pub fn vector(self) -> u8 {
+        self as u8
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DivideError => "DIVIDE ERROR",
+            Self::Debug => "DEBUG",
+            Self::NonMaskableInterrupt => "NON-MASKABLE INTERRUPT",
+            Self::Breakpoint => "BREAKPOINT",
+            Self::Overflow => "OVERFLOW",
+            Self::BoundRangeExceeded => "BOUND RANGE EXCEEDED",
+            Self::InvalidOpcode => "INVALID OPCODE",
+            Self::DeviceNotAvailable => "DEVICE NOT AVAILABLE",
+            Self::DoubleFault => "DOUBLE FAULT",
+            Self::InvalidTss => "INVALID TSS",
+            Self::SegmentNotPresent => "SEGMENT NOT PRESENT",
+            Self::StackSegmentFault => "STACK SEGMENT FAULT",
+            Self::GeneralProtectionFault => "GENERAL PROTECTION FAULT",
+            Self::PageFault => "PAGE FAULT",
+            Self::X87FloatingPoint => "X87 FLOATING POINT",
+            Self::AlignmentCheck => "ALIGNMENT CHECK",
+            Self::MachineCheck => "MACHINE CHECK",
+            Self::SimdFloatingPoint => "SIMD FLOATING POINT",
+            Self::Virtualization => "VIRTUALIZATION",
+            Self::ControlProtection => "CONTROL PROTECTION",
+            Self::HypervisorInjection => "HYPERVISOR INJECTION",
+            Self::VmmCommunication => "VMM COMMUNICATION",
+            Self::Security => "SECURITY",
+        }
+    }
+
+    /// Short name used in the Intel and AMD manuals, such as `#GP`.
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Self::DivideError => "#DE",
+            Self::Debug => "#DB",
+            Self::NonMaskableInterrupt => "NMI",
+            Self::Breakpoint => "#BP",
+            Self::Overflow => "#OF",
+            Self::BoundRangeExceeded => "#BR",
+            Self::InvalidOpcode => "#UD",
+            Self::DeviceNotAvailable => "#NM",
+            Self::DoubleFault => "#DF",
+            Self::InvalidTss => "#TS",
+            Self::SegmentNotPresent => "#NP",
+            Self::StackSegmentFault => "#SS",
+            Self::GeneralProtectionFault => "#GP",
+            Self::PageFault => "#PF",
+            Self::X87FloatingPoint => "#MF",
+            Self::AlignmentCheck => "#AC",
+            Self::MachineCheck => "#MC",
+            Self::SimdFloatingPoint => "#XM",
+            Self::Virtualization => "#VE",
+            Self::ControlProtection => "#CP",
+            Self::HypervisorInjection => "#HV",
+            Self::VmmCommunication => "#VC",
+            Self::Security => "#SX",
+        }
+    }
+}
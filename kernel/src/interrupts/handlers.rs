@@ -1,5 +1,13 @@
 pub mod breakpoint;
+pub mod debug;
 pub mod double_fault;
+pub mod fatal;
 pub mod keyboard_interrupt;
+pub mod local_timer_interrupt;
+pub mod machine_check;
+pub mod mouse_interrupt;
 pub mod page_fault;
+pub mod selector_fault;
+pub mod serial_interrupt;
+pub mod spurious_interrupt;
 pub mod timer_interrupt;
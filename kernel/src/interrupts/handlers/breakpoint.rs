@@ -1,6 +1,9 @@
-use drivers::println;
-use x86_64::structures::idt::InterruptStackFrame;
+use crate::interrupts::crash_report;
+use crate::interrupts::exception::Exception;
+use crate::interrupts::trap_frame::{trap_stub, TrapFrame};
 
-pub extern "x86-interrupt" fn handler(stack_frame: InterruptStackFrame) {
-    println!("EXCEPTION: BREAKPOINT\n{stack_frame:#?}");
+trap_stub!(entry, handler);
+
+extern "C" fn handler(frame: &mut TrapFrame) {
+    crash_report::report(Exception::Breakpoint, frame, None);
 }
@@ -0,0 +1,12 @@
+use x86_64::registers::debug::Dr6;
+
+use crate::interrupts::crash_report;
+use crate::interrupts::exception::Exception;
+use crate::interrupts::trap_frame::{trap_stub, TrapFrame};
+
+trap_stub!(entry, handler);
+
+extern "C" fn handler(frame: &mut TrapFrame) {
+    let dr6 = Dr6::read();
+    crash_report::report(Exception::Debug, frame, Some(format_args!("DR6: {dr6:?}")));
+}
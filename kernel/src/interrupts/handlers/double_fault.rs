@@ -1,5 +1,9 @@
-use x86_64::structures::idt::InterruptStackFrame;
+use crate::interrupts::crash_report;
+use crate::interrupts::exception::Exception;
+use crate::interrupts::trap_frame::{trap_stub, TrapFrame};
 
-pub extern "x86-interrupt" fn handler(stack_frame: InterruptStackFrame, _error_code: u64) -> ! {
-    panic!("EXCEPTION: DOUBLE FAULT\n{stack_frame:#?}");
+trap_stub!(entry, handler, error_code);
+
+extern "C" fn handler(frame: &mut TrapFrame) {
+    crash_report::fatal(Exception::DoubleFault, frame, None);
 }
@@ -0,0 +1,96 @@
+//! Exceptions that don't need any specific handling: they are reported and the CPU is halted.
+
+use crate::interrupts::crash_report;
+use crate::interrupts::exception::Exception;
+use crate::interrupts::trap_frame::{trap_stub, TrapFrame};
+
+macro_rules! fatal_handler {
+    ($entry:ident, $handler:ident, $exception:ident) => {
+        trap_stub!($entry, $handler);
+
+        extern "C" fn $handler(frame: &mut TrapFrame) {
+            crash_report::fatal(Exception::$exception, frame, None);
+        }
+    };
+    ($entry:ident, $handler:ident, $exception:ident, error_code) => {
+        trap_stub!($entry, $handler, error_code);
+
+        extern "C" fn $handler(frame: &mut TrapFrame) {
+            crash_report::fatal(
+                Exception::$exception,
+                frame,
+                Some(format_args!("Error Code: {:#x}", frame.error_code)),
+            );
+        }
+    };
+}
+
+fatal_handler!(divide_error_entry, divide_error, DivideError);
+fatal_handler!(
+    non_maskable_interrupt_entry,
+    non_maskable_interrupt,
+    NonMaskableInterrupt
+);
+fatal_handler!(overflow_entry, overflow, Overflow);
+fatal_handler!(
+    bound_range_exceeded_entry,
+    bound_range_exceeded,
+    BoundRangeExceeded
+);
+fatal_handler!(invalid_opcode_entry, invalid_opcode, InvalidOpcode);
+fatal_handler!(
+    device_not_available_entry,
+    device_not_available,
+    DeviceNotAvailable
+);
+fatal_handler!(
+    x87_floating_point_entry,
+    x87_floating_point,
+    X87FloatingPoint
+);
+fatal_handler!(
+    alignment_check_entry,
+    alignment_check,
+    AlignmentCheck,
+    error_code
+);
+fatal_handler!(
+    simd_floating_point_entry,
+    simd_floating_point,
+    SimdFloatingPoint
+);
+fatal_handler!(virtualization_entry, virtualization, Virtualization);
+fatal_handler!(
+    hypervisor_injection_entry,
+    hypervisor_injection,
+    HypervisorInjection
+);
+fatal_handler!(
+    vmm_communication_entry,
+    vmm_communication,
+    VmmCommunication,
+    error_code
+);
+fatal_handler!(security_entry, security, Security, error_code);
+
+trap_stub!(control_protection_entry, control_protection, error_code);
+
+extern "C" fn control_protection(frame: &mut TrapFrame) {
+    let cause = match frame.error_code & 0x7fff {
+        1 => "near return",
+        2 => "far return or interrupt return",
+        3 => "missing end branch",
+        4 => "shadow stack restore",
+        5 => "shadow stack busy",
+        _ => "unknown",
+    };
+
+    crash_report::fatal(
+        Exception::ControlProtection,
+        frame,
+        Some(format_args!(
+            "Error Code: {:#x} ({cause})",
+            frame.error_code
+        )),
+    );
+}
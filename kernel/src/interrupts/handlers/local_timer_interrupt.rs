@@ -0,0 +1,14 @@
+use x86_64::structures::idt::InterruptStackFrame;
+
+use crate::interrupts::apic;
+use crate::smp::KernelGs;
+use crate::thread;
+
+/// Handles the local APIC timer of the application processors, whose only job is preemption:
+/// the clock and the timers are driven by the PIT on the bootstrap processor.
+pub extern "x86-interrupt" fn handler(stack_frame: InterruptStackFrame) {
+    // the scheduler uses the per-CPU data
+    let _kernel_gs = KernelGs::enter(&stack_frame);
+    apic::end_of_interrupt();
+    thread::tick();
+}
@@ -0,0 +1,24 @@
+use x86_64::registers::model_specific::Msr;
+
+use crate::interrupts::crash_report;
+use crate::interrupts::exception::Exception;
+use crate::interrupts::trap_frame::{trap_stub, TrapFrame};
+
+/// `IA32_MCG_STATUS`, which tells whether execution can be restarted after a machine check.
+const MCG_STATUS: Msr = Msr::new(0x17a);
+
+trap_stub!(entry, handler);
+
+extern "C" fn handler(frame: &mut TrapFrame) {
+    // Machine checks are only delivered when CR4.MCE is set, in which case the MSR exists.
+    let status = unsafe { MCG_STATUS.read() };
+    crash_report::fatal(
+        Exception::MachineCheck,
+        frame,
+        Some(format_args!(
+            "MCG_STATUS: {status:#x} (restart IP valid: {}, error IP valid: {})",
+            status & 1 != 0,
+            status & 2 != 0
+        )),
+    );
+}
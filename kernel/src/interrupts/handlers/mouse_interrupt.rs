@@ -0,0 +1,13 @@
+use x86_64::instructions::port::Port;
+use x86_64::structures::idt::InterruptStackFrame;
+
+use crate::interrupts::apic;
+use crate::mouse;
+
+pub extern "x86-interrupt" fn handler(_stack_frame: InterruptStackFrame) {
+    let mut port = Port::new(0x60);
+    let byte: u8 = unsafe { port.read() };
+    mouse::add_byte(byte);
+
+    apic::end_of_interrupt();
+}
@@ -1,15 +1,50 @@
-use drivers::println;
-use utils::hlt::hlt_loop;
 use x86_64::registers::control::Cr2;
-use x86_64::structures::idt::{InterruptStackFrame, PageFaultErrorCode};
-
-pub extern "x86-interrupt" fn handler(
-    stack_frame: InterruptStackFrame,
-    error_code: PageFaultErrorCode,
-) {
-    println!("EXCEPTION: PAGE FAULT");
-    println!("Accessed Address: {:?}", Cr2::read());
-    println!("Error Code: {error_code:?}");
-    println!("{stack_frame:#?}");
-    hlt_loop();
+use x86_64::structures::idt::PageFaultErrorCode;
+
+use crate::interrupts::crash_report;
+use crate::interrupts::exception::Exception;
+use crate::interrupts::trap_frame::{trap_stub, TrapFrame};
+use crate::memory::{cow, stack};
+
+trap_stub!(entry, handler, error_code);
+
+extern "C" fn handler(frame: &mut TrapFrame) {
+    let accessed_address = Cr2::read();
+    let error_code = PageFaultErrorCode::from_bits_truncate(frame.error_code);
+
+    // A write to a present page may target a frame shared copy-on-write, which is split here.
+    let write_to_present_page =
+        PageFaultErrorCode::CAUSED_BY_WRITE | PageFaultErrorCode::PROTECTION_VIOLATION;
+    let user_mode = error_code.contains(PageFaultErrorCode::USER_MODE);
+    if error_code.contains(write_to_present_page)
+        && cow::handle_write_fault(accessed_address, user_mode)
+    {
+        return;
+    }
+
+    let overflowed = stack::with_overflowed_stack(accessed_address, |stack| {
+        crash_report::report(
+            Exception::PageFault,
+            frame,
+            Some(format_args!(
+                "Stack overflow in task {}\nStack: {:?}..{:?} ({} bytes)\nAccessed Address: {accessed_address:?}\nError Code: {error_code:?}",
+                stack.name,
+                stack.bounds.start(),
+                stack.bounds.end(),
+                stack.bounds.size()
+            )),
+        );
+    });
+
+    if overflowed.is_none() {
+        crash_report::report(
+            Exception::PageFault,
+            frame,
+            Some(format_args!(
+                "Accessed Address: {accessed_address:?}\nError Code: {error_code:?}"
+            )),
+        );
+    }
+
+    crash_report::stop(frame);
 }
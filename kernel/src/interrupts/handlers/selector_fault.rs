@@ -0,0 +1,66 @@
+//! Exceptions whose error code references a segment selector.
+
+use core::fmt;
+
+use x86_64::structures::idt::SelectorErrorCode;
+
+use crate::interrupts::crash_report;
+use crate::interrupts::exception::Exception;
+use crate::interrupts::trap_frame::{trap_stub, TrapFrame};
+
+trap_stub!(invalid_tss_entry, invalid_tss, error_code);
+trap_stub!(segment_not_present_entry, segment_not_present, error_code);
+trap_stub!(stack_segment_fault_entry, stack_segment_fault, error_code);
+trap_stub!(
+    general_protection_fault_entry,
+    general_protection_fault,
+    error_code
+);
+
+extern "C" fn invalid_tss(frame: &mut TrapFrame) {
+    fatal(Exception::InvalidTss, frame);
+}
+
+extern "C" fn segment_not_present(frame: &mut TrapFrame) {
+    fatal(Exception::SegmentNotPresent, frame);
+}
+
+extern "C" fn stack_segment_fault(frame: &mut TrapFrame) {
+    fatal(Exception::StackSegmentFault, frame);
+}
+
+extern "C" fn general_protection_fault(frame: &mut TrapFrame) {
+    fatal(Exception::GeneralProtectionFault, frame);
+}
+
+fn fatal(exception: Exception, frame: &TrapFrame) -> ! {
+    let error_code = SelectorErrorCode::new_truncate(frame.error_code);
+    crash_report::fatal(
+        exception,
+        frame,
+        Some(format_args!("{}", DecodedSelector(error_code))),
+    );
+}
+
+struct DecodedSelector(SelectorErrorCode);
+
+impl fmt::Display for DecodedSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_null() {
+            return write!(f, "Error Code: 0 (not related to a segment selector)");
+        }
+
+        write!(
+            f,
+            "Error Code: selector index {} in the {:?}",
+            self.0.index(),
+            self.0.descriptor_table()
+        )?;
+
+        if self.0.external() {
+            write!(f, ", during delivery of an external event")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use drivers::serial::ComPort;
+use x86_64::structures::idt::InterruptStackFrame;
+
+use crate::interrupts::apic;
+use crate::serial;
+
+pub extern "x86-interrupt" fn com1_handler(_stack_frame: InterruptStackFrame) {
+    serial::receive(ComPort::Com1);
+
+    apic::end_of_interrupt();
+}
+
+pub extern "x86-interrupt" fn com2_handler(_stack_frame: InterruptStackFrame) {
+    serial::receive(ComPort::Com2);
+
+    apic::end_of_interrupt();
+}
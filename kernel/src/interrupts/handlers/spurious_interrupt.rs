@@ -0,0 +1,4 @@
+use x86_64::structures::idt::InterruptStackFrame;
+
+/// Spurious interrupts of the local APIC and of the masked PICs must not be acknowledged.
+pub extern "x86-interrupt" fn handler(_stack_frame: InterruptStackFrame) {}
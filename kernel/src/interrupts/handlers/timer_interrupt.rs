@@ -1,10 +1,16 @@
 use x86_64::structures::idt::InterruptStackFrame;
 
-use crate::interrupts::{index::InterruptIndex, pics::PICS};
+use crate::interrupts::apic;
+use crate::smp::KernelGs;
+use crate::thread;
+use crate::time::{clock, timers};
 
-pub extern "x86-interrupt" fn handler(_stack_frame: InterruptStackFrame) {
-    unsafe {
-        PICS.lock()
-            .notify_end_of_interrupt(InterruptIndex::Timer.as_u8());
-    }
+pub extern "x86-interrupt" fn handler(stack_frame: InterruptStackFrame) {
+    // the scheduler uses the per-CPU data
+    let _kernel_gs = KernelGs::enter(&stack_frame);
+    let now = clock::tick();
+    apic::end_of_interrupt();
+
+    timers::run_expired(now);
+    thread::tick();
 }
@@ -1,32 +1,78 @@
 use lazy_static::lazy_static;
-use x86_64::structures::idt::InterruptDescriptorTable;
+use spin::Mutex;
+use x86_64::structures::idt::{Entry, EntryOptions, HandlerFunc, InterruptDescriptorTable};
+use x86_64::VirtAddr;
 
 use crate::gdt;
 use crate::interrupts::handlers::*;
-use crate::interrupts::index::InterruptIndex;
+use crate::interrupts::pics::PIC_1_OFFSET;
+use crate::interrupts::vectors::{LOCAL_TIMER_VECTOR, SPURIOUS_VECTOR};
 
 lazy_static! {
-    static ref IDT: InterruptDescriptorTable = {
+    static ref IDT: Mutex<InterruptDescriptorTable> = {
         let mut idt = InterruptDescriptorTable::new();
 
         // Exceptions
-        idt.breakpoint.set_handler_fn(breakpoint::handler);
-        idt.page_fault.set_handler_fn(page_fault::handler);
-
         unsafe {
-            idt.double_fault
-                .set_handler_fn(double_fault::handler)
+            set_trap(&mut idt.divide_error, fatal::divide_error_entry);
+            set_trap(&mut idt.debug, debug::entry);
+            set_trap(&mut idt.non_maskable_interrupt, fatal::non_maskable_interrupt_entry);
+            set_trap(&mut idt.breakpoint, breakpoint::entry);
+            set_trap(&mut idt.overflow, fatal::overflow_entry);
+            set_trap(&mut idt.bound_range_exceeded, fatal::bound_range_exceeded_entry);
+            set_trap(&mut idt.invalid_opcode, fatal::invalid_opcode_entry);
+            set_trap(&mut idt.device_not_available, fatal::device_not_available_entry);
+            set_trap(&mut idt.double_fault, double_fault::entry)
                 .set_stack_index(gdt::tss::DOUBLE_FAULT_IST_INDEX);
+            set_trap(&mut idt.invalid_tss, selector_fault::invalid_tss_entry);
+            set_trap(&mut idt.segment_not_present, selector_fault::segment_not_present_entry);
+            set_trap(&mut idt.stack_segment_fault, selector_fault::stack_segment_fault_entry);
+            set_trap(
+                &mut idt.general_protection_fault,
+                selector_fault::general_protection_fault_entry,
+            );
+            // The page fault handler runs on its own stack so that it can report overflows of
+            // the current stack, instead of faulting again while pushing the interrupt frame.
+            set_trap(&mut idt.page_fault, page_fault::entry)
+                .set_stack_index(gdt::tss::PAGE_FAULT_IST_INDEX);
+            set_trap(&mut idt.x87_floating_point, fatal::x87_floating_point_entry);
+            set_trap(&mut idt.alignment_check, fatal::alignment_check_entry);
+            set_trap(&mut idt.machine_check, machine_check::entry);
+            set_trap(&mut idt.simd_floating_point, fatal::simd_floating_point_entry);
+            set_trap(&mut idt.virtualization, fatal::virtualization_entry);
+            set_trap(&mut idt.cp_protection_exception, fatal::control_protection_entry);
+            set_trap(&mut idt.hv_injection_exception, fatal::hypervisor_injection_entry);
+            set_trap(&mut idt.vmm_communication_exception, fatal::vmm_communication_entry);
+            set_trap(&mut idt.security_exception, fatal::security_entry);
         }
 
-        // Interrupts
-        idt[InterruptIndex::Timer.as_usize()].set_handler_fn(timer_interrupt::handler);
-        idt[InterruptIndex::Keyboard.as_usize()].set_handler_fn(keyboard_interrupt::handler);
+        // Interrupts, device handlers are added with `set_handler`
+        idt[SPURIOUS_VECTOR as usize].set_handler_fn(spurious_interrupt::handler);
+        idt[LOCAL_TIMER_VECTOR as usize].set_handler_fn(local_timer_interrupt::handler);
+        // IRQ 7 of each masked PIC can still be raised spuriously
+        idt[PIC_1_OFFSET as usize + 7].set_handler_fn(spurious_interrupt::handler);
+        idt[PIC_1_OFFSET as usize + 15].set_handler_fn(spurious_interrupt::handler);
 
-        idt
+        Mutex::new(idt)
     };
 }
 
+/// Points an IDT entry to an entry point defined with `trap_stub!`.
+///
+/// Safety: `stub` must be defined with `trap_stub!`, with the `error_code` variant if and only if
+/// the CPU pushes an error code for this entry.
+unsafe fn set_trap<F>(entry: &mut Entry<F>, stub: unsafe extern "C" fn()) -> &mut EntryOptions {
+    unsafe { entry.set_handler_addr(VirtAddr::new(stub as usize as u64)) }
+}
+
+/// Loads the IDT on the current processor, the same table is shared by all of them.
 pub fn init() {
-    IDT.load();
+    // Safety: the table lives in a static and is only modified through `set_handler`
+    unsafe { IDT.lock().load_unsafe() };
+}
+
+/// Sets the handler of the interrupt `vector`, which must not be an exception.
+pub fn set_handler(vector: u8, handler: HandlerFunc) {
+    assert!(vector >= 32, "vector {vector} is reserved for exceptions");
+    IDT.lock()[vector as usize].set_handler_fn(handler);
 }
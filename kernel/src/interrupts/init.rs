@@ -0,0 +1,13 @@
+use super::handlers::timer_interrupt;
+use super::{apic, irq, pics};
+
+/// Masks the legacy PICs, enables the APICs and routes the timer interrupt.
+///
+/// Must be called once the ACPI tables are located. The PS/2 driver routes the interrupts of the
+/// keyboard and of the mouse, see [`ps2::init`](crate::ps2::init).
+pub fn init() {
+    pics::init();
+    apic::init();
+
+    irq::register_isa("timer", irq::TIMER, timer_interrupt::handler);
+}
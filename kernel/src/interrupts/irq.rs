@@ -0,0 +1,50 @@
+use alloc::vec::Vec;
+
+use utils::sync::IrqSpinlock;
+use x86_64::structures::idt::HandlerFunc;
+
+use super::{apic, idt, vectors};
+
+/// ISA IRQ of the Programmable Interval Timer.
+pub const TIMER: u8 = 0;
+/// ISA IRQ of the first PS/2 port, the keyboard.
+pub const KEYBOARD: u8 = 1;
+/// ISA IRQ of the second serial port.
+pub const COM2: u8 = 3;
+/// ISA IRQ of the first serial port.
+pub const COM1: u8 = 4;
+/// ISA IRQ of the second PS/2 port, the mouse.
+pub const MOUSE: u8 = 12;
+
+/// A device interrupt routed through the I/O APIC.
+#[derive(Debug, Clone, Copy)]
+pub struct Irq {
+    pub name: &'static str,
+    pub isa_irq: u8,
+    pub vector: u8,
+}
+
+static IRQS: IrqSpinlock<Vec<Irq>> = IrqSpinlock::new("IRQS", Vec::new());
+
+/// Routes the ISA IRQ `isa_irq` to `handler` on a newly allocated vector, and returns the vector.
+///
+/// The handler must acknowledge the interrupt with [`apic::end_of_interrupt`].
+pub fn register_isa(name: &'static str, isa_irq: u8, handler: HandlerFunc) -> u8 {
+    let vector = vectors::allocate().expect("no interrupt vector left");
+
+    idt::set_handler(vector, handler);
+    apic::route_isa_irq(isa_irq, vector);
+
+    IRQS.lock().push(Irq {
+        name,
+        isa_irq,
+        vector,
+    });
+
+    vector
+}
+
+/// Returns the registered device interrupts.
+pub fn registered() -> Vec<Irq> {
+    IRQS.lock().clone()
+}
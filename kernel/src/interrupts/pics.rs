@@ -1,12 +1,20 @@
 use pic8259::ChainedPics;
-use spin::Mutex;
+use utils::sync::IrqSpinlock;
 
+/// The legacy PICs are remapped right after the exceptions, so that their spurious interrupts
+/// cannot be mistaken for exceptions.
 pub const PIC_1_OFFSET: u8 = 32;
 pub const PIC_2_OFFSET: u8 = PIC_1_OFFSET + 8;
 
-pub static PICS: Mutex<ChainedPics> =
-    Mutex::new(unsafe { ChainedPics::new(PIC_1_OFFSET, PIC_2_OFFSET) });
+pub static PICS: IrqSpinlock<ChainedPics> = IrqSpinlock::new("PICS", unsafe {
+    ChainedPics::new(PIC_1_OFFSET, PIC_2_OFFSET)
+});
 
+/// Remaps the legacy PICs and masks all their IRQs, which are routed through the I/O APIC.
 pub fn init() {
-    unsafe { PICS.lock().initialize() };
+    unsafe {
+        let mut pics = PICS.lock();
+        pics.initialize();
+        pics.disable();
+    }
 }
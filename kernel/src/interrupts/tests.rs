@@ -3,3 +3,27 @@ fn test_breakpoint_exception() {
     // invoke a breakpoint exception
     x86_64::instructions::interrupts::int3();
 }
+
+#[test_case]
+fn test_trap_frame_layout() {
+    use super::trap_frame::TrapFrame;
+
+    // 15 general purpose registers, the error code and the 5 values pushed by the CPU
+    assert_eq!(size_of::<TrapFrame>(), (15 + 1 + 5) * size_of::<u64>());
+}
+
+#[test_case]
+fn test_vector_allocation() {
+    use super::vectors::{allocate, free, FIRST_VECTOR, LAST_VECTOR};
+
+    let first = allocate().expect("no vector left");
+    let second = allocate().expect("no vector left");
+    assert_ne!(first, second);
+    assert!((FIRST_VECTOR..=LAST_VECTOR).contains(&first));
+    assert!((FIRST_VECTOR..=LAST_VECTOR).contains(&second));
+
+    free(first);
+    assert_eq!(allocate(), Some(first));
+    free(first);
+    free(second);
+}
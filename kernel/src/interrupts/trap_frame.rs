@@ -0,0 +1,121 @@
+use x86_64::structures::idt::InterruptStackFrameValue;
+
+/// General purpose registers, in the order in which [`trap_stub!`] pushes them.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct Registers {
+    pub r15: u64,
+    pub r14: u64,
+    pub r13: u64,
+    pub r12: u64,
+    pub r11: u64,
+    pub r10: u64,
+    pub r9: u64,
+    pub r8: u64,
+    pub rbp: u64,
+    pub rdi: u64,
+    pub rsi: u64,
+    pub rdx: u64,
+    pub rcx: u64,
+    pub rbx: u64,
+    pub rax: u64,
+}
+
+/// Everything that is saved on the stack when a [`trap_stub!`] handler is entered.
+///
+/// Handlers receive it by mutable reference: any change is restored into the CPU
+/// when the handler returns.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct TrapFrame {
+    pub registers: Registers,
+    /// Error code pushed by the CPU, or 0 for vectors that don't have one.
+    pub error_code: u64,
+    pub stack_frame: InterruptStackFrameValue,
+}
+
+/// Defines a naked interrupt entry point that saves every general purpose register into a
+/// [`TrapFrame`], calls `$handler` with it and restores it before returning with `iretq`.
+///
+/// If the CPU was in user mode, the GS base is swapped with the kernel one around the handler.
+///
+/// `$handler` must be an `extern "C" fn(&mut TrapFrame)`. Use the `error_code` variant for
+/// vectors on which the CPU pushes an error code, so that the frame layout stays the same.
+macro_rules! trap_stub {
+    ($name:ident, $handler:path) => {
+        #[unsafe(naked)]
+        pub unsafe extern "C" fn $name() {
+            core::arch::naked_asm!(
+                "push 0",
+                $crate::interrupts::trap_frame::trap_stub!(@body),
+                handler = sym $handler,
+            );
+        }
+    };
+    ($name:ident, $handler:path, error_code) => {
+        #[unsafe(naked)]
+        pub unsafe extern "C" fn $name() {
+            core::arch::naked_asm!(
+                $crate::interrupts::trap_frame::trap_stub!(@body),
+                handler = sym $handler,
+            );
+        }
+    };
+    (@body) => {
+        concat!(
+            // the handler runs with the kernel GS base, also when the CPU was in user mode, in
+            // which case the code segment pushed by the CPU has a non-zero privilege level
+            "test byte ptr [rsp + 16], 3\n",
+            "jz 2f\n",
+            "swapgs\n",
+            "2:\n",
+            "push rax\n",
+            "push rbx\n",
+            "push rcx\n",
+            "push rdx\n",
+            "push rsi\n",
+            "push rdi\n",
+            "push rbp\n",
+            "push r8\n",
+            "push r9\n",
+            "push r10\n",
+            "push r11\n",
+            "push r12\n",
+            "push r13\n",
+            "push r14\n",
+            "push r15\n",
+            "cld\n",
+            "mov rdi, rsp\n",
+            // The CPU aligns the stack on 16 bytes before pushing the 48 bytes of the interrupt
+            // frame and error code, so the 15 registers leave it 8 bytes off the alignment that
+            // the System V ABI requires at call sites.
+            "sub rsp, 8\n",
+            "call {handler}\n",
+            "add rsp, 8\n",
+            "pop r15\n",
+            "pop r14\n",
+            "pop r13\n",
+            "pop r12\n",
+            "pop r11\n",
+            "pop r10\n",
+            "pop r9\n",
+            "pop r8\n",
+            "pop rbp\n",
+            "pop rdi\n",
+            "pop rsi\n",
+            "pop rdx\n",
+            "pop rcx\n",
+            "pop rbx\n",
+            "pop rax\n",
+            "test byte ptr [rsp + 16], 3\n",
+            "jz 3f\n",
+            "swapgs\n",
+            "3:\n",
+            // skip the error code
+            "add rsp, 8\n",
+            "iretq\n",
+        )
+    };
+}
+
+pub(crate) use trap_stub;
@@ -0,0 +1,30 @@
+use spin::Mutex;
+
+/// First vector handed out to devices, above the exceptions and the vectors of the masked PICs.
+pub const FIRST_VECTOR: u8 = 48;
+/// Last vector handed out to devices, the ones above are reserved for inter-processor interrupts
+/// and the local APIC.
+pub const LAST_VECTOR: u8 = 0xef;
+/// Vector of the local APIC timer, which preempts threads on the application processors.
+pub const LOCAL_TIMER_VECTOR: u8 = 0xf0;
+/// Vector of the spurious interrupts of the local APIC.
+pub const SPURIOUS_VECTOR: u8 = 0xff;
+
+/// One bit per vector, set when the vector is allocated.
+static ALLOCATED: Mutex<[u64; 4]> = Mutex::new([0; 4]);
+
+/// Reserves a free interrupt vector for a device, or returns `None` if none is left.
+pub fn allocate() -> Option<u8> {
+    let mut allocated = ALLOCATED.lock();
+
+    let vector = (FIRST_VECTOR..=LAST_VECTOR)
+        .find(|&vector| allocated[vector as usize / 64] & (1 << (vector % 64)) == 0)?;
+    allocated[vector as usize / 64] |= 1 << (vector % 64);
+
+    Some(vector)
+}
+
+/// Releases a vector returned by [`allocate`].
+pub fn free(vector: u8) {
+    ALLOCATED.lock()[vector as usize / 64] &= !(1 << (vector % 64));
+}
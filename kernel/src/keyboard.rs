@@ -0,0 +1,22 @@
+mod decoder;
+mod event;
+mod inner;
+mod input;
+mod layout;
+mod leds;
+mod scancode;
+mod subscriber;
+
+pub use decoder::Decoder;
+pub use event::{KeyEvent, Modifiers};
+pub use inner::decode_keypresses;
+pub(crate) use input::add_input;
+pub use input::read;
+pub use layout::Layout;
+pub use pc_keyboard::{KeyCode, KeyState};
+pub(crate) use scancode::add_scancode;
+pub use scancode::ScancodeStream;
+pub use subscriber::{subscribe, Subscription};
+
+#[cfg(test)]
+mod tests;
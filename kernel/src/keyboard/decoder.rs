@@ -0,0 +1,108 @@
+use pc_keyboard::{DecodedKey, KeyCode, KeyState, ScancodeSet, ScancodeSet1};
+
+use super::event::{KeyEvent, Modifiers};
+use super::layout::Layout;
+
+/// Bytes that the keyboard sends in response to commands, which are not scancodes.
+const ACKNOWLEDGE: u8 = 0xfa;
+const RESEND: u8 = 0xfe;
+/// Sent by the keyboard when its buffer overflows, or when a key error happens.
+const ERRORS: [u8; 2] = [0x00, 0xff];
+
+/// Decodes the bytes sent by a keyboard that uses scancode set 1 into [`KeyEvent`]s, with the
+/// characters typed on a [`Layout`], and tracks the modifiers and the locks.
+pub struct Decoder {
+    scancodes: ScancodeSet1,
+    layout: Layout,
+    modifiers: Modifiers,
+    /// Lock keys held down, so that they do not toggle their lock again while they repeat
+    locks_held: Modifiers,
+    /// Set from the hidden control key that the Pause key sends before the Num Lock key, until
+    /// the Num Lock key is released
+    pause: bool,
+}
+
+impl Decoder {
+    /// Creates a decoder with Num Lock on, like most firmware leave it.
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            scancodes: ScancodeSet1::new(),
+            layout,
+            modifiers: Modifiers::NUM_LOCK,
+            locks_held: Modifiers::empty(),
+            pause: false,
+        }
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Adds a byte received from the keyboard, and returns the event that it completes.
+    ///
+    /// Responses to commands and unknown scancodes are ignored.
+    pub fn add_byte(&mut self, byte: u8) -> Option<KeyEvent> {
+        if byte == ACKNOWLEDGE || byte == RESEND || ERRORS.contains(&byte) {
+            return None;
+        }
+        let event = self.scancodes.advance_state(byte).ok()??;
+
+        let state = event.state;
+        if state == KeyState::SingleShot {
+            return None;
+        }
+        let code = match (event.code, self.pause) {
+            (KeyCode::RControl2, _) => {
+                self.pause |= state == KeyState::Down;
+                return None;
+            }
+            (KeyCode::NumpadLock, true) => {
+                // the release of the Pause key comes after the release of its hidden control key
+                self.pause = state == KeyState::Down;
+                KeyCode::PauseBreak
+            }
+            (code, _) => code,
+        };
+
+        if let Some(modifier) = Modifiers::of_key(code) {
+            self.apply(modifier, state);
+        }
+
+        let character = match state {
+            KeyState::Down => match self.layout.map_keycode(code, self.modifiers) {
+                DecodedKey::Unicode(character) => Some(character),
+                DecodedKey::RawKey(_) => None,
+            },
+            _ => None,
+        };
+
+        Some(KeyEvent {
+            code,
+            state,
+            modifiers: self.modifiers,
+            character,
+        })
+    }
+
+    /// Holds or releases a modifier key, or toggles a lock when its key is pressed.
+    fn apply(&mut self, modifier: Modifiers, state: KeyState) {
+        let down = state == KeyState::Down;
+
+        if !Modifiers::LOCKS.contains(modifier) {
+            self.modifiers.set(modifier, down);
+            return;
+        }
+        if down && !self.locks_held.contains(modifier) {
+            self.modifiers.toggle(modifier);
+        }
+        self.locks_held.set(modifier, down);
+    }
+}
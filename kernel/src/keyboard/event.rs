@@ -0,0 +1,72 @@
+use bitflags::bitflags;
+use pc_keyboard::{KeyCode, KeyState};
+
+/// A key pressed or released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    /// [`KeyState::Down`] when the key is pressed, and again while it repeats, or
+    /// [`KeyState::Up`] when it is released
+    pub state: KeyState,
+    /// Modifiers and locks once the event is applied
+    pub modifiers: Modifiers,
+    /// Character typed with the modifiers, for the key-down events of the keys that type one
+    pub character: Option<char>,
+}
+
+bitflags! {
+    /// Modifier keys held down, and lock keys toggled on.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct Modifiers: u16 {
+        const LEFT_SHIFT = 1 << 0;
+        const RIGHT_SHIFT = 1 << 1;
+        const LEFT_CTRL = 1 << 2;
+        const RIGHT_CTRL = 1 << 3;
+        const LEFT_ALT = 1 << 4;
+        const ALT_GR = 1 << 5;
+        const LEFT_SUPER = 1 << 6;
+        const RIGHT_SUPER = 1 << 7;
+        const CAPS_LOCK = 1 << 8;
+        const NUM_LOCK = 1 << 9;
+        const SCROLL_LOCK = 1 << 10;
+
+        const SHIFT = Self::LEFT_SHIFT.bits() | Self::RIGHT_SHIFT.bits();
+        const CTRL = Self::LEFT_CTRL.bits() | Self::RIGHT_CTRL.bits();
+        const SUPER = Self::LEFT_SUPER.bits() | Self::RIGHT_SUPER.bits();
+        const LOCKS = Self::CAPS_LOCK.bits() | Self::NUM_LOCK.bits() | Self::SCROLL_LOCK.bits();
+    }
+}
+
+impl Modifiers {
+    /// Returns the modifier that `code` holds down, or the lock that it toggles.
+    pub fn of_key(code: KeyCode) -> Option<Self> {
+        Some(match code {
+            KeyCode::LShift => Self::LEFT_SHIFT,
+            KeyCode::RShift => Self::RIGHT_SHIFT,
+            KeyCode::LControl => Self::LEFT_CTRL,
+            KeyCode::RControl => Self::RIGHT_CTRL,
+            KeyCode::LAlt => Self::LEFT_ALT,
+            KeyCode::RAltGr => Self::ALT_GR,
+            KeyCode::LWin => Self::LEFT_SUPER,
+            KeyCode::RWin => Self::RIGHT_SUPER,
+            KeyCode::CapsLock => Self::CAPS_LOCK,
+            KeyCode::NumpadLock => Self::NUM_LOCK,
+            KeyCode::ScrollLock => Self::SCROLL_LOCK,
+            _ => return None,
+        })
+    }
+
+    /// Returns the modifiers in the form that the layouts of [`pc_keyboard`] expect.
+    pub(super) fn to_pc_keyboard(self) -> pc_keyboard::Modifiers {
+        pc_keyboard::Modifiers {
+            lshift: self.contains(Self::LEFT_SHIFT),
+            rshift: self.contains(Self::RIGHT_SHIFT),
+            lctrl: self.contains(Self::LEFT_CTRL),
+            rctrl: self.contains(Self::RIGHT_CTRL),
+            numlock: self.contains(Self::NUM_LOCK),
+            capslock: self.contains(Self::CAPS_LOCK),
+            alt_gr: self.contains(Self::ALT_GR),
+            rctrl2: false,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use drivers::display::frame_buffer::WRITER;
+use futures_util::stream::StreamExt;
+use pc_keyboard::{KeyCode, KeyState};
+
+use super::decoder::Decoder;
+use super::event::{KeyEvent, Modifiers};
+use super::layout::Layout;
+use super::scancode::ScancodeStream;
+use super::{input, leds, subscriber};
+
+/// Decodes the scancodes received from the keyboard with the [configured](Layout::configured)
+/// layout, publishes the key events to the [subscribers](super::subscribe), lights the lock LEDs,
+/// and keeps the keys typed for [`read`](super::read).
+///
+/// The keys are not echoed, the readers print what they want to show, like the
+/// [shell](crate::shell). Shift and Page Up or Page Down scroll the screen through the
+/// scrollback instead.
+pub async fn decode_keypresses() {
+    let mut scancodes = ScancodeStream::new();
+    let mut decoder = Decoder::new(Layout::configured());
+    leds::set(decoder.modifiers());
+
+    while let Some(scancode) = scancodes.next().await {
+        let locks = decoder.modifiers() & Modifiers::LOCKS;
+        let Some(event) = decoder.add_byte(scancode) else {
+            continue;
+        };
+
+        if event.modifiers & Modifiers::LOCKS != locks {
+            leds::set(event.modifiers);
+        }
+        subscriber::publish(event);
+        if !scroll_screen(&event) {
+            input::add_key(&event);
+        }
+    }
+}
+
+/// Scrolls the screen by half a page if `event` presses Shift and Page Up or Page Down, and
+/// returns whether it did.
+fn scroll_screen(event: &KeyEvent) -> bool {
+    if event.state != KeyState::Down || !event.modifiers.intersects(Modifiers::SHIFT) {
+        return false;
+    }
+    let Some(writer) = WRITER.r#try() else {
+        return false;
+    };
+
+    let mut writer = writer.lock();
+    let lines = writer.rows() / 2;
+    match event.code {
+        KeyCode::PageUp => writer.scroll_back(lines),
+        KeyCode::PageDown => writer.scroll_forward(lines),
+        _ => return false,
+    }
+    true
+}
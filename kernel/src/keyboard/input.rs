@@ -0,0 +1,79 @@
+use alloc::collections::VecDeque;
+
+use pc_keyboard::{KeyCode, KeyState};
+
+use super::event::KeyEvent;
+use crate::sync::{IrqSpinlock, WaitQueue};
+
+/// Maximum number of bytes typed and not read yet.
+const INPUT_SIZE: usize = 1024;
+
+/// Keys typed on the keyboard, encoded by [`encode`], until they are [`read`].
+static INPUT: IrqSpinlock<VecDeque<u8>> = IrqSpinlock::new("keyboard input", VecDeque::new());
+static INPUT_WAITERS: WaitQueue = WaitQueue::new();
+
+/// Waits for characters to be typed, and moves as many of them as fit into `buffer`.
+///
+/// Returns the number of bytes read, which is only 0 if `buffer` is empty.
+pub fn read(buffer: &mut [u8]) -> usize {
+    if buffer.is_empty() {
+        return 0;
+    }
+
+    loop {
+        INPUT_WAITERS.wait_until(|| !INPUT.lock().is_empty());
+
+        // another reader may have taken the input in between
+        let mut input = INPUT.lock();
+        let count = buffer.len().min(input.len());
+        if count > 0 {
+            for (byte, typed) in buffer.iter_mut().zip(input.drain(..count)) {
+                *byte = typed;
+            }
+            return count;
+        }
+    }
+}
+
+/// Keeps `bytes` for [`read`], unless too many bytes are already waiting.
+///
+/// The serial terminal types there too, for the shell to be driven over it.
+pub(crate) fn add_input(bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    {
+        let mut input = INPUT.lock();
+        if input.len() + bytes.len() > INPUT_SIZE {
+            return;
+        }
+        input.extend(bytes);
+    }
+    INPUT_WAITERS.wake_all();
+}
+
+/// Keeps the bytes that stand for the key pressed in `event` for [`read`].
+pub(super) fn add_key(event: &KeyEvent) {
+    if event.state == KeyState::Down {
+        add_input(encode(event, &mut [0; 4]));
+    }
+}
+
+/// Returns the bytes that stand for the key of `event` in the keyboard input: the UTF-8 encoding
+/// of characters, and the escape sequences of ANSI terminals for the keys that move the cursor.
+///
+/// Returns nothing for the other keys.
+pub(super) fn encode<'a>(event: &KeyEvent, encoded: &'a mut [u8; 4]) -> &'a [u8] {
+    match (event.character, event.code) {
+        (Some('\x7f'), _) => b"\x1b[3~",
+        (Some(character), _) => character.encode_utf8(encoded).as_bytes(),
+        (None, KeyCode::ArrowUp) => b"\x1b[A",
+        (None, KeyCode::ArrowDown) => b"\x1b[B",
+        (None, KeyCode::ArrowRight) => b"\x1b[C",
+        (None, KeyCode::ArrowLeft) => b"\x1b[D",
+        (None, KeyCode::Home) => b"\x1b[H",
+        (None, KeyCode::End) => b"\x1b[F",
+        (None, _) => b"",
+    }
+}
@@ -0,0 +1,90 @@
+use core::fmt;
+
+use log::warn;
+use pc_keyboard::{layouts, DecodedKey, HandleControl, KeyCode, KeyboardLayout};
+
+use super::event::Modifiers;
+use crate::config;
+
+/// Keyboard layouts, which give the characters typed by the keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// US, QWERTY with 104 keys
+    Us104Key,
+    /// UK, QWERTY with 105 keys
+    Uk105Key,
+    /// French, AZERTY
+    #[default]
+    Azerty,
+    /// German, QWERTZ with 105 keys
+    De105Key,
+    /// US Dvorak
+    Dvorak,
+}
+
+impl Layout {
+    pub const ALL: [Self; 5] = [
+        Self::Us104Key,
+        Self::Uk105Key,
+        Self::Azerty,
+        Self::De105Key,
+        Self::Dvorak,
+    ];
+
+    /// Option of the boot configuration that names the layout.
+    pub const CONFIG_KEY: &'static str = "keyboard.layout";
+
+    /// Returns the name of the layout in the boot configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Us104Key => "us",
+            Self::Uk105Key => "uk",
+            Self::Azerty => "azerty",
+            Self::De105Key => "de",
+            Self::Dvorak => "dvorak",
+        }
+    }
+
+    /// Returns the layout named `name`, ignoring its case. `fr` also names [`Self::Azerty`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("fr") {
+            return Some(Self::Azerty);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|layout| layout.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the layout set by the boot configuration, or the default one if it sets none or
+    /// an unknown one.
+    pub fn configured() -> Self {
+        let Some(name) = config::get(Self::CONFIG_KEY) else {
+            return Self::default();
+        };
+
+        Self::from_name(name).unwrap_or_else(|| {
+            warn!("unknown keyboard layout `{name}`, using the default one");
+            Self::default()
+        })
+    }
+
+    /// Returns what `code` types with `modifiers`.
+    pub(super) fn map_keycode(self, code: KeyCode, modifiers: Modifiers) -> DecodedKey {
+        let modifiers = &modifiers.to_pc_keyboard();
+        let handle_ctrl = HandleControl::Ignore;
+
+        match self {
+            Self::Us104Key => layouts::Us104Key.map_keycode(code, modifiers, handle_ctrl),
+            Self::Uk105Key => layouts::Uk105Key.map_keycode(code, modifiers, handle_ctrl),
+            Self::Azerty => layouts::Azerty.map_keycode(code, modifiers, handle_ctrl),
+            Self::De105Key => layouts::De105Key.map_keycode(code, modifiers, handle_ctrl),
+            Self::Dvorak => layouts::Dvorak104Key.map_keycode(code, modifiers, handle_ctrl),
+        }
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
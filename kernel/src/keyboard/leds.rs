@@ -0,0 +1,32 @@
+use super::event::Modifiers;
+use crate::ps2::{self, Port};
+
+/// Keyboard command that sets the LEDs to the next byte.
+const SET_LEDS: u8 = 0xed;
+
+const SCROLL_LOCK_LED: u8 = 1 << 0;
+const NUM_LOCK_LED: u8 = 1 << 1;
+const CAPS_LOCK_LED: u8 = 1 << 2;
+
+/// Lights the LEDs of the locks set in `modifiers`, on the keyboard of the first PS/2 port.
+///
+/// The keyboard acknowledges both bytes, the decoder ignores the acknowledgements.
+pub(super) fn set(modifiers: Modifiers) {
+    if ps2::device(Port::First).is_none_or(|device| !device.is_keyboard()) {
+        return;
+    }
+
+    let mut leds = 0;
+    if modifiers.contains(Modifiers::SCROLL_LOCK) {
+        leds |= SCROLL_LOCK_LED;
+    }
+    if modifiers.contains(Modifiers::NUM_LOCK) {
+        leds |= NUM_LOCK_LED;
+    }
+    if modifiers.contains(Modifiers::CAPS_LOCK) {
+        leds |= CAPS_LOCK_LED;
+    }
+
+    // the LEDs are only a hint, they are left as they are if the controller is stuck
+    let _ = ps2::write(Port::First, SET_LEDS).and_then(|()| ps2::write(Port::First, leds));
+}
@@ -0,0 +1,73 @@
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crossbeam_queue::ArrayQueue;
+use futures_util::stream::Stream;
+use futures_util::task::AtomicWaker;
+use spin::Once;
+
+/// Maximum number of scancodes received and not decoded yet.
+const SCANCODE_QUEUE_SIZE: usize = 100;
+
+static SCANCODE_QUEUE: Once<ArrayQueue<u8>> = Once::new();
+static WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Queues a scancode for the [`ScancodeStream`].
+///
+/// Called by the keyboard interrupt handler, so it must not block nor allocate.
+pub(crate) fn add_scancode(scancode: u8) {
+    // scancodes received before the stream is created, or while the queue is full, are dropped
+    if let Some(queue) = SCANCODE_QUEUE.r#try() {
+        if queue.push(scancode).is_ok() {
+            WAKER.wake();
+        }
+    }
+}
+
+/// The scancodes received from the keyboard. There can be only one stream.
+pub struct ScancodeStream {
+    _private: (),
+}
+
+impl ScancodeStream {
+    pub fn new() -> Self {
+        let mut created = false;
+        SCANCODE_QUEUE.call_once(|| {
+            created = true;
+            ArrayQueue::new(SCANCODE_QUEUE_SIZE)
+        });
+        assert!(created, "ScancodeStream::new should only be called once");
+
+        ScancodeStream { _private: () }
+    }
+}
+
+impl Default for ScancodeStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stream for ScancodeStream {
+    type Item = u8;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<u8>> {
+        let queue = SCANCODE_QUEUE
+            .r#try()
+            .expect("scancode queue not initialized");
+
+        // fast path, without registering the waker
+        if let Some(scancode) = queue.pop() {
+            return Poll::Ready(Some(scancode));
+        }
+
+        WAKER.register(context.waker());
+        match queue.pop() {
+            Some(scancode) => {
+                WAKER.take();
+                Poll::Ready(Some(scancode))
+            }
+            None => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use alloc::collections::VecDeque;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+
+use super::event::KeyEvent;
+use crate::sync::{IrqSpinlock, WaitQueue};
+
+/// Maximum number of events published to a subscriber and not taken yet.
+const CHANNEL_SIZE: usize = 256;
+
+struct Channel {
+    events: IrqSpinlock<VecDeque<KeyEvent>>,
+    waiters: WaitQueue,
+}
+
+static SUBSCRIBERS: IrqSpinlock<Vec<Weak<Channel>>> =
+    IrqSpinlock::new("keyboard subscribers", Vec::new());
+
+/// Receives the key events published after it was created, until it is dropped.
+pub struct Subscription {
+    channel: Arc<Channel>,
+}
+
+impl Subscription {
+    /// Waits for the next key event.
+    pub fn next(&self) -> KeyEvent {
+        let mut event = None;
+        self.channel.waiters.wait_until(|| {
+            event = self.try_next();
+            event.is_some()
+        });
+        event.unwrap()
+    }
+
+    /// Returns the next key event, or `None` if there is none yet.
+    pub fn try_next(&self) -> Option<KeyEvent> {
+        self.channel.events.lock().pop_front()
+    }
+}
+
+/// Subscribes to the key presses and releases.
+///
+/// The events that a subscriber does not take fast enough are dropped.
+pub fn subscribe() -> Subscription {
+    let channel = Arc::new(Channel {
+        events: IrqSpinlock::new("keyboard events", VecDeque::new()),
+        waiters: WaitQueue::new(),
+    });
+    SUBSCRIBERS.lock().push(Arc::downgrade(&channel));
+
+    Subscription { channel }
+}
+
+/// Sends `event` to every subscriber, and forgets the dropped ones.
+pub(super) fn publish(event: KeyEvent) {
+    let mut subscribers = SUBSCRIBERS.lock();
+    subscribers.retain(|subscriber| {
+        let Some(channel) = subscriber.upgrade() else {
+            return false;
+        };
+
+        {
+            let mut events = channel.events.lock();
+            if events.len() < CHANNEL_SIZE {
+                events.push_back(event);
+            }
+        }
+        channel.waiters.wake_all();
+        true
+    });
+}
@@ -0,0 +1,151 @@
+use alloc::vec::Vec;
+
+use super::input::encode;
+use super::{Decoder, KeyCode, KeyEvent, KeyState, Layout, Modifiers};
+
+const LEFT_SHIFT_DOWN: u8 = 0x2a;
+const LEFT_SHIFT_UP: u8 = 0xaa;
+const CAPS_LOCK_DOWN: u8 = 0x3a;
+const CAPS_LOCK_UP: u8 = 0xba;
+/// The key right of Tab, Q on QWERTY layouts and A on AZERTY ones
+const Q_DOWN: u8 = 0x10;
+const Q_UP: u8 = 0x90;
+
+fn decode(decoder: &mut Decoder, bytes: &[u8]) -> Vec<KeyEvent> {
+    bytes
+        .iter()
+        .filter_map(|&byte| decoder.add_byte(byte))
+        .collect()
+}
+
+fn characters(decoder: &mut Decoder, bytes: &[u8]) -> Vec<char> {
+    decode(decoder, bytes)
+        .into_iter()
+        .filter_map(|event| event.character)
+        .collect()
+}
+
+#[test_case]
+fn test_layout_names() {
+    for layout in Layout::ALL {
+        assert_eq!(Layout::from_name(layout.name()), Some(layout));
+    }
+    assert_eq!(Layout::from_name("US"), Some(Layout::Us104Key));
+    assert_eq!(Layout::from_name("fr"), Some(Layout::Azerty));
+    assert_eq!(Layout::from_name("qwerty"), None);
+}
+
+#[test_case]
+fn test_layouts() {
+    let bytes = [Q_DOWN, Q_UP, LEFT_SHIFT_DOWN, Q_DOWN, Q_UP, LEFT_SHIFT_UP];
+
+    assert_eq!(
+        characters(&mut Decoder::new(Layout::Us104Key), &bytes),
+        ['q', 'Q']
+    );
+    assert_eq!(
+        characters(&mut Decoder::new(Layout::Azerty), &bytes),
+        ['a', 'A']
+    );
+    assert_eq!(
+        characters(&mut Decoder::new(Layout::Dvorak), &bytes),
+        ['\'', '"']
+    );
+}
+
+#[test_case]
+fn test_key_events() {
+    let mut decoder = Decoder::new(Layout::Us104Key);
+    let events = decode(
+        &mut decoder,
+        &[LEFT_SHIFT_DOWN, Q_DOWN, Q_UP, LEFT_SHIFT_UP],
+    );
+
+    let shifted = Modifiers::LEFT_SHIFT | Modifiers::NUM_LOCK;
+    assert_eq!(
+        events,
+        [
+            KeyEvent {
+                code: KeyCode::LShift,
+                state: KeyState::Down,
+                modifiers: shifted,
+                character: None,
+            },
+            KeyEvent {
+                code: KeyCode::Q,
+                state: KeyState::Down,
+                modifiers: shifted,
+                character: Some('Q'),
+            },
+            KeyEvent {
+                code: KeyCode::Q,
+                state: KeyState::Up,
+                modifiers: shifted,
+                character: None,
+            },
+            KeyEvent {
+                code: KeyCode::LShift,
+                state: KeyState::Up,
+                modifiers: Modifiers::NUM_LOCK,
+                character: None,
+            },
+        ]
+    );
+}
+
+#[test_case]
+fn test_locks() {
+    let mut decoder = Decoder::new(Layout::Us104Key);
+    assert_eq!(decoder.modifiers(), Modifiers::NUM_LOCK);
+
+    // the key repeats while it is held, but only toggles the lock once
+    decode(
+        &mut decoder,
+        &[CAPS_LOCK_DOWN, CAPS_LOCK_DOWN, CAPS_LOCK_UP],
+    );
+    assert!(decoder.modifiers().contains(Modifiers::CAPS_LOCK));
+    assert_eq!(characters(&mut decoder, &[Q_DOWN, Q_UP]), ['Q']);
+
+    decode(&mut decoder, &[CAPS_LOCK_DOWN, CAPS_LOCK_UP]);
+    assert!(!decoder.modifiers().contains(Modifiers::CAPS_LOCK));
+}
+
+#[test_case]
+fn test_pause() {
+    let mut decoder = Decoder::new(Layout::Us104Key);
+    let events = decode(&mut decoder, &[0xe1, 0x1d, 0x45, 0xe1, 0x9d, 0xc5]);
+
+    let codes: Vec<_> = events
+        .iter()
+        .map(|event| (event.code, event.state))
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            (KeyCode::PauseBreak, KeyState::Down),
+            (KeyCode::PauseBreak, KeyState::Up)
+        ]
+    );
+    assert_eq!(decoder.modifiers(), Modifiers::NUM_LOCK);
+}
+
+#[test_case]
+fn test_ignore_responses() {
+    let mut decoder = Decoder::new(Layout::Us104Key);
+    let events = decode(&mut decoder, &[0xfa, Q_DOWN, 0xfe, 0xfa, Q_UP]);
+
+    assert_eq!(events.len(), 2);
+}
+
+#[test_case]
+fn test_encode() {
+    let mut decoder = Decoder::new(Layout::Us104Key);
+    // Q, Delete and the left arrow
+    let events = decode(&mut decoder, &[Q_DOWN, 0xe0, 0x53, 0xe0, 0x4b]);
+    let encoded: Vec<_> = events
+        .iter()
+        .map(|event| Vec::from(encode(event, &mut [0; 4])))
+        .collect();
+
+    assert_eq!(encoded, [&b"q"[..], b"\x1b[3~", b"\x1b[D"]);
+}
@@ -8,13 +8,33 @@
 
 extern crate alloc;
 
+pub mod acpi;
+pub mod backtrace;
+pub mod config;
+pub mod elf;
+pub mod fs;
 pub mod gdt;
 pub mod heap;
 mod init;
 pub mod interrupts;
+pub mod keyboard;
+pub mod logger;
 pub mod macros;
 pub mod memory;
+pub mod mouse;
 pub mod panic;
+pub mod power;
+pub mod process;
+pub mod ps2;
+pub mod serial;
+pub mod shell;
+pub mod smp;
+pub mod sync;
+pub mod syscall;
+pub mod task;
+pub mod thread;
+pub mod time;
+pub mod usermode;
 
 pub use init::init;
 
@@ -28,7 +48,7 @@ cfg_test! {
     fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
         init(boot_info);
         test_main();
-        utils::hlt::hlt_loop();
+        power::shutdown();
     }
 
     #[panic_handler]
@@ -0,0 +1,10 @@
+mod filter;
+mod inner;
+mod ring_buffer;
+
+pub use filter::{Error, Filter};
+pub use inner::{configure, init, replay, write_contents, BUFFER_SIZE, CONFIG_KEY};
+pub use ring_buffer::RingBuffer;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,90 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use log::{LevelFilter, Metadata};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A directive isn't a level nor a `module=level` pair
+    InvalidDirective,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDirective => write!(f, "invalid log directive"),
+        }
+    }
+}
+
+/// Which records are logged, by the module that logs them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    /// Level of the modules without a directive
+    default: LevelFilter,
+    /// Levels of modules and of their submodules
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    /// Logs the records of the modules up to `default`.
+    pub const fn new(default: LevelFilter) -> Self {
+        Self {
+            default,
+            directives: Vec::new(),
+        }
+    }
+
+    /// Parses directives separated by commas, each a level for every module, like `info`, or
+    /// a level for a module and its submodules, like `kernel::ps2=debug`.
+    ///
+    /// The most specific directive of a module wins, and levels are case-insensitive.
+    pub fn parse(spec: &str) -> Result<Self, Error> {
+        let mut filter = Self::new(LevelFilter::Info);
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let level = level.trim().parse().map_err(|_| Error::InvalidDirective)?;
+                    let module = module.trim();
+                    if module.is_empty() {
+                        return Err(Error::InvalidDirective);
+                    }
+                    filter.directives.retain(|(other, _)| other != module);
+                    filter.directives.push((String::from(module), level));
+                }
+                None => {
+                    filter.default = directive.parse().map_err(|_| Error::InvalidDirective)?;
+                }
+            }
+        }
+
+        Ok(filter)
+    }
+
+    /// Returns the most verbose level of the records of the module `target`.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .filter(|(module, _)| {
+                target
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    /// Returns the most verbose level of all modules.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, Ord::max)
+    }
+
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level(metadata.target())
+    }
+}
@@ -0,0 +1,127 @@
+use core::fmt::Write;
+use core::ops::Range;
+use core::str;
+
+use drivers::console::Console;
+use drivers::print;
+use log::{LevelFilter, Log, Metadata, Record};
+use utils::sync::IrqSpinlock;
+
+use super::filter::Filter;
+use super::ring_buffer::RingBuffer;
+use crate::{config, time};
+
+/// Option of the boot configuration with the directives of the filter, see [`Filter::parse`].
+pub const CONFIG_KEY: &str = "log.filter";
+
+/// Number of bytes of the last lines logged that are kept.
+pub const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Number of bytes copied at once out of the ring buffer to be written.
+const CHUNK_SIZE: usize = 256;
+
+static LOGGER: Logger = Logger;
+static FILTER: IrqSpinlock<Filter> = IrqSpinlock::new("LOG FILTER", Filter::new(LevelFilter::Info));
+static BUFFER: IrqSpinlock<RingBuffer<BUFFER_SIZE>> =
+    IrqSpinlock::new("LOG BUFFER", RingBuffer::new());
+
+/// Writes the records to every console, and keeps them in a ring buffer.
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        FILTER.lock().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let uptime = time::uptime();
+        let (seconds, micros) = (uptime.as_secs(), uptime.subsec_micros());
+        let (level, target, args) = (record.level(), record.target(), record.args());
+
+        // The record is formatted once, into the ring buffer, and written from there
+        let positions = {
+            let mut buffer = BUFFER.lock();
+            let start = buffer.positions().end;
+            // Writing to the ring buffer never fails
+            let _ = writeln!(
+                buffer,
+                "[{seconds:>5}.{micros:06}] {level:<5} {target}: {args}"
+            );
+            start..buffer.positions().end
+        };
+        write_range(positions, |text| print!("{text}"));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger, which logs the records up to [`LevelFilter::Info`] until it is
+/// configured.
+///
+/// It doesn't allocate, so this should be called first, once a console is registered.
+pub fn init() {
+    log::set_logger(&LOGGER).expect("logger already installed");
+    log::set_max_level(FILTER.lock().max_level());
+}
+
+/// Filters the records with the directives of the boot configuration, see [`CONFIG_KEY`].
+pub fn configure() {
+    let Some(spec) = config::get(CONFIG_KEY) else {
+        return;
+    };
+
+    match Filter::parse(spec) {
+        Ok(filter) => {
+            log::set_max_level(filter.max_level());
+            *FILTER.lock() = filter;
+        }
+        Err(error) => log::warn!("{error} in `{spec}`, using the default filter"),
+    }
+}
+
+/// Writes the lines kept in the ring buffer to `console`, to show the records logged before it
+/// was registered.
+pub fn replay(console: &dyn Console) {
+    write_contents(|text| console.write_str(text));
+}
+
+/// Passes the lines kept in the ring buffer to `write`, the oldest first, in chunks of at most
+/// [`CHUNK_SIZE`] bytes, without allocating.
+pub fn write_contents(write: impl FnMut(&str)) {
+    let positions = BUFFER.lock().positions();
+    write_range(positions, write);
+}
+
+/// Passes the bytes of the ring buffer at `positions` to `write`, in chunks.
+///
+/// The buffer is only locked to copy each chunk, so that it isn't locked while the chunk is
+/// written. The bytes dropped meanwhile to make room for new records are skipped.
+fn write_range(positions: Range<usize>, mut write: impl FnMut(&str)) {
+    let mut chunk = [0; CHUNK_SIZE];
+    let mut position = positions.start;
+
+    while position < positions.end {
+        let length = (positions.end - position).min(CHUNK_SIZE);
+        let read = {
+            let buffer = BUFFER.lock();
+            match buffer.read(position, &mut chunk[..length]) {
+                Some(read) => read,
+                // Dropped bytes end with a line, so the oldest kept byte starts one
+                None => {
+                    position = buffer.positions().start;
+                    continue;
+                }
+            }
+        };
+
+        // A char cut by the end of the chunk is written with the next chunk
+        let bytes = &chunk[..read];
+        let valid = str::from_utf8(bytes).map_or_else(|error| error.valid_up_to(), str::len);
+        write(str::from_utf8(&bytes[..valid]).unwrap_or_default());
+        position += valid.max(1);
+    }
+}
@@ -0,0 +1,112 @@
+use core::fmt;
+use core::ops::Range;
+
+/// The last lines of text written to it, up to `N` bytes.
+///
+/// It doesn't allocate, so it can keep the text logged before the heap is ready.
+#[derive(Debug, Clone)]
+pub struct RingBuffer<const N: usize> {
+    bytes: [u8; N],
+    /// Index of the oldest byte
+    start: usize,
+    len: usize,
+    /// Number of bytes dropped since the buffer was created
+    dropped: usize,
+}
+
+impl<const N: usize> RingBuffer<N> {
+    pub const fn new() -> Self {
+        Self {
+            bytes: [0; N],
+            start: 0,
+            len: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends `bytes`, dropping the oldest lines to make room for them.
+    pub fn push(&mut self, bytes: &[u8]) {
+        let bytes = &bytes[bytes.len().saturating_sub(N)..];
+        let excess = (self.len + bytes.len()).saturating_sub(N);
+        if excess > 0 {
+            self.drop_front(excess);
+            // Drops the rest of the line cut by the previous drop
+            let (first, second) = self.as_slices();
+            let rest = first
+                .iter()
+                .chain(second)
+                .position(|&byte| byte == b'\n')
+                .map_or(self.len, |position| position + 1);
+            self.drop_front(rest);
+        }
+
+        let end = (self.start + self.len) % N;
+        let (first, second) = bytes.split_at(bytes.len().min(N - end));
+        self.bytes[end..end + first.len()].copy_from_slice(first);
+        self.bytes[..second.len()].copy_from_slice(second);
+        self.len += bytes.len();
+    }
+
+    /// Returns the text, the oldest first, in two parts as it may wrap around the end of the
+    /// buffer.
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        let end = self.start + self.len;
+        if end <= N {
+            (&self.bytes[self.start..end], &[])
+        } else {
+            (&self.bytes[self.start..], &self.bytes[..end - N])
+        }
+    }
+
+    /// Returns the positions of the bytes kept, counted from the first byte ever pushed, which
+    /// stay the same as the oldest bytes are dropped.
+    pub fn positions(&self) -> Range<usize> {
+        self.dropped..self.dropped + self.len
+    }
+
+    /// Copies the bytes from `position`, see [`Self::positions`], into `buffer`, and returns
+    /// their number, or [`None`] if the byte at `position` was dropped.
+    pub fn read(&self, position: usize, buffer: &mut [u8]) -> Option<usize> {
+        let offset = position.checked_sub(self.dropped)?;
+        let count = self.len.checked_sub(offset)?.min(buffer.len());
+
+        let index = (self.start + offset) % N;
+        let (first, second) = buffer[..count].split_at_mut(count.min(N - index));
+        first.copy_from_slice(&self.bytes[index..index + first.len()]);
+        second.copy_from_slice(&self.bytes[..second.len()]);
+        Some(count)
+    }
+
+    pub fn clear(&mut self) {
+        self.drop_front(self.len);
+        self.start = 0;
+    }
+
+    fn drop_front(&mut self, count: usize) {
+        let count = count.min(self.len);
+        self.start = (self.start + count) % N;
+        self.len -= count;
+        self.dropped += count;
+    }
+}
+
+impl<const N: usize> Default for RingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Write for RingBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
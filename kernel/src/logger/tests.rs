@@ -0,0 +1,72 @@
+use alloc::string::String;
+
+use log::{Level, LevelFilter, Metadata};
+
+use super::{write_contents, Error, Filter, RingBuffer};
+
+fn metadata(level: Level, target: &str) -> Metadata<'_> {
+    Metadata::builder().level(level).target(target).build()
+}
+
+#[test_case]
+fn test_parse_filter() {
+    let filter = Filter::parse("warn, kernel::ps2=debug,kernel::ps2::device=OFF").unwrap();
+
+    assert_eq!(filter.level("kernel::smp"), LevelFilter::Warn);
+    assert_eq!(filter.level("kernel::ps2"), LevelFilter::Debug);
+    assert_eq!(filter.level("kernel::ps2::inner"), LevelFilter::Debug);
+    assert_eq!(filter.level("kernel::ps2::device"), LevelFilter::Off);
+    // a module is not a prefix of the name of another one
+    assert_eq!(filter.level("kernel::ps2x"), LevelFilter::Warn);
+    assert_eq!(filter.max_level(), LevelFilter::Debug);
+
+    assert!(filter.enabled(&metadata(Level::Error, "kernel")));
+    assert!(!filter.enabled(&metadata(Level::Info, "kernel")));
+
+    assert_eq!(Filter::parse("loud"), Err(Error::InvalidDirective));
+    assert_eq!(Filter::parse("=info"), Err(Error::InvalidDirective));
+    assert_eq!(Filter::parse("").unwrap().max_level(), LevelFilter::Info);
+}
+
+#[test_case]
+fn test_ring_buffer_drops_whole_lines() {
+    let mut buffer = RingBuffer::<16>::new();
+    buffer.push(b"first\nsecond\n");
+    assert_eq!(buffer.as_slices(), (&b"first\nsecond\n"[..], &b""[..]));
+
+    buffer.push(b"third\n");
+    let (first, second) = buffer.as_slices();
+    assert_eq!([first, second].concat(), b"second\nthird\n");
+
+    buffer.push(b"a line too long to fit\n");
+    let (first, second) = buffer.as_slices();
+    assert_eq!([first, second].concat(), b"too long to fit\n");
+}
+
+#[test_case]
+fn test_ring_buffer_read() {
+    let mut buffer = RingBuffer::<16>::new();
+    buffer.push(b"first\nsecond\n");
+    buffer.push(b"third\n");
+    assert_eq!(buffer.positions(), 6..19);
+
+    let mut bytes = [0; 8];
+    // the first line was dropped
+    assert_eq!(buffer.read(0, &mut bytes), None);
+    // the bytes wrap around the end of the buffer
+    assert_eq!(buffer.read(10, &mut bytes), Some(8));
+    assert_eq!(&bytes, b"nd\nthird");
+    assert_eq!(buffer.read(18, &mut bytes), Some(1));
+    assert_eq!(buffer.read(19, &mut bytes), Some(0));
+}
+
+#[test_case]
+fn test_records_are_kept() {
+    log::warn!("kept for dmesg");
+
+    let mut contents = String::new();
+    write_contents(|text| contents.push_str(text));
+    let line = contents.lines().last().unwrap();
+    assert!(line.ends_with("WARN  kernel::logger::tests: kept for dmesg"));
+    assert!(line.starts_with('['));
+}
@@ -8,12 +8,8 @@ use core::panic::PanicInfo;
 use alloc::{boxed::Box, rc::Rc, vec, vec::Vec};
 use bootloader_api::config::{BootloaderConfig, Mapping};
 use bootloader_api::{entry_point, BootInfo};
-use drivers::fs::ext2::Ext2;
-use drivers::fs::traits::FileSystem;
 use drivers::println;
 use utils::hlt::hlt_loop;
-use utils::posix::path::PathBuf;
-use x86_64::VirtAddr;
 
 pub static BOOTLOADER_CONFIG: BootloaderConfig = {
     let mut config = BootloaderConfig::new_default();
@@ -46,10 +42,7 @@ fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
     core::mem::drop(rc);
     println!("reference count is {} now", Rc::strong_count(&cloned_rc));
 
-    // read file from ext2 file system
-    let fs = unsafe { Ext2::new(VirtAddr::new(0)).unwrap() };
-    let inode = fs.read(PathBuf::from("/home/dimitri"), None);
-    println!("Inode: {inode:?}");
+    kernel::shell::spawn();
 
     hlt_loop();
 }
@@ -1,5 +1,13 @@
+pub mod address_space;
+pub mod buffer;
+pub mod constants;
+pub mod cow;
 pub mod frame_allocator;
+pub mod global;
 mod init;
+pub mod stack;
+pub mod user;
 
+pub use address_space::AddressSpace;
 pub use frame_allocator::BootInfoFrameAllocator;
 pub use init::init;
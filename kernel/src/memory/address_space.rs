@@ -0,0 +1,272 @@
+use alloc::vec::Vec;
+
+use x86_64::registers::control::Cr3;
+use x86_64::structures::paging::mapper::{MapToError, TranslateError};
+use x86_64::structures::paging::page_table::PageTableEntry;
+use x86_64::structures::paging::{
+    FrameAllocator, FrameDeallocator, Mapper, OffsetPageTable, Page, PageTable, PageTableFlags,
+    PageTableIndex, PhysFrame, Size4KiB, Translate,
+};
+use x86_64::{PhysAddr, VirtAddr};
+
+use super::constants::{PAGE_SIZE, USER_REGION_END, USER_REGION_START};
+use super::global::{self, phys_to_virt};
+use super::{cow, BootInfoFrameAllocator};
+
+/// Page table in which a program runs.
+///
+/// The kernel is mapped like in the kernel page table, by sharing its level 3 tables, and the
+/// user region ([`USER_REGION_START`] to [`USER_REGION_END`]) has its own mappings, which are
+/// freed with the address space.
+///
+/// The level 4 entries of the kernel are copied when the address space is created, so the kernel
+/// must not map memory in new level 4 entries once programs run.
+#[derive(Debug)]
+pub struct AddressSpace {
+    level_4_frame: PhysFrame,
+}
+
+impl AddressSpace {
+    /// Creates an address space with an empty user region.
+    pub fn new() -> Result<Self, MapToError<Size4KiB>> {
+        global::with(|mapper, frame_allocator| {
+            let level_4_frame = frame_allocator
+                .allocate_frame()
+                .ok_or(MapToError::FrameAllocationFailed)?;
+
+            let kernel_table = mapper.level_4_table();
+            let table = unsafe { table_mut(level_4_frame) };
+            for (index, entry) in table.iter_mut().enumerate() {
+                *entry = if index == usize::from(user_entry_index()) {
+                    PageTableEntry::new()
+                } else {
+                    kernel_table[index].clone()
+                };
+            }
+
+            Ok(Self { level_4_frame })
+        })
+    }
+
+    /// Returns the frame of the level 4 table, to be loaded in `CR3`.
+    pub fn level_4_frame(&self) -> PhysFrame {
+        self.level_4_frame
+    }
+
+    /// Maps zeroed pages covering the `size` bytes at `start`, that user code can access with
+    /// `flags`.
+    ///
+    /// Panics if the pages are not in the user region.
+    pub fn map(
+        &mut self,
+        start: VirtAddr,
+        size: u64,
+        flags: PageTableFlags,
+    ) -> Result<(), MapToError<Size4KiB>> {
+        assert!(
+            start.as_u64() >= USER_REGION_START
+                && start.as_u64() + size <= USER_REGION_END
+                && size > 0,
+            "pages outside the user region"
+        );
+
+        let flags = flags | PageTableFlags::PRESENT | PageTableFlags::USER_ACCESSIBLE;
+        let parent_flags =
+            PageTableFlags::PRESENT | PageTableFlags::WRITABLE | PageTableFlags::USER_ACCESSIBLE;
+        let pages = Page::<Size4KiB>::range_inclusive(
+            Page::containing_address(start),
+            Page::containing_address(start + (size - 1)),
+        );
+
+        let mut mapper = self.mapper();
+        global::with_frame_allocator(|frame_allocator| {
+            for page in pages {
+                let frame = frame_allocator
+                    .allocate_frame()
+                    .ok_or(MapToError::FrameAllocationFailed)?;
+
+                unsafe {
+                    phys_to_virt(frame.start_address())
+                        .as_mut_ptr::<u8>()
+                        .write_bytes(0, PAGE_SIZE as usize);
+                    mapper
+                        .map_to_with_table_flags(page, frame, flags, parent_flags, frame_allocator)?
+                        // the address space may not be active, its entries are flushed with CR3
+                        .ignore();
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Copies `data` to `addr`, through the physical memory mapping so that read-only pages can
+    /// be filled.
+    pub fn write(&mut self, addr: VirtAddr, data: &[u8]) -> Result<(), TranslateError> {
+        let mapper = self.mapper();
+        let mut offset = 0;
+
+        while offset < data.len() {
+            let destination = addr + offset as u64;
+            let chunk_size = (PAGE_SIZE - destination.as_u64() % PAGE_SIZE) as usize;
+            let chunk = &data[offset..data.len().min(offset + chunk_size)];
+
+            let frame = mapper
+                .translate_addr(destination)
+                .ok_or(TranslateError::PageNotMapped)?;
+            unsafe {
+                phys_to_virt(frame)
+                    .as_mut_ptr::<u8>()
+                    .copy_from_nonoverlapping(chunk.as_ptr(), chunk.len());
+            }
+
+            offset += chunk.len();
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy of the address space, in which the user pages share their frames
+    /// copy-on-write with this one.
+    ///
+    /// Must be called by the thread that runs in this address space, as it makes the writable
+    /// pages read-only and flushes them from the TLB of the current processor.
+    pub fn fork(&mut self) -> Result<Self, MapToError<Size4KiB>> {
+        let mut child = Self::new()?;
+        let pages = self.user_pages();
+
+        let mut mapper = self.mapper();
+        let mut child_mapper = child.mapper();
+        global::with_frame_allocator(|frame_allocator| -> Result<(), MapToError<Size4KiB>> {
+            for page in pages {
+                let (frame, flags) =
+                    cow::share(&mut mapper, page, frame_allocator).expect("user page not mapped");
+                unsafe {
+                    cow::map_shared(&mut child_mapper, page, frame, flags, frame_allocator)?;
+                }
+            }
+
+            Ok(())
+        })?;
+
+        Ok(child)
+    }
+
+    /// Returns the pages mapped in the user region.
+    fn user_pages(&self) -> Vec<Page> {
+        let mut pages = Vec::new();
+        let level_4 = unsafe { table_mut(self.level_4_frame) };
+        let level_4_index = user_entry_index();
+
+        for (level_3_index, level_3_entry) in tables(&level_4[level_4_index]) {
+            for (level_2_index, level_2_entry) in tables(level_3_entry) {
+                for (level_1_index, level_1_entry) in tables(level_2_entry) {
+                    if level_1_entry.flags().contains(PageTableFlags::PRESENT) {
+                        pages.push(Page::from_page_table_indices(
+                            level_4_index,
+                            level_3_index,
+                            level_2_index,
+                            level_1_index,
+                        ));
+                    }
+                }
+            }
+        }
+
+        pages
+    }
+
+    fn mapper(&mut self) -> OffsetPageTable<'_> {
+        let table = unsafe { table_mut(self.level_4_frame) };
+        unsafe { OffsetPageTable::new(table, phys_to_virt(PhysAddr::new(0))) }
+    }
+}
+
+impl Drop for AddressSpace {
+    fn drop(&mut self) {
+        assert_ne!(
+            Cr3::read().0,
+            self.level_4_frame,
+            "dropping the active address space"
+        );
+
+        global::with_frame_allocator(|frame_allocator| unsafe {
+            let table = table_mut(self.level_4_frame);
+            free_entry(&table[user_entry_index()], 4, frame_allocator);
+            frame_allocator.deallocate_frame(self.level_4_frame);
+        });
+    }
+}
+
+/// Index of the level 4 entry of the user region, the only one that differs between address
+/// spaces.
+fn user_entry_index() -> PageTableIndex {
+    const _: () = assert!(
+        USER_REGION_END - USER_REGION_START == 512 * 512 * 512 * PAGE_SIZE,
+        "the user region must be a single level 4 entry"
+    );
+
+    VirtAddr::new(USER_REGION_START).p4_index()
+}
+
+/// Returns a mapper for the active page table.
+///
+/// # Safety
+///
+/// Nothing else may change the mappings that the mapper changes while it is used.
+pub(super) unsafe fn active_mapper<'a>() -> OffsetPageTable<'a> {
+    let table = unsafe { table_mut(Cr3::read().0) };
+    unsafe { OffsetPageTable::new(table, phys_to_virt(PhysAddr::new(0))) }
+}
+
+/// Returns the entries of the page table that `entry` points to, with their index, or nothing if
+/// it does not point to one.
+fn tables(entry: &PageTableEntry) -> impl Iterator<Item = (PageTableIndex, &PageTableEntry)> {
+    let table = entry
+        .frame()
+        .ok()
+        .map(|frame| unsafe { &*table_mut(frame) });
+    table.into_iter().flat_map(|table| {
+        table
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| (PageTableIndex::new(index as u16), entry))
+    })
+}
+
+/// Returns the page table stored in `frame`.
+///
+/// # Safety
+///
+/// `frame` must hold a page table that is not referenced anywhere else.
+unsafe fn table_mut<'a>(frame: PhysFrame) -> &'a mut PageTable {
+    unsafe { &mut *phys_to_virt(frame.start_address()).as_mut_ptr::<PageTable>() }
+}
+
+/// Frees the frames mapped under `entry`, an entry of a level `level` table, and the page tables
+/// that map them.
+///
+/// # Safety
+///
+/// Nothing may use the memory mapped under `entry` anymore.
+unsafe fn free_entry(
+    entry: &PageTableEntry,
+    level: u8,
+    frame_allocator: &mut BootInfoFrameAllocator,
+) {
+    // huge pages are never mapped in the user region
+    let Ok(frame) = entry.frame() else {
+        return;
+    };
+
+    if level == 1 {
+        unsafe { frame_allocator.release(frame) };
+        return;
+    }
+
+    let table = unsafe { table_mut(frame) };
+    for entry in table.iter() {
+        unsafe { free_entry(entry, level - 1, frame_allocator) };
+    }
+    unsafe { frame_allocator.deallocate_frame(frame) };
+}
@@ -0,0 +1,48 @@
+use core::slice;
+
+use spin::Mutex;
+use x86_64::structures::paging::mapper::MapToError;
+use x86_64::structures::paging::{Page, Size4KiB};
+use x86_64::VirtAddr;
+
+use super::constants::{KERNEL_BUFFERS_START, PAGE_SIZE};
+use super::global;
+use super::stack::{map_page, unmap_pages};
+
+/// Start of the next buffer.
+static NEXT: Mutex<VirtAddr> = Mutex::new(VirtAddr::new_truncate(KERNEL_BUFFERS_START));
+
+/// Maps a buffer of `size` bytes to new frames, outside of the heap.
+///
+/// This is meant for buffers too large for the heap that are used until the kernel stops, like
+/// the back buffer of the screen, so they are never freed. Their contents are not cleared.
+///
+/// Like those of stacks, the virtual addresses of a buffer are never reused, even if mapping it
+/// fails, in which case the pages already mapped are freed.
+pub fn allocate(size: usize) -> Result<&'static mut [u8], MapToError<Size4KiB>> {
+    let (start, end) = {
+        let mut next = NEXT.lock();
+        let start = *next;
+        *next = start + (size as u64).div_ceil(PAGE_SIZE) * PAGE_SIZE;
+        (start, *next)
+    };
+
+    global::with(
+        |mapper, frame_allocator| -> Result<(), MapToError<Size4KiB>> {
+            let start_page = Page::containing_address(start);
+            let end_page = Page::containing_address(end);
+
+            for page in Page::range(start_page, end_page) {
+                if let Err(error) = map_page(page, mapper, frame_allocator) {
+                    unmap_pages(Page::range(start_page, page), mapper, frame_allocator);
+                    return Err(error);
+                }
+            }
+
+            Ok(())
+        },
+    )?;
+
+    // Safety: the pages were just mapped, and are only reachable through this slice
+    Ok(unsafe { slice::from_raw_parts_mut(start.as_mut_ptr(), size) })
+}
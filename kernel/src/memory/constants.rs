@@ -0,0 +1,21 @@
+pub const PAGE_SIZE: u64 = 4096;
+
+/// End of the first MiB of physical memory, which is left to the firmware and to the trampoline
+/// that starts the application processors.
+pub const LOW_MEMORY_END: u64 = 0x10_0000;
+
+/// Start of the virtual region in which kernel stacks are allocated.
+pub const KERNEL_STACKS_START: u64 = 0x_5555_5555_0000;
+
+/// Start of the virtual region in which the kernel buffers too large for the heap are mapped.
+pub const KERNEL_BUFFERS_START: u64 = 0x_6666_6666_0000;
+
+/// Default size of a kernel stack, guard page excluded.
+pub const KERNEL_STACK_SIZE: u64 = 4096 * 5; // 20 KiB
+
+/// Virtual region in which the memory of user programs is allocated.
+pub const USER_REGION_START: u64 = 0x_2000_0000_0000;
+pub const USER_REGION_END: u64 = 0x_2080_0000_0000;
+
+/// Size of the stack of a program, which ends at the top of the user region.
+pub const USER_STACK_SIZE: u64 = 4096 * 16;
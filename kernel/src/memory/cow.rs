@@ -0,0 +1,187 @@
+use core::ptr;
+
+use x86_64::structures::paging::mapper::{
+    FlagUpdateError, MapToError, MappedFrame, TranslateResult,
+};
+use x86_64::structures::paging::{
+    FrameAllocator, Mapper, OffsetPageTable, Page, PageTableFlags, PhysFrame, Size4KiB, Translate,
+};
+use x86_64::VirtAddr;
+
+use super::constants::{PAGE_SIZE, USER_REGION_END, USER_REGION_START};
+use super::{address_space, global, BootInfoFrameAllocator};
+
+/// Page table flag marking a read-only page whose frame is shared copy-on-write.
+///
+/// Uses one of the bits that the CPU leaves available to the operating system.
+pub const COPY_ON_WRITE: PageTableFlags = PageTableFlags::BIT_9;
+
+/// Marks `page` as copy-on-write in `mapper` and adds a reference to its frame.
+///
+/// Returns the frame and the flags with which it must be mapped by the new owner,
+/// using [`map_shared`].
+pub fn share(
+    mapper: &mut OffsetPageTable,
+    page: Page,
+    frame_allocator: &mut BootInfoFrameAllocator,
+) -> Result<(PhysFrame, PageTableFlags), FlagUpdateError> {
+    let TranslateResult::Mapped {
+        frame: MappedFrame::Size4KiB(frame),
+        flags,
+        ..
+    } = mapper.translate(page.start_address())
+    else {
+        return Err(FlagUpdateError::PageNotMapped);
+    };
+
+    let flags = if flags.contains(PageTableFlags::WRITABLE) {
+        let flags = (flags - PageTableFlags::WRITABLE) | COPY_ON_WRITE;
+        unsafe { mapper.update_flags(page, flags)?.flush() };
+        flags
+    } else {
+        // Read-only pages never need to be copied, and pages that are already
+        // copy-on-write keep their flags.
+        flags
+    };
+
+    frame_allocator.share(frame);
+    Ok((frame, flags))
+}
+
+/// Maps a frame returned by [`share`] at `page`.
+///
+/// # Safety
+///
+/// The caller must guarantee that `frame` and `flags`
+/// come from [`share`], and that `page` is not used for anything else.
+pub unsafe fn map_shared(
+    mapper: &mut OffsetPageTable,
+    page: Page,
+    frame: PhysFrame,
+    flags: PageTableFlags,
+    frame_allocator: &mut BootInfoFrameAllocator,
+) -> Result<(), MapToError<Size4KiB>> {
+    unsafe { mapper.map_to(page, frame, flags, frame_allocator)?.flush() };
+    Ok(())
+}
+
+/// Resolves a write to a copy-on-write page.
+///
+/// If the frame is still shared, it is copied to a new frame that only this mapping owns.
+/// If this mapping is the last reference, the page is simply made writable again.
+///
+/// Pages of the user region are looked up in the active address space, and the other ones in
+/// the kernel page table.
+///
+/// A fault from user mode (`user_mode`) cannot happen while the faulting core holds the memory
+/// management locks, so it waits for them. A fault from kernel mode may, so it does not wait:
+/// system calls copy the pages of the user buffers they write beforehand, with
+/// [`resolve_user_range`].
+///
+/// Returns `false` if `addr` is not in a copy-on-write page, or if the fault is from kernel mode
+/// and the memory management locks are taken, in which case the fault must be handled as a
+/// regular one.
+pub fn handle_write_fault(addr: VirtAddr, user_mode: bool) -> bool {
+    let page = Page::containing_address(addr);
+
+    if (USER_REGION_START..USER_REGION_END).contains(&addr.as_u64()) {
+        // Safety: only the thread of the address space changes its user mappings, and it is the
+        // one that faulted
+        let copy = |frame_allocator: &mut BootInfoFrameAllocator| unsafe {
+            let mut mapper = address_space::active_mapper();
+            copy_on_write(&mut mapper, page, frame_allocator).is_some()
+        };
+
+        return if user_mode {
+            global::with_frame_allocator(copy)
+        } else {
+            global::try_with_frame_allocator(copy).unwrap_or(false)
+        };
+    }
+
+    global::try_with(|mapper, frame_allocator| unsafe {
+        copy_on_write(mapper, page, frame_allocator).is_some()
+    })
+    .unwrap_or(false)
+}
+
+/// Copies the copy-on-write pages among the `size` bytes at `addr` in the user region of the
+/// active address space, so that the kernel writes them without faulting.
+///
+/// System calls call this before writing to the buffers of the user code, as a fault from kernel
+/// mode may not wait for the frame allocator, see [`handle_write_fault`]. The bytes must be
+/// accessible, see [`user::is_accessible`](super::user::is_accessible).
+///
+/// Returns `false` if a page could not be copied, for lack of memory.
+pub fn resolve_user_range(addr: u64, size: u64) -> bool {
+    if size == 0 {
+        return true;
+    }
+    let first = Page::<Size4KiB>::containing_address(VirtAddr::new(addr));
+    let last = Page::containing_address(VirtAddr::new(addr + size - 1));
+
+    global::with_frame_allocator(|frame_allocator| {
+        // Safety: only the thread of the address space changes its user mappings, and it is the
+        // one making the system call
+        let mut mapper = unsafe { address_space::active_mapper() };
+        Page::range_inclusive(first, last).all(|page| {
+            let shared = matches!(
+                mapper.translate(page.start_address()),
+                TranslateResult::Mapped { flags, .. } if flags.contains(COPY_ON_WRITE)
+            );
+            !shared || unsafe { copy_on_write(&mut mapper, page, frame_allocator) }.is_some()
+        })
+    })
+}
+
+/// # Safety
+///
+/// The caller must guarantee that `page` belongs to the
+/// address space that `mapper` manages and that no other core is using its translation.
+unsafe fn copy_on_write(
+    mapper: &mut OffsetPageTable,
+    page: Page,
+    frame_allocator: &mut BootInfoFrameAllocator,
+) -> Option<()> {
+    let TranslateResult::Mapped {
+        frame: MappedFrame::Size4KiB(frame),
+        flags,
+        ..
+    } = mapper.translate(page.start_address())
+    else {
+        return None;
+    };
+
+    if !flags.contains(COPY_ON_WRITE) {
+        return None;
+    }
+
+    let flags = (flags - COPY_ON_WRITE) | PageTableFlags::WRITABLE;
+
+    if frame_allocator.reference_count(frame) == 1 {
+        unsafe { mapper.update_flags(page, flags).ok()?.flush() };
+        return Some(());
+    }
+
+    let new_frame = frame_allocator.allocate_frame()?;
+    let phys_offset = mapper.phys_offset();
+    unsafe {
+        ptr::copy_nonoverlapping(
+            (phys_offset + frame.start_address().as_u64()).as_ptr::<u8>(),
+            (phys_offset + new_frame.start_address().as_u64()).as_mut_ptr::<u8>(),
+            PAGE_SIZE as usize,
+        );
+    }
+
+    let (_, flush) = mapper.unmap(page).ok()?;
+    flush.flush();
+    unsafe {
+        mapper
+            .map_to(page, new_frame, flags, frame_allocator)
+            .ok()?
+            .flush();
+        frame_allocator.release(frame);
+    }
+
+    Some(())
+}
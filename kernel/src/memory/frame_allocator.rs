@@ -1,13 +1,28 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
 use bootloader_api::info::{MemoryRegionKind, MemoryRegions};
 use x86_64::{
-    structures::paging::{FrameAllocator, PhysFrame, Size4KiB},
+    structures::paging::{FrameAllocator, FrameDeallocator, PhysFrame, Size4KiB},
     PhysAddr,
 };
 
+use super::constants::LOW_MEMORY_END;
+
 /// A FrameAllocator that returns usable frames from the bootloader's memory map.
+///
+/// Frames below [`LOW_MEMORY_END`] are never allocated.
+///
+/// Every allocated frame has a reference count, so that it can be shared between several
+/// mappings (see [`crate::memory::cow`]). Frames are only given back to the allocator once
+/// their last reference is released.
 pub struct BootInfoFrameAllocator {
     memory_map: &'static MemoryRegions,
     next: usize,
+    /// Reference counts of the frames that are mapped more than once.
+    /// A frame that is not in the map has a single reference.
+    shared_frames: BTreeMap<PhysFrame, usize>,
+    /// Frames that were released and can be allocated again.
+    free_frames: Vec<PhysFrame>,
 }
 
 impl BootInfoFrameAllocator {
@@ -20,27 +35,99 @@ impl BootInfoFrameAllocator {
         BootInfoFrameAllocator {
             memory_map,
             next: 0,
+            shared_frames: BTreeMap::new(),
+            free_frames: Vec::new(),
         }
     }
 
     /// Returns an iterator over the usable frames specified in the memory map.
-    fn usable_frames(&mut self) -> impl Iterator<Item = PhysFrame> {
+    fn usable_frames(&self) -> impl Iterator<Item = PhysFrame> + 'static {
         // get usable regions from memory map
         let regions = self.memory_map.iter();
         let usable_regions = regions.filter(|r| r.kind == MemoryRegionKind::Usable);
         // map each region to its address range
         let addr_ranges = usable_regions.map(|r| r.start..r.end);
-        // transform to an iterator of frame start addresses
-        let frame_addresses = addr_ranges.flat_map(|r| r.step_by(4096));
+        // transform to an iterator of frame start addresses, skipping low memory
+        let frame_addresses = addr_ranges
+            .flat_map(|r| r.step_by(4096))
+            .filter(|&addr| addr >= LOW_MEMORY_END);
         // create `PhysFrame` types from the start addresses
         frame_addresses.map(|addr| PhysFrame::containing_address(PhysAddr::new(addr)))
     }
+
+    /// Returns `true` if the memory map marks `frame` as usable.
+    ///
+    /// This is meant to check that a frame below [`LOW_MEMORY_END`], which is never allocated,
+    /// is free to use.
+    pub fn is_usable(&self, frame: PhysFrame) -> bool {
+        let addr = frame.start_address().as_u64();
+        self.memory_map
+            .iter()
+            .any(|r| r.kind == MemoryRegionKind::Usable && r.start <= addr && addr + 4096 <= r.end)
+    }
+
+    /// Returns the number of frames that can be allocated, including the allocated ones.
+    pub fn usable_frame_count(&self) -> usize {
+        self.usable_frames().count()
+    }
+
+    /// Returns the number of frames that are allocated.
+    pub fn allocated_frame_count(&self) -> usize {
+        self.next.min(self.usable_frame_count()) - self.free_frames.len()
+    }
+
+    /// Returns the number of mappings that reference `frame`.
+    pub fn reference_count(&self, frame: PhysFrame) -> usize {
+        self.shared_frames.get(&frame).copied().unwrap_or(1)
+    }
+
+    /// Adds a reference to an allocated frame.
+    pub fn share(&mut self, frame: PhysFrame) {
+        *self.shared_frames.entry(frame).or_insert(1) += 1;
+    }
+
+    /// Removes a reference to an allocated frame, and frees it if it was the last one.
+    ///
+    /// Returns `true` if the frame was freed.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that the reference being released is no longer used.
+    pub unsafe fn release(&mut self, frame: PhysFrame) -> bool {
+        match self.shared_frames.get_mut(&frame) {
+            Some(count) if *count > 2 => {
+                *count -= 1;
+                false
+            }
+            Some(_) => {
+                self.shared_frames.remove(&frame);
+                false
+            }
+            None => {
+                unsafe { self.deallocate_frame(frame) };
+                true
+            }
+        }
+    }
 }
 
 unsafe impl FrameAllocator<Size4KiB> for BootInfoFrameAllocator {
     fn allocate_frame(&mut self) -> Option<PhysFrame> {
+        if let Some(frame) = self.free_frames.pop() {
+            return Some(frame);
+        }
+
         let frame = self.usable_frames().nth(self.next);
         self.next += 1;
         frame
     }
 }
+
+impl FrameDeallocator<Size4KiB> for BootInfoFrameAllocator {
+    unsafe fn deallocate_frame(&mut self, frame: PhysFrame) {
+        self.shared_frames.remove(&frame);
+        self.free_frames.push(frame);
+    }
+}
+
+unsafe impl Send for BootInfoFrameAllocator {}
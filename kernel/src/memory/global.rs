@@ -0,0 +1,104 @@
+use spin::{Mutex, Once};
+use x86_64::instructions::interrupts;
+use x86_64::registers::control::Cr3;
+use x86_64::structures::paging::{OffsetPageTable, PhysFrame};
+use x86_64::{PhysAddr, VirtAddr};
+
+use super::BootInfoFrameAllocator;
+
+/// The active kernel page table, available once [`init`] has been called.
+pub static MAPPER: Once<Mutex<OffsetPageTable<'static>>> = Once::new();
+
+/// The physical frame allocator, available once [`init`] has been called.
+pub static FRAME_ALLOCATOR: Once<Mutex<BootInfoFrameAllocator>> = Once::new();
+
+/// Virtual address at which the bootloader mapped the complete physical memory.
+static PHYSICAL_MEMORY_OFFSET: Once<VirtAddr> = Once::new();
+
+/// Level 4 table of the kernel page table, used by the threads that have no address space.
+static KERNEL_LEVEL_4_FRAME: Once<PhysFrame> = Once::new();
+
+/// Makes the mapper and the frame allocator available to the rest of the kernel.
+pub fn init(mapper: OffsetPageTable<'static>, frame_allocator: BootInfoFrameAllocator) {
+    PHYSICAL_MEMORY_OFFSET.call_once(|| mapper.phys_offset());
+    KERNEL_LEVEL_4_FRAME.call_once(|| Cr3::read().0);
+    MAPPER.call_once(|| Mutex::new(mapper));
+    FRAME_ALLOCATOR.call_once(|| Mutex::new(frame_allocator));
+}
+
+/// Runs `f` with both the mapper and the frame allocator locked.
+///
+/// The locks are always taken in the same order (mapper first) to avoid deadlocks, and
+/// interrupts are disabled while they are held, so that the current thread cannot be preempted
+/// by a thread waiting for them.
+/// Panics if [`init`] has not been called yet.
+pub fn with<R>(
+    f: impl FnOnce(&mut OffsetPageTable<'static>, &mut BootInfoFrameAllocator) -> R,
+) -> R {
+    interrupts::without_interrupts(|| {
+        let mut mapper = MAPPER.r#try().expect("memory not initialized").lock();
+        let mut frame_allocator = FRAME_ALLOCATOR
+            .r#try()
+            .expect("memory not initialized")
+            .lock();
+
+        f(&mut mapper, &mut frame_allocator)
+    })
+}
+
+/// Like [`with`], but only locks the frame allocator.
+///
+/// This is meant for page tables that are not the kernel one, see
+/// [`AddressSpace`](super::address_space::AddressSpace).
+pub fn with_frame_allocator<R>(f: impl FnOnce(&mut BootInfoFrameAllocator) -> R) -> R {
+    interrupts::without_interrupts(|| {
+        let mut frame_allocator = FRAME_ALLOCATOR
+            .r#try()
+            .expect("memory not initialized")
+            .lock();
+
+        f(&mut frame_allocator)
+    })
+}
+
+/// Like [`with`], but returns [`None`] instead of waiting if either lock is already taken,
+/// or if [`init`] has not been called yet.
+///
+/// This is meant for interrupt handlers, which would deadlock if they interrupted code
+/// holding one of the locks.
+pub fn try_with<R>(
+    f: impl FnOnce(&mut OffsetPageTable<'static>, &mut BootInfoFrameAllocator) -> R,
+) -> Option<R> {
+    let mut mapper = MAPPER.r#try()?.try_lock()?;
+    let mut frame_allocator = FRAME_ALLOCATOR.r#try()?.try_lock()?;
+
+    Some(f(&mut mapper, &mut frame_allocator))
+}
+
+/// Like [`with_frame_allocator`], but returns [`None`] instead of waiting if the lock is already
+/// taken, or if [`init`] has not been called yet.
+pub fn try_with_frame_allocator<R>(f: impl FnOnce(&mut BootInfoFrameAllocator) -> R) -> Option<R> {
+    let mut frame_allocator = FRAME_ALLOCATOR.r#try()?.try_lock()?;
+
+    Some(f(&mut frame_allocator))
+}
+
+/// Returns the virtual address through which the physical address `addr` can be accessed.
+///
+/// Panics if [`init`] has not been called yet.
+pub fn phys_to_virt(addr: PhysAddr) -> VirtAddr {
+    let offset = PHYSICAL_MEMORY_OFFSET
+        .r#try()
+        .expect("memory not initialized");
+
+    *offset + addr.as_u64()
+}
+
+/// Returns the frame of the level 4 table of the kernel page table.
+///
+/// Panics if [`init`] has not been called yet.
+pub fn kernel_level_4_frame() -> PhysFrame {
+    *KERNEL_LEVEL_4_FRAME
+        .r#try()
+        .expect("memory not initialized")
+}
@@ -0,0 +1,174 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use spin::Mutex;
+use x86_64::structures::paging::mapper::MapToError;
+use x86_64::structures::paging::{
+    FrameAllocator, FrameDeallocator, Mapper, Page, PageTableFlags, Size4KiB,
+};
+use x86_64::VirtAddr;
+
+use super::constants::{KERNEL_STACKS_START, PAGE_SIZE};
+use super::global;
+
+/// Bounds of a kernel stack.
+///
+/// The stack grows downwards from `end` to `start`. The page right below `start` is
+/// never mapped, so that overflowing the stack triggers a page fault instead of
+/// silently corrupting the memory below it.
+#[derive(Debug, Clone, Copy)]
+pub struct StackBounds {
+    start: VirtAddr,
+    end: VirtAddr,
+}
+
+impl StackBounds {
+    /// Lowest usable address of the stack.
+    pub fn start(&self) -> VirtAddr {
+        self.start
+    }
+
+    /// Highest address of the stack, which is the initial stack pointer.
+    pub fn end(&self) -> VirtAddr {
+        self.end
+    }
+
+    /// The unmapped page right below the stack.
+    pub fn guard_page(&self) -> Page {
+        Page::containing_address(self.start - 1_u64)
+    }
+
+    pub fn size(&self) -> u64 {
+        self.end - self.start
+    }
+}
+
+/// A kernel stack registered for overflow detection.
+#[derive(Debug)]
+pub struct KernelStack {
+    /// Name of the task using the stack, reported on overflow.
+    pub name: String,
+    pub bounds: StackBounds,
+}
+
+struct Registry {
+    next: VirtAddr,
+    stacks: Vec<KernelStack>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    next: VirtAddr::new_truncate(KERNEL_STACKS_START),
+    stacks: Vec::new(),
+});
+
+/// Allocates a kernel stack of at least `size` bytes, preceded by an unmapped guard page.
+///
+/// The virtual addresses of the stack are never reused, even once it is [freed](free) or if
+/// mapping it fails, in which case the pages already mapped are freed.
+pub fn allocate(name: &str, size: u64) -> Result<StackBounds, MapToError<Size4KiB>> {
+    let mut registry = REGISTRY.lock();
+
+    let guard_page = Page::<Size4KiB>::containing_address(registry.next);
+    let start = guard_page.start_address() + PAGE_SIZE;
+    let end = start + size.div_ceil(PAGE_SIZE) * PAGE_SIZE;
+    let bounds = StackBounds { start, end };
+    registry.next = end;
+
+    global::with(
+        |mapper, frame_allocator| -> Result<(), MapToError<Size4KiB>> {
+            let start_page = Page::containing_address(start);
+            let end_page = Page::containing_address(end - 1_u64);
+
+            for page in Page::range_inclusive(start_page, end_page) {
+                if let Err(error) = map_page(page, mapper, frame_allocator) {
+                    unmap_pages(Page::range(start_page, page), mapper, frame_allocator);
+                    return Err(error);
+                }
+            }
+
+            Ok(())
+        },
+    )?;
+
+    registry.stacks.push(KernelStack {
+        name: String::from(name),
+        bounds,
+    });
+
+    Ok(bounds)
+}
+
+/// Maps `page` to a new frame, which is freed again if the mapping fails.
+pub(super) fn map_page(
+    page: Page,
+    mapper: &mut impl Mapper<Size4KiB>,
+    frame_allocator: &mut (impl FrameAllocator<Size4KiB> + FrameDeallocator<Size4KiB>),
+) -> Result<(), MapToError<Size4KiB>> {
+    let frame = frame_allocator
+        .allocate_frame()
+        .ok_or(MapToError::FrameAllocationFailed)?;
+    let flags = PageTableFlags::PRESENT | PageTableFlags::WRITABLE;
+
+    match unsafe { mapper.map_to(page, frame, flags, frame_allocator) } {
+        Ok(flush) => {
+            flush.flush();
+            Ok(())
+        }
+        Err(error) => {
+            unsafe { frame_allocator.deallocate_frame(frame) };
+            Err(error)
+        }
+    }
+}
+
+/// Unmaps the mapped pages of `pages`, and frees their frames.
+pub(super) fn unmap_pages(
+    pages: impl Iterator<Item = Page>,
+    mapper: &mut impl Mapper<Size4KiB>,
+    frame_allocator: &mut impl FrameDeallocator<Size4KiB>,
+) {
+    for page in pages {
+        if let Ok((frame, flush)) = mapper.unmap(page) {
+            flush.flush();
+            unsafe { frame_allocator.deallocate_frame(frame) };
+        }
+    }
+}
+
+/// Unmaps a stack returned by [`allocate`] and frees its frames.
+///
+/// # Safety
+///
+/// The stack must not be used anymore, in particular it must not be the current stack.
+pub unsafe fn free(bounds: StackBounds) {
+    let mut registry = REGISTRY.lock();
+    registry
+        .stacks
+        .retain(|stack| stack.bounds.start != bounds.start);
+
+    global::with(|mapper, frame_allocator| {
+        let start_page = Page::<Size4KiB>::containing_address(bounds.start);
+        let end_page = Page::containing_address(bounds.end - 1_u64);
+
+        unmap_pages(
+            Page::range_inclusive(start_page, end_page),
+            mapper,
+            frame_allocator,
+        );
+    });
+}
+
+/// Calls `f` with the stack whose guard page contains `addr`, if there is one.
+///
+/// This is meant to be called from the page fault handler, so the registry is only
+/// tried once instead of being waited on: if the fault happened while it was locked,
+/// this returns [`None`].
+pub fn with_overflowed_stack<R>(addr: VirtAddr, f: impl FnOnce(&KernelStack) -> R) -> Option<R> {
+    let registry = REGISTRY.try_lock()?;
+    let page = Page::containing_address(addr);
+
+    registry
+        .stacks
+        .iter()
+        .find(|stack| stack.bounds.guard_page() == page)
+        .map(f)
+}
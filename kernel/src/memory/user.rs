@@ -0,0 +1,70 @@
+use x86_64::registers::control::Cr3;
+use x86_64::structures::paging::{Page, PageTable, PageTableFlags, Size4KiB};
+use x86_64::VirtAddr;
+
+use super::constants::{USER_REGION_END, USER_REGION_START};
+use super::cow::COPY_ON_WRITE;
+use super::global::phys_to_virt;
+
+/// Flags that every level of the page table must set for a page to allow an access.
+const ACCESS_FLAGS: PageTableFlags =
+    PageTableFlags::WRITABLE.union(PageTableFlags::USER_ACCESSIBLE);
+
+/// Returns `true` if user code can access the `size` bytes at `addr` in the active address space,
+/// and write them if `write` is set.
+///
+/// This is meant to check the buffers given by system calls before the kernel accesses them.
+/// Copy-on-write pages count as writable, as they are copied before the kernel writes them, see
+/// [`cow::resolve_user_range`](super::cow::resolve_user_range).
+pub fn is_accessible(addr: u64, size: u64, write: bool) -> bool {
+    let Some(end) = addr.checked_add(size) else {
+        return false;
+    };
+    if addr < USER_REGION_START || end > USER_REGION_END {
+        return false;
+    }
+    if size == 0 {
+        return true;
+    }
+
+    let first = Page::<Size4KiB>::containing_address(VirtAddr::new(addr));
+    let last = Page::<Size4KiB>::containing_address(VirtAddr::new(end - 1));
+    Page::range_inclusive(first, last).all(|page| {
+        let flags = flags(page);
+        flags.contains(PageTableFlags::PRESENT | PageTableFlags::USER_ACCESSIBLE)
+            && (!write || flags.intersects(PageTableFlags::WRITABLE | COPY_ON_WRITE))
+    })
+}
+
+/// Returns the flags with which `page` is mapped in the active address space, or empty flags if it
+/// is not mapped.
+///
+/// The [`ACCESS_FLAGS`] are only set if every level sets them, the other flags are the ones of the
+/// page.
+///
+/// Only the address space of the current thread is walked, and only its thread changes its user
+/// mappings, so they cannot change meanwhile.
+fn flags(page: Page) -> PageTableFlags {
+    let indexes = [
+        page.p4_index(),
+        page.p3_index(),
+        page.p2_index(),
+        page.p1_index(),
+    ];
+    let mut frame = Cr3::read().0.start_address();
+    let mut flags = PageTableFlags::all();
+
+    for index in indexes {
+        let table = unsafe { &*phys_to_virt(frame).as_ptr::<PageTable>() };
+        let entry = &table[index];
+        flags = entry.flags() & (flags | !ACCESS_FLAGS);
+
+        // huge pages are never mapped in the user region
+        if !flags.contains(PageTableFlags::PRESENT) || flags.contains(PageTableFlags::HUGE_PAGE) {
+            return PageTableFlags::empty();
+        }
+        frame = entry.addr();
+    }
+
+    flags
+}
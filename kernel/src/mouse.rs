@@ -0,0 +1,11 @@
+mod event;
+mod packet;
+mod stream;
+
+pub use event::{Buttons, MouseEvent};
+pub use packet::{PacketDecoder, PacketFormat};
+pub use stream::MouseEventStream;
+pub(crate) use stream::{add_byte, init};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,27 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// Mouse buttons held down.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct Buttons: u8 {
+        const LEFT = 1 << 0;
+        const RIGHT = 1 << 1;
+        const MIDDLE = 1 << 2;
+        /// Side button, usually "back"
+        const FOURTH = 1 << 3;
+        /// Side button, usually "forward"
+        const FIFTH = 1 << 4;
+    }
+}
+
+/// A movement of the mouse, or a change of its buttons, since the previous event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MouseEvent {
+    /// Horizontal movement, positive to the right
+    pub dx: i16,
+    /// Vertical movement, positive downwards like the rows of the screen
+    pub dy: i16,
+    /// Wheel movement, positive when scrolling down, towards the user
+    pub wheel: i8,
+    pub buttons: Buttons,
+}
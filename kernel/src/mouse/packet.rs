@@ -0,0 +1,113 @@
+use super::event::{Buttons, MouseEvent};
+
+/// Bits of the first byte of every packet.
+const LEFT_BUTTON: u8 = 1 << 0;
+const RIGHT_BUTTON: u8 = 1 << 1;
+const MIDDLE_BUTTON: u8 = 1 << 2;
+/// Always set, which lets the decoder find the start of the packets again after losing a byte.
+const ALWAYS_ONE: u8 = 1 << 3;
+const X_SIGN: u8 = 1 << 4;
+const Y_SIGN: u8 = 1 << 5;
+const X_OVERFLOW: u8 = 1 << 6;
+const Y_OVERFLOW: u8 = 1 << 7;
+
+/// Bits of the fourth byte of the packets of five-button mice.
+const FOURTH_BUTTON: u8 = 1 << 4;
+const FIFTH_BUTTON: u8 = 1 << 5;
+
+/// The packets that a PS/2 mouse sends, depending on the extensions it enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketFormat {
+    /// 3 bytes: buttons and movement
+    Standard,
+    /// 4 bytes, the last one for the wheel (IntelliMouse)
+    Wheel,
+    /// 4 bytes, the last one for the wheel and two more buttons (IntelliMouse Explorer)
+    FiveButtons,
+}
+
+impl PacketFormat {
+    pub fn size(self) -> usize {
+        match self {
+            Self::Standard => 3,
+            Self::Wheel | Self::FiveButtons => 4,
+        }
+    }
+}
+
+/// Assembles the bytes sent by a PS/2 mouse into packets, and decodes them into
+/// [`MouseEvent`]s.
+#[derive(Debug, Clone)]
+pub struct PacketDecoder {
+    format: PacketFormat,
+    bytes: [u8; 4],
+    received: usize,
+}
+
+impl PacketDecoder {
+    pub fn new(format: PacketFormat) -> Self {
+        Self {
+            format,
+            bytes: [0; 4],
+            received: 0,
+        }
+    }
+
+    pub fn format(&self) -> PacketFormat {
+        self.format
+    }
+
+    /// Adds a byte received from the mouse, and returns the event of the packet that it
+    /// completes.
+    ///
+    /// Bytes that cannot start a packet are dropped.
+    pub fn add_byte(&mut self, byte: u8) -> Option<MouseEvent> {
+        if self.received == 0 && byte & ALWAYS_ONE == 0 {
+            return None;
+        }
+
+        self.bytes[self.received] = byte;
+        self.received += 1;
+        if self.received < self.format.size() {
+            return None;
+        }
+        self.received = 0;
+        Some(self.decode())
+    }
+
+    fn decode(&self) -> MouseEvent {
+        let [flags, x, y, extra] = self.bytes;
+
+        let mut buttons = Buttons::empty();
+        buttons.set(Buttons::LEFT, flags & LEFT_BUTTON != 0);
+        buttons.set(Buttons::RIGHT, flags & RIGHT_BUTTON != 0);
+        buttons.set(Buttons::MIDDLE, flags & MIDDLE_BUTTON != 0);
+
+        // the movements are 9-bit two's complement numbers, whose sign bits are in the flags
+        let movement = |value: u8, sign: u8, overflow: u8| match flags & overflow {
+            0 => value as i16 - if flags & sign != 0 { 0x100 } else { 0 },
+            _ => 0,
+        };
+        let dx = movement(x, X_SIGN, X_OVERFLOW);
+        // the mouse counts upwards movements as positive
+        let dy = -movement(y, Y_SIGN, Y_OVERFLOW);
+
+        let wheel = match self.format {
+            PacketFormat::Standard => 0,
+            PacketFormat::Wheel => extra as i8,
+            PacketFormat::FiveButtons => {
+                buttons.set(Buttons::FOURTH, extra & FOURTH_BUTTON != 0);
+                buttons.set(Buttons::FIFTH, extra & FIFTH_BUTTON != 0);
+                // 4-bit two's complement number
+                ((extra << 4) as i8) >> 4
+            }
+        };
+
+        MouseEvent {
+            dx,
+            dy,
+            wheel,
+            buttons,
+        }
+    }
+}
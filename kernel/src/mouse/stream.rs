@@ -0,0 +1,90 @@
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll};
+
+use crossbeam_queue::ArrayQueue;
+use futures_util::stream::Stream;
+use futures_util::task::AtomicWaker;
+use spin::Once;
+use utils::sync::IrqSpinlock;
+
+use super::event::MouseEvent;
+use super::packet::{PacketDecoder, PacketFormat};
+
+/// Maximum number of events decoded and not taken from the stream yet.
+const EVENT_QUEUE_SIZE: usize = 100;
+
+/// Decodes the bytes received by the interrupt handler, `None` until [`init`].
+static DECODER: IrqSpinlock<Option<PacketDecoder>> = IrqSpinlock::new("mouse decoder", None);
+static EVENT_QUEUE: Once<ArrayQueue<MouseEvent>> = Once::new();
+static WAKER: AtomicWaker = AtomicWaker::new();
+static STREAM_CREATED: AtomicBool = AtomicBool::new(false);
+
+/// Decodes the packets of a mouse that sends them in `format`.
+///
+/// Called by the PS/2 driver once it found the mouse, before routing its interrupts.
+pub(crate) fn init(format: PacketFormat) {
+    EVENT_QUEUE.call_once(|| ArrayQueue::new(EVENT_QUEUE_SIZE));
+    *DECODER.lock() = Some(PacketDecoder::new(format));
+}
+
+/// Adds a byte received from the mouse, and queues the event of the packet that it completes
+/// for the [`MouseEventStream`].
+///
+/// Called by the mouse interrupt handler, so it must not block nor allocate.
+pub(crate) fn add_byte(byte: u8) {
+    let Some(event) = DECODER
+        .lock()
+        .as_mut()
+        .and_then(|decoder| decoder.add_byte(byte))
+    else {
+        return;
+    };
+
+    // events received while the queue is full are dropped
+    if let Some(queue) = EVENT_QUEUE.r#try() {
+        if queue.push(event).is_ok() {
+            WAKER.wake();
+        }
+    }
+}
+
+/// The events of the mouse. There can be only one stream, which never ends if there is no mouse.
+pub struct MouseEventStream {
+    _private: (),
+}
+
+impl MouseEventStream {
+    pub fn new() -> Self {
+        let created = STREAM_CREATED.swap(true, Ordering::Relaxed);
+        assert!(!created, "MouseEventStream::new should only be called once");
+
+        MouseEventStream { _private: () }
+    }
+}
+
+impl Default for MouseEventStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stream for MouseEventStream {
+    type Item = MouseEvent;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<MouseEvent>> {
+        // without a mouse, the queue is never created and the stream stays pending
+        WAKER.register(context.waker());
+        let Some(queue) = EVENT_QUEUE.r#try() else {
+            return Poll::Pending;
+        };
+
+        match queue.pop() {
+            Some(event) => {
+                WAKER.take();
+                Poll::Ready(Some(event))
+            }
+            None => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+
+use super::{Buttons, MouseEvent, PacketDecoder, PacketFormat};
+
+fn decode(format: PacketFormat, bytes: &[u8]) -> Vec<MouseEvent> {
+    let mut decoder = PacketDecoder::new(format);
+    bytes
+        .iter()
+        .filter_map(|&byte| decoder.add_byte(byte))
+        .collect()
+}
+
+#[test_case]
+fn test_standard_packets() {
+    // left button, 5 to the right and 3 up, then 2 to the left and 4 down
+    let events = decode(
+        PacketFormat::Standard,
+        &[0x09, 0x05, 0x03, 0x38, 0xfe, 0xfc],
+    );
+
+    assert_eq!(
+        events,
+        [
+            MouseEvent {
+                dx: 5,
+                dy: -3,
+                wheel: 0,
+                buttons: Buttons::LEFT,
+            },
+            MouseEvent {
+                dx: -2,
+                dy: 4,
+                wheel: 0,
+                buttons: Buttons::empty(),
+            },
+        ]
+    );
+}
+
+#[test_case]
+fn test_overflow() {
+    let events = decode(PacketFormat::Standard, &[0x4a, 0xff, 0x10]);
+
+    assert_eq!(events[0].dx, 0);
+    assert_eq!(events[0].dy, -16);
+    assert_eq!(events[0].buttons, Buttons::RIGHT);
+}
+
+#[test_case]
+fn test_resynchronize() {
+    // the first byte of a packet always has bit 3 set, the others are dropped until then
+    let events = decode(PacketFormat::Standard, &[0x00, 0x05, 0x0c, 0x01, 0x01]);
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].buttons, Buttons::MIDDLE);
+}
+
+#[test_case]
+fn test_wheel_packets() {
+    let events = decode(PacketFormat::Wheel, &[0x08, 0x00, 0x00, 0xff]);
+    assert_eq!(events[0].wheel, -1);
+
+    let events = decode(
+        PacketFormat::FiveButtons,
+        &[0x08, 0x00, 0x00, 0x1f, 0x08, 0x00, 0x00, 0x22],
+    );
+    assert_eq!(events[0].wheel, -1);
+    assert_eq!(events[0].buttons, Buttons::FOURTH);
+    assert_eq!(events[1].wheel, 2);
+    assert_eq!(events[1].buttons, Buttons::FIFTH);
+}
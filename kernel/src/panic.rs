@@ -3,7 +3,10 @@ use core::panic::PanicInfo;
 use drivers::println;
 use utils::hlt::hlt_loop;
 
+use crate::backtrace::Backtrace;
+
 pub fn handler(info: &PanicInfo) -> ! {
     println!("{info}");
+    println!("{}", Backtrace::capture());
     hlt_loop();
 }
@@ -0,0 +1,5 @@
+mod reboot;
+mod shutdown;
+
+pub use reboot::reboot;
+pub use shutdown::shutdown;
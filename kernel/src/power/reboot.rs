@@ -0,0 +1,62 @@
+use log::warn;
+use x86_64::instructions::port::Port;
+use x86_64::structures::idt::InterruptDescriptorTable;
+
+use crate::acpi::fadt::Fadt;
+use crate::acpi::find_table;
+
+const KEYBOARD_CONTROLLER_STATUS: u16 = 0x64;
+const KEYBOARD_CONTROLLER_COMMAND: u16 = 0x64;
+/// Set in the status register while the controller has not read the last command.
+const INPUT_BUFFER_FULL: u8 = 1 << 1;
+/// Pulses the reset line of the CPU.
+const RESET_COMMAND: u8 = 0xfe;
+
+/// Resets the system with the ACPI reset register, then the keyboard controller, and finally a
+/// triple fault.
+pub fn reboot() -> ! {
+    x86_64::instructions::interrupts::disable();
+
+    if let Some((reset_register, reset_value)) = find_table::<Fadt>().and_then(Fadt::reset_register)
+    {
+        unsafe { reset_register.write(reset_value as u64) };
+        wait();
+    }
+
+    warn!("ACPI reset failed, trying the keyboard controller");
+    unsafe {
+        let mut status = Port::<u8>::new(KEYBOARD_CONTROLLER_STATUS);
+        let mut command = Port::<u8>::new(KEYBOARD_CONTROLLER_COMMAND);
+
+        for _ in 0..1_000_000 {
+            if status.read() & INPUT_BUFFER_FULL == 0 {
+                break;
+            }
+            core::hint::spin_loop();
+        }
+        command.write(RESET_COMMAND);
+    }
+    wait();
+
+    warn!("keyboard controller reset failed, triple faulting");
+    triple_fault();
+}
+
+/// Gives the system some time to reset.
+fn wait() {
+    for _ in 0..1_000_000 {
+        core::hint::spin_loop();
+    }
+}
+
+/// Loads an empty IDT and raises an exception: the CPU cannot deliver it, nor the resulting
+/// double fault, and resets.
+fn triple_fault() -> ! {
+    static EMPTY_IDT: spin::Once<InterruptDescriptorTable> = spin::Once::new();
+
+    let idt = EMPTY_IDT.call_once(InterruptDescriptorTable::new);
+    idt.load();
+    x86_64::instructions::interrupts::int3();
+
+    unreachable!("the CPU did not reset after a triple fault");
+}
@@ -0,0 +1,75 @@
+use log::error;
+use utils::hlt::hlt_loop;
+use x86_64::instructions::port::Port;
+use x86_64::PhysAddr;
+
+use crate::acpi::fadt::Fadt;
+use crate::acpi::sdt::SdtHeader;
+use crate::acpi::{aml, find_table};
+use crate::memory::global::phys_to_virt;
+
+/// Enables the system control interrupts, which tells that ACPI controls the hardware.
+const SCI_EN: u16 = 1 << 0;
+const SLP_EN: u16 = 1 << 13;
+const SLP_TYP_SHIFT: u16 = 10;
+
+/// Powers the system off with the ACPI S5 sleep state, or halts the CPU if that is not possible.
+pub fn shutdown() -> ! {
+    x86_64::instructions::interrupts::disable();
+
+    if let Err(error) = enter_s5() {
+        error!("ACPI shutdown failed: {error}, halting");
+    }
+
+    hlt_loop();
+}
+
+fn enter_s5() -> Result<(), &'static str> {
+    let fadt = find_table::<Fadt>().ok_or("no FADT")?;
+
+    let dsdt = unsafe { &*phys_to_virt(PhysAddr::new(fadt.dsdt_address())).as_ptr::<SdtHeader>() };
+    if &dsdt.signature != b"DSDT" || !dsdt.has_valid_checksum() {
+        return Err("invalid DSDT");
+    }
+    let (slp_typ_a, slp_typ_b) = aml::sleep_type(dsdt.data(), b"_S5_").ok_or("no _S5_ object")?;
+
+    let pm1a_control = fadt.pm1a_control_block().ok_or("no PM1a control block")?;
+    enable_acpi(fadt)?;
+
+    unsafe {
+        if let Some(pm1b_control) = fadt.pm1b_control_block() {
+            pm1b_control.write((slp_typ_b as u64) << SLP_TYP_SHIFT | SLP_EN as u64);
+        }
+        pm1a_control
+            .write((slp_typ_a as u64) << SLP_TYP_SHIFT | SLP_EN as u64)
+            .ok_or("unsupported PM1a control block")?;
+    }
+
+    // the system may take a moment to power off
+    for _ in 0..1_000_000 {
+        core::hint::spin_loop();
+    }
+    Err("the system is still running")
+}
+
+/// Switches the system to ACPI mode through the SMI command port, if it is not already.
+fn enable_acpi(fadt: &Fadt) -> Result<(), &'static str> {
+    let pm1a_control = fadt.pm1a_control_block().ok_or("no PM1a control block")?;
+    let is_enabled =
+        || unsafe { pm1a_control.read() }.is_some_and(|value| value as u16 & SCI_EN != 0);
+
+    let (smi_command_port, acpi_enable) = (fadt.smi_command_port, fadt.acpi_enable);
+    if is_enabled() || smi_command_port == 0 || acpi_enable == 0 {
+        return Ok(());
+    }
+
+    unsafe { Port::<u8>::new(smi_command_port as u16).write(acpi_enable) };
+    for _ in 0..1_000_000 {
+        if is_enabled() {
+            return Ok(());
+        }
+        core::hint::spin_loop();
+    }
+
+    Err("ACPI mode could not be enabled")
+}
@@ -0,0 +1,11 @@
+mod exec;
+mod inner;
+
+pub use exec::{execve, fork};
+pub use inner::{
+    current, exit, parent, spawn, try_wait, wait, with_directory, with_files, Error, ExitStatus,
+    Pid,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,110 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use drivers::fs::ext2::{Inode, Type};
+use x86_64::instructions::interrupts;
+
+use super::inner::{run, Error, Pid, PROCESSES};
+use crate::syscall::{self, SyscallFrame};
+use crate::{elf, fs, thread, usermode};
+
+/// Creates a child of the current process that runs a copy of it, and returns its identifier.
+///
+/// The child shares the memory of the process copy-on-write, duplicates its file descriptors, and
+/// returns 0 from the system call that saved `frame`.
+pub fn fork(frame: &SyscallFrame) -> Result<Pid, Error> {
+    let frame = *frame;
+    let (pid, level_4_frame) = {
+        let mut table = PROCESSES.lock();
+        let (_, process) = table.current()?;
+        let address_space = process
+            .address_space
+            .as_mut()
+            .expect("running process without address space")
+            .fork()
+            .map_err(|_| Error::OutOfMemory)?;
+        let level_4_frame = address_space.level_4_frame();
+
+        (table.add_child(address_space)?, level_4_frame)
+    };
+
+    let name = thread::name(thread::current()).unwrap_or_default();
+    run(&name, pid, move || {
+        interrupts::disable();
+        unsafe {
+            // Safety: the address space is only freed by `exit`, which switches away from it, and
+            // the frame comes from the process that it is a copy of
+            thread::set_page_table(level_4_frame);
+            syscall::return_to_user(&frame, 0)
+        }
+    });
+    Ok(pid)
+}
+
+/// Replaces the program of the current process by the executable `path`, relative to its working
+/// directory unless it is absolute, with the `arguments` and `environment` strings.
+///
+/// The file descriptors and the working directory are kept. Only returns if the executable could
+/// not be loaded, in which case the process is unchanged.
+pub fn execve(path: &str, arguments: Vec<String>, environment: Vec<String>) -> Error {
+    let (level_4_frame, entry, stack_pointer) = {
+        let program = match load(path, &arguments, &environment) {
+            Ok(program) => program,
+            Err(error) => return error,
+        };
+        let level_4_frame = program.address_space.level_4_frame();
+
+        let previous = {
+            let mut table = PROCESSES.lock();
+            let (_, process) = table.current().expect("checked by load");
+            process.address_space.replace(program.address_space)
+        };
+
+        interrupts::disable();
+        // Safety: the address space is only freed by `exit`, which switches away from it
+        unsafe { thread::set_page_table(level_4_frame) };
+        // the program is not running anymore, and only its thread could access its memory
+        drop(previous);
+
+        (level_4_frame, program.entry, program.stack_pointer)
+    };
+    // nothing is dropped after jumping to the program
+    drop(arguments);
+    drop(environment);
+
+    // Safety: the address space was just loaded, and is only freed by `exit`
+    unsafe { usermode::enter(level_4_frame, entry, stack_pointer) }
+}
+
+/// Loads the executable `path` for the current process.
+fn load(
+    path: &str,
+    arguments: &[String],
+    environment: &[String],
+) -> Result<usermode::Program, Error> {
+    let directory = super::with_directory(|directory| directory.clone())?;
+    let inode = fs::lookup(path, &directory)?;
+    match inode.file_type() {
+        Type::File => {}
+        Type::Directory => return Err(fs::Error::IsADirectory.into()),
+        _ => return Err(fs::Error::NotFound.into()),
+    }
+    let image = ExecutableFile(inode);
+
+    let arguments: Vec<&str> = arguments.iter().map(String::as_str).collect();
+    let environment: Vec<&str> = environment.iter().map(String::as_str).collect();
+    Ok(usermode::load_executable(&image, &arguments, &environment)?)
+}
+
+/// A regular file of the file system, whose blocks are read as the executable is loaded.
+struct ExecutableFile(&'static Inode);
+
+impl elf::Source for ExecutableFile {
+    fn size(&self) -> usize {
+        self.0.size() as usize
+    }
+
+    fn read_at(&self, offset: usize, buffer: &mut [u8]) -> bool {
+        fs::read_at(self.0, offset, buffer) == Ok(buffer.len())
+    }
+}
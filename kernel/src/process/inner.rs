@@ -0,0 +1,332 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::elf;
+use crate::fs::{self, FileTable, WorkingDirectory};
+use crate::memory::{global, AddressSpace};
+use crate::sync::{IrqSpinlock, WaitQueue};
+use crate::thread::{self, ThreadId};
+use crate::usermode::{self, Program};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pid(u64);
+
+impl Pid {
+    /// Identifier of the kernel, the parent of the processes it starts.
+    pub const KERNEL: Pid = Pid(0);
+
+    pub const fn new(pid: u64) -> Self {
+        Self(pid)
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Pid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// How a process ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// With the `exit` system call.
+    Exited(u8),
+    /// Stopped by the kernel because of an exception.
+    Killed,
+}
+
+impl ExitStatus {
+    /// Signal number reported for the processes stopped by an exception, `SIGSEGV`.
+    const KILLED_SIGNAL: u32 = 11;
+
+    /// Returns the status encoded like the wait statuses of POSIX, which the `WEXITSTATUS` and
+    /// `WTERMSIG` macros decode.
+    pub fn to_wait_status(self) -> u32 {
+        match self {
+            Self::Exited(status) => (status as u32) << 8,
+            Self::Killed => Self::KILLED_SIGNAL,
+        }
+    }
+}
+
+/// Errors of the process operations.
+#[derive(Debug)]
+pub enum Error {
+    /// The current thread is a kernel thread, not a process.
+    NotAProcess,
+    /// There is no such child to wait for.
+    NoChild,
+    /// The memory of the process could not be allocated.
+    OutOfMemory,
+    /// The executable could not be opened.
+    File(fs::Error),
+    /// The executable could not be loaded.
+    Executable(elf::Error),
+}
+
+impl From<fs::Error> for Error {
+    fn from(error: fs::Error) -> Self {
+        Self::File(error)
+    }
+}
+
+impl From<elf::Error> for Error {
+    fn from(error: elf::Error) -> Self {
+        Self::Executable(error)
+    }
+}
+
+/// Who waits for a process to exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Parent {
+    /// A process, or [`Pid::KERNEL`] for the processes started by the kernel.
+    Process(Pid),
+    /// The parent exited, so nothing waits for the process, which is freed when it exits.
+    Orphan,
+}
+
+/// A program running in user mode on a single thread.
+#[derive(Debug)]
+pub(super) struct Process {
+    parent: Parent,
+    /// `None` once the process exited
+    pub(super) address_space: Option<AddressSpace>,
+    pub(super) files: FileTable,
+    pub(super) directory: WorkingDirectory,
+    /// Set once the process exited, until its parent waits for it
+    exit_status: Option<ExitStatus>,
+}
+
+pub(super) struct Table {
+    processes: BTreeMap<Pid, Process>,
+    /// Process run by each thread
+    threads: BTreeMap<ThreadId, Pid>,
+    next_pid: u64,
+}
+
+impl Table {
+    /// Returns the process run by the current thread.
+    pub(super) fn current(&mut self) -> Result<(Pid, &mut Process), Error> {
+        let pid = *self
+            .threads
+            .get(&thread::current())
+            .ok_or(Error::NotAProcess)?;
+        Ok((pid, self.processes.get_mut(&pid).unwrap()))
+    }
+
+    /// Adds a child to the current process, with its `address_space` and a copy of its file
+    /// descriptors and working directory, and returns its identifier.
+    pub(super) fn add_child(&mut self, address_space: AddressSpace) -> Result<Pid, Error> {
+        let (parent, process) = self.current()?;
+        let files = process.files.clone();
+        let directory = process.directory.clone();
+
+        Ok(self.add(parent, address_space, files, directory))
+    }
+
+    /// Adds a process, which runs once a thread is registered for it with [`run`].
+    fn add(
+        &mut self,
+        parent: Pid,
+        address_space: AddressSpace,
+        files: FileTable,
+        directory: WorkingDirectory,
+    ) -> Pid {
+        let pid = Pid(self.next_pid);
+        self.next_pid += 1;
+
+        self.processes.insert(
+            pid,
+            Process {
+                parent: Parent::Process(parent),
+                address_space: Some(address_space),
+                files,
+                directory,
+                exit_status: None,
+            },
+        );
+        pid
+    }
+
+    /// Frees a child of `parent` that exited, `target` or any one if it is `None`, and returns
+    /// how it ended.
+    ///
+    /// Returns `Ok(None)` if the children are still running.
+    fn reap(
+        &mut self,
+        parent: Pid,
+        target: Option<Pid>,
+    ) -> Result<Option<(Pid, ExitStatus)>, Error> {
+        let mut children = self.processes.iter().filter(|(pid, process)| {
+            process.parent == Parent::Process(parent) && target.is_none_or(|target| **pid == target)
+        });
+
+        let Some((&first, _)) = children.next() else {
+            return Err(Error::NoChild);
+        };
+        let exited = core::iter::once(first)
+            .chain(children.map(|(&pid, _)| pid))
+            .find(|pid| self.processes[pid].exit_status.is_some());
+
+        Ok(exited.map(|pid| {
+            let process = self.processes.remove(&pid).unwrap();
+            (pid, process.exit_status.unwrap())
+        }))
+    }
+}
+
+pub(super) static PROCESSES: IrqSpinlock<Table> = IrqSpinlock::new(
+    "processes",
+    Table {
+        processes: BTreeMap::new(),
+        threads: BTreeMap::new(),
+        next_pid: 1,
+    },
+);
+
+/// Woken up every time a process exits.
+static EXITED: WaitQueue = WaitQueue::new();
+
+/// Starts `program` as a new process, whose parent is the kernel, on a new thread named `name`.
+///
+/// Its standard streams are the keyboard and the screen, and its working directory is the root.
+/// It stays a zombie once it exits, until a kernel thread waits for it.
+pub fn spawn(name: &str, program: Program) -> Pid {
+    let Program {
+        address_space,
+        entry,
+        stack_pointer,
+    } = program;
+    let level_4_frame = address_space.level_4_frame();
+
+    let pid = PROCESSES.lock().add(
+        Pid::KERNEL,
+        address_space,
+        FileTable::standard(),
+        WorkingDirectory::root(),
+    );
+
+    run(name, pid, move || unsafe {
+        // Safety: the address space is only freed by `exit`, which switches away from it
+        usermode::enter(level_4_frame, entry, stack_pointer)
+    });
+    pid
+}
+
+/// Starts a thread named `name` that runs the process `pid` with `enter`, which must switch to
+/// its address space and never return.
+pub(super) fn run(name: &str, pid: Pid, enter: impl FnOnce() + Send + 'static) {
+    thread::spawn(name, move || {
+        PROCESSES.lock().threads.insert(thread::current(), pid);
+        enter();
+    });
+}
+
+/// Returns the identifier of the process run by the current thread, or `None` for kernel
+/// threads.
+pub fn current() -> Option<Pid> {
+    PROCESSES.lock().threads.get(&thread::current()).copied()
+}
+
+/// Returns the identifier of the parent of the current process, [`Pid::KERNEL`] if it was
+/// started by the kernel or if its parent exited.
+pub fn parent() -> Result<Pid, Error> {
+    let mut table = PROCESSES.lock();
+    let (_, process) = table.current()?;
+
+    Ok(match process.parent {
+        Parent::Process(pid) => pid,
+        Parent::Orphan => Pid::KERNEL,
+    })
+}
+
+/// Runs `f` with the file descriptors of the current process.
+pub fn with_files<R>(f: impl FnOnce(&mut FileTable) -> R) -> Result<R, Error> {
+    let mut table = PROCESSES.lock();
+    let (_, process) = table.current()?;
+    Ok(f(&mut process.files))
+}
+
+/// Runs `f` with the working directory of the current process.
+pub fn with_directory<R>(f: impl FnOnce(&mut WorkingDirectory) -> R) -> Result<R, Error> {
+    let mut table = PROCESSES.lock();
+    let (_, process) = table.current()?;
+    Ok(f(&mut process.directory))
+}
+
+/// Ends the current process with `status`, frees its memory and closes its files, and exits the
+/// thread.
+///
+/// Its children become orphans, and it stays a zombie until its parent waits for it.
+///
+/// Panics if the current thread is not a process.
+pub fn exit(status: ExitStatus) -> ! {
+    let (address_space, files) = {
+        let mut table = PROCESSES.lock();
+        let pid = table
+            .threads
+            .remove(&thread::current())
+            .expect("not a process");
+
+        let children: Vec<Pid> = table
+            .processes
+            .iter()
+            .filter(|(_, process)| process.parent == Parent::Process(pid))
+            .map(|(&child, _)| child)
+            .collect();
+        for child in children {
+            let process = table.processes.get_mut(&child).unwrap();
+            if process.exit_status.is_some() {
+                table.processes.remove(&child);
+            } else {
+                process.parent = Parent::Orphan;
+            }
+        }
+
+        let process = table.processes.get_mut(&pid).unwrap();
+        let resources = (
+            process.address_space.take(),
+            core::mem::take(&mut process.files),
+        );
+        if process.parent == Parent::Orphan {
+            table.processes.remove(&pid);
+        } else {
+            process.exit_status = Some(status);
+        }
+        resources
+    };
+    EXITED.wake_all();
+
+    // Safety: the kernel page table maps the kernel, and is never freed
+    unsafe { thread::set_page_table(global::kernel_level_4_frame()) };
+    // the process is not running anymore, and only its thread could access its memory
+    drop(address_space);
+    drop(files);
+    thread::exit();
+}
+
+/// Waits for a child of the current process to exit, `target` or any one if it is `None`, frees
+/// it and returns how it ended.
+///
+/// Kernel threads wait for the processes started by the kernel.
+pub fn wait(target: Option<Pid>) -> Result<(Pid, ExitStatus), Error> {
+    let mut result = Ok(None);
+    EXITED.wait_until(|| {
+        result = try_wait(target);
+        !matches!(result, Ok(None))
+    });
+
+    result.map(|exited| exited.unwrap())
+}
+
+/// Like [`wait`], but returns `Ok(None)` instead of waiting if no child exited yet.
+pub fn try_wait(target: Option<Pid>) -> Result<Option<(Pid, ExitStatus)>, Error> {
+    let mut table = PROCESSES.lock();
+    let parent = table.current().map_or(Pid::KERNEL, |(pid, _)| pid);
+    table.reap(parent, target)
+}
@@ -0,0 +1,109 @@
+use core::arch::global_asm;
+use core::ptr::addr_of;
+
+use super::{try_wait, wait, Error, ExitStatus, Pid};
+use crate::syscall::number;
+use crate::usermode;
+
+global_asm!(
+    ".pushsection .rodata.test_programs, \"a\"",
+    // forks a child that exits with 7, waits for it and exits with 0 if its status is right
+    "test_program_fork_start:",
+    "    mov eax, {fork}",
+    "    syscall",
+    "    test rax, rax",
+    "    js 3f",
+    "    jnz 2f",
+    "    mov eax, {exit}",
+    "    mov edi, 7",
+    "    syscall",
+    "    ud2",
+    "2:",
+    "    mov r12, rax",
+    "    sub rsp, 16",
+    "    mov eax, {waitpid}",
+    "    mov rdi, r12",
+    "    mov rsi, rsp",
+    "    xor edx, edx",
+    "    syscall",
+    "    cmp rax, r12",
+    "    jne 3f",
+    "    cmp dword ptr [rsp], 0x700",
+    "    jne 3f",
+    "    mov eax, {exit}",
+    "    xor edi, edi",
+    "    syscall",
+    "    ud2",
+    "3:",
+    "    mov eax, {exit}",
+    "    mov edi, 1",
+    "    syscall",
+    "    ud2",
+    "test_program_fork_end:",
+    // executes a missing file, and exits with the error number
+    "test_program_execve_start:",
+    "    mov eax, {execve}",
+    "    lea rdi, [rip + 2f]",
+    "    xor esi, esi",
+    "    xor edx, edx",
+    "    syscall",
+    "    neg rax",
+    "    mov rdi, rax",
+    "    mov eax, {exit}",
+    "    syscall",
+    "    ud2",
+    "2:",
+    "    .asciz \"/missing\"",
+    "test_program_execve_end:",
+    ".popsection",
+    fork = const number::FORK,
+    execve = const number::EXECVE,
+    waitpid = const number::WAITPID,
+    exit = const number::EXIT,
+);
+
+extern "C" {
+    static test_program_fork_start: u8;
+    static test_program_fork_end: u8;
+    static test_program_execve_start: u8;
+    static test_program_execve_end: u8;
+}
+
+fn program(start: *const u8, end: *const u8) -> &'static [u8] {
+    unsafe { core::slice::from_raw_parts(start, end.offset_from(start) as usize) }
+}
+
+#[test_case]
+fn test_fork_and_wait() {
+    let code = program(
+        addr_of!(test_program_fork_start),
+        addr_of!(test_program_fork_end),
+    );
+    let pid = usermode::spawn("fork test", code).unwrap();
+    assert_eq!(wait(Some(pid)).unwrap(), (pid, ExitStatus::Exited(0)));
+}
+
+#[test_case]
+fn test_execve_missing_file() {
+    let code = program(
+        addr_of!(test_program_execve_start),
+        addr_of!(test_program_execve_end),
+    );
+    let pid = usermode::spawn("execve test", code).unwrap();
+    // ENOENT
+    assert_eq!(wait(Some(pid)).unwrap(), (pid, ExitStatus::Exited(2)));
+}
+
+#[test_case]
+fn test_wait_unknown_child() {
+    assert!(matches!(
+        try_wait(Some(Pid::new(u64::MAX))),
+        Err(Error::NoChild)
+    ));
+}
+
+#[test_case]
+fn test_wait_status() {
+    assert_eq!(ExitStatus::Exited(3).to_wait_status(), 0x300);
+    assert_eq!(ExitStatus::Killed.to_wait_status(), 11);
+}
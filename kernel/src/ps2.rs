@@ -0,0 +1,10 @@
+mod controller;
+mod device;
+mod inner;
+
+pub use controller::{Configuration, Controller, Error, Port};
+pub use device::DeviceType;
+pub use inner::{device, init, write};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,278 @@
+use core::fmt;
+use core::time::Duration;
+
+use bitflags::bitflags;
+use x86_64::instructions::port::{Port as IoPort, PortReadOnly, PortWriteOnly};
+
+use crate::time::{constants::NANOS_PER_SECOND, tsc};
+
+const DATA_PORT: u16 = 0x60;
+/// Status register when read, command register when written.
+const STATUS_PORT: u16 = 0x64;
+const COMMAND_PORT: u16 = 0x64;
+
+const READ_CONFIGURATION: u8 = 0x20;
+const WRITE_CONFIGURATION: u8 = 0x60;
+const DISABLE_SECOND_PORT: u8 = 0xa7;
+const ENABLE_SECOND_PORT: u8 = 0xa8;
+const TEST_SECOND_PORT: u8 = 0xa9;
+const TEST_CONTROLLER: u8 = 0xaa;
+const TEST_FIRST_PORT: u8 = 0xab;
+const DISABLE_FIRST_PORT: u8 = 0xad;
+const ENABLE_FIRST_PORT: u8 = 0xae;
+/// Sends the next data byte to the device on the second port, instead of the first one.
+const WRITE_SECOND_PORT: u8 = 0xd4;
+
+const CONTROLLER_TEST_PASSED: u8 = 0x55;
+const PORT_TEST_PASSED: u8 = 0x00;
+
+/// Response of the devices to the commands they accept.
+const ACKNOWLEDGE: u8 = 0xfa;
+/// Response of the devices to the commands they did not receive correctly.
+const RESEND: u8 = 0xfe;
+/// Number of times a command is sent again when the device asks for it.
+const MAX_RESENDS: usize = 3;
+
+/// Time the controller and the devices have to respond, except to resets.
+pub const TIMEOUT: Duration = Duration::from_millis(50);
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Status: u8 {
+        /// A byte can be read from the data port.
+        const OUTPUT_FULL = 1 << 0;
+        /// The last byte written was not taken by the controller yet.
+        const INPUT_FULL = 1 << 1;
+        const TIMEOUT_ERROR = 1 << 6;
+        const PARITY_ERROR = 1 << 7;
+    }
+
+    /// Controller configuration byte.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Configuration: u8 {
+        const FIRST_PORT_INTERRUPT = 1 << 0;
+        const SECOND_PORT_INTERRUPT = 1 << 1;
+        /// Set once the firmware passed its self-test.
+        const SYSTEM = 1 << 2;
+        const FIRST_PORT_CLOCK_DISABLED = 1 << 4;
+        const SECOND_PORT_CLOCK_DISABLED = 1 << 5;
+        /// Translates the scancodes of the first port to scancode set 1.
+        const FIRST_PORT_TRANSLATION = 1 << 6;
+    }
+}
+
+/// One of the two ports of the controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Port {
+    /// Usually wired to the keyboard, raises ISA IRQ 1.
+    First,
+    /// Usually wired to the mouse, raises ISA IRQ 12.
+    Second,
+}
+
+impl Port {
+    pub const ALL: [Self; 2] = [Self::First, Self::Second];
+
+    pub fn index(self) -> usize {
+        match self {
+            Self::First => 0,
+            Self::Second => 1,
+        }
+    }
+}
+
+impl fmt::Display for Port {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::First => "first",
+            Self::Second => "second",
+        })
+    }
+}
+
+/// Errors of the controller and of the devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Nothing answers on the I/O ports of the controller.
+    NoController,
+    /// The controller or a device did not respond in time.
+    Timeout,
+    /// The controller failed its self-test, with this response.
+    ControllerTest(u8),
+    /// The interface of a port failed its test, with this response.
+    PortTest(Port, u8),
+    /// A device failed its self-test after a reset, with this response.
+    DeviceTest(Port, u8),
+    /// A device responded to a command with this byte, instead of acknowledging it.
+    Rejected(Port, u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoController => write!(f, "no PS/2 controller"),
+            Self::Timeout => write!(f, "timed out"),
+            Self::ControllerTest(response) => {
+                write!(f, "controller self-test failed ({response:#04x})")
+            }
+            Self::PortTest(port, response) => {
+                write!(f, "{port} port test failed ({response:#04x})")
+            }
+            Self::DeviceTest(port, response) => {
+                write!(
+                    f,
+                    "device on the {port} port failed its self-test ({response:#04x})"
+                )
+            }
+            Self::Rejected(port, response) => {
+                write!(
+                    f,
+                    "device on the {port} port rejected a command ({response:#04x})"
+                )
+            }
+        }
+    }
+}
+
+/// The 8042 PS/2 controller.
+///
+/// Every operation polls the controller, so they must not run while the interrupt handlers of
+/// the ports may read the data port.
+pub struct Controller {
+    data: IoPort<u8>,
+    status: PortReadOnly<u8>,
+    command: PortWriteOnly<u8>,
+}
+
+impl Controller {
+    pub const fn new() -> Self {
+        Self {
+            data: IoPort::new(DATA_PORT),
+            status: PortReadOnly::new(STATUS_PORT),
+            command: PortWriteOnly::new(COMMAND_PORT),
+        }
+    }
+
+    fn status(&mut self) -> Status {
+        Status::from_bits_retain(unsafe { self.status.read() })
+    }
+
+    /// Returns `true` if the status register reads like a floating bus.
+    pub fn is_missing(&mut self) -> bool {
+        self.status().bits() == 0xff
+    }
+
+    /// Polls the status register until `ready` returns `true` or `timeout` elapses.
+    fn wait(&mut self, timeout: Duration, ready: impl Fn(Status) -> bool) -> Result<(), Error> {
+        let frequency = tsc::frequency().expect("TSC not calibrated");
+        let cycles = (timeout.as_nanos() * frequency as u128 / NANOS_PER_SECOND as u128) as u64;
+        let start = tsc::read();
+
+        while !ready(self.status()) {
+            if tsc::read() - start > cycles {
+                return Err(Error::Timeout);
+            }
+            core::hint::spin_loop();
+        }
+        Ok(())
+    }
+
+    /// Waits up to `timeout` for a byte from the controller or from a device, and reads it.
+    pub fn read(&mut self, timeout: Duration) -> Result<u8, Error> {
+        self.wait(timeout, |status| status.contains(Status::OUTPUT_FULL))?;
+        Ok(unsafe { self.data.read() })
+    }
+
+    /// Discards the bytes waiting to be read.
+    pub fn flush(&mut self) {
+        while self.status().contains(Status::OUTPUT_FULL) {
+            unsafe { self.data.read() };
+        }
+    }
+
+    fn write_command(&mut self, command: u8) -> Result<(), Error> {
+        self.wait(TIMEOUT, |status| !status.contains(Status::INPUT_FULL))?;
+        unsafe { self.command.write(command) };
+        Ok(())
+    }
+
+    fn write_data(&mut self, byte: u8) -> Result<(), Error> {
+        self.wait(TIMEOUT, |status| !status.contains(Status::INPUT_FULL))?;
+        unsafe { self.data.write(byte) };
+        Ok(())
+    }
+
+    pub fn configuration(&mut self) -> Result<Configuration, Error> {
+        self.write_command(READ_CONFIGURATION)?;
+        Ok(Configuration::from_bits_retain(self.read(TIMEOUT)?))
+    }
+
+    pub fn set_configuration(&mut self, configuration: Configuration) -> Result<(), Error> {
+        self.write_command(WRITE_CONFIGURATION)?;
+        self.write_data(configuration.bits())
+    }
+
+    /// Runs the self-test of the controller, which may reset its configuration.
+    pub fn test(&mut self) -> Result<(), Error> {
+        self.write_command(TEST_CONTROLLER)?;
+        match self.read(TIMEOUT)? {
+            CONTROLLER_TEST_PASSED => Ok(()),
+            response => Err(Error::ControllerTest(response)),
+        }
+    }
+
+    /// Tests the interface of `port`, which does not involve its device.
+    pub fn test_port(&mut self, port: Port) -> Result<(), Error> {
+        self.write_command(match port {
+            Port::First => TEST_FIRST_PORT,
+            Port::Second => TEST_SECOND_PORT,
+        })?;
+        match self.read(TIMEOUT)? {
+            PORT_TEST_PASSED => Ok(()),
+            response => Err(Error::PortTest(port, response)),
+        }
+    }
+
+    pub fn enable_port(&mut self, port: Port) -> Result<(), Error> {
+        self.write_command(match port {
+            Port::First => ENABLE_FIRST_PORT,
+            Port::Second => ENABLE_SECOND_PORT,
+        })
+    }
+
+    pub fn disable_port(&mut self, port: Port) -> Result<(), Error> {
+        self.write_command(match port {
+            Port::First => DISABLE_FIRST_PORT,
+            Port::Second => DISABLE_SECOND_PORT,
+        })
+    }
+
+    /// Sends `byte` to the device on `port`, without waiting for its response.
+    pub fn write(&mut self, port: Port, byte: u8) -> Result<(), Error> {
+        if port == Port::Second {
+            self.write_command(WRITE_SECOND_PORT)?;
+        }
+        self.write_data(byte)
+    }
+
+    /// Sends the command `byte` to the device on `port`, and waits for it to be acknowledged.
+    ///
+    /// The command is sent again if the device asks for it.
+    pub fn send(&mut self, port: Port, byte: u8) -> Result<(), Error> {
+        for _ in 0..=MAX_RESENDS {
+            self.write(port, byte)?;
+            match self.read(TIMEOUT)? {
+                ACKNOWLEDGE => return Ok(()),
+                RESEND => continue,
+                response => return Err(Error::Rejected(port, response)),
+            }
+        }
+        Err(Error::Rejected(port, RESEND))
+    }
+}
+
+impl Default for Controller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,142 @@
+use core::fmt;
+use core::time::Duration;
+
+use super::controller::{Controller, Error, Port, TIMEOUT};
+use crate::mouse::PacketFormat;
+
+const RESET: u8 = 0xff;
+const IDENTIFY: u8 = 0xf2;
+const SET_SAMPLE_RATE: u8 = 0xf3;
+const ENABLE_SCANNING: u8 = 0xf4;
+const DISABLE_SCANNING: u8 = 0xf5;
+
+/// Sent by the devices once they passed their self-test.
+const SELF_TEST_PASSED: u8 = 0xaa;
+/// Time the devices have to run their self-test after a reset.
+const RESET_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Sample rates that enable the wheel of IntelliMouse mice, and then their two extra buttons.
+const WHEEL_SEQUENCE: [u8; 3] = [200, 100, 80];
+const FIVE_BUTTONS_SEQUENCE: [u8; 3] = [200, 200, 80];
+/// Default sample rate of the mice, in reports per second.
+const SAMPLE_RATE: u8 = 100;
+
+/// Kinds of devices plugged into a port, told apart by their response to the identify command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    /// Keyboard that does not answer the identify command
+    AtKeyboard,
+    /// Keyboard that answers `0xab`, followed by `0x83`, or `0x41` or `0xc1` once translated
+    Mf2Keyboard,
+    /// Mouse with three buttons and no wheel, `0x00`
+    StandardMouse,
+    /// Mouse with a wheel, `0x03`
+    WheelMouse,
+    /// Mouse with a wheel and five buttons, `0x04`
+    FiveButtonMouse,
+    /// Any other response
+    Unknown,
+}
+
+impl DeviceType {
+    /// Returns the kind of device that answered `identity` to the identify command.
+    pub fn from_identity(identity: &[u8]) -> Self {
+        match identity {
+            [] => Self::AtKeyboard,
+            [0xab, 0x83 | 0x41 | 0xc1] => Self::Mf2Keyboard,
+            [0x00] => Self::StandardMouse,
+            [0x03] => Self::WheelMouse,
+            [0x04] => Self::FiveButtonMouse,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn is_keyboard(self) -> bool {
+        matches!(self, Self::AtKeyboard | Self::Mf2Keyboard)
+    }
+
+    /// Returns the packets that the device sends, if it is a mouse.
+    pub fn packet_format(self) -> Option<PacketFormat> {
+        match self {
+            Self::StandardMouse => Some(PacketFormat::Standard),
+            Self::WheelMouse => Some(PacketFormat::Wheel),
+            Self::FiveButtonMouse => Some(PacketFormat::FiveButtons),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::AtKeyboard => "AT keyboard",
+            Self::Mf2Keyboard => "MF2 keyboard",
+            Self::StandardMouse => "mouse",
+            Self::WheelMouse => "wheel mouse",
+            Self::FiveButtonMouse => "five-button mouse",
+            Self::Unknown => "unknown device",
+        })
+    }
+}
+
+/// Resets the device on `port`, identifies it, enables the extensions of mice, and lets it send
+/// its scancodes or packets.
+///
+/// The port must be enabled, and its interrupt disabled.
+pub(super) fn init(controller: &mut Controller, port: Port) -> Result<DeviceType, Error> {
+    controller.send(port, RESET)?;
+    match controller.read(RESET_TIMEOUT)? {
+        SELF_TEST_PASSED => {}
+        response => return Err(Error::DeviceTest(port, response)),
+    }
+    // mice follow with their identity, which is asked again below
+    while controller.read(TIMEOUT).is_ok() {}
+
+    controller.send(port, DISABLE_SCANNING)?;
+    let mut device = identify(controller, port)?;
+    if device == DeviceType::StandardMouse {
+        device = enable_mouse_extensions(controller, port)?;
+    }
+    controller.send(port, ENABLE_SCANNING)?;
+
+    Ok(device)
+}
+
+fn identify(controller: &mut Controller, port: Port) -> Result<DeviceType, Error> {
+    controller.send(port, IDENTIFY)?;
+
+    let mut identity = [0; 2];
+    let mut length = 0;
+    while length < identity.len() {
+        match controller.read(TIMEOUT) {
+            Ok(byte) => identity[length] = byte,
+            Err(_) => break,
+        }
+        length += 1;
+    }
+    Ok(DeviceType::from_identity(&identity[..length]))
+}
+
+/// Enables the wheel and then the extra buttons of the mouse on `port` with the magic sequences
+/// of sample rates, and returns what it became.
+fn enable_mouse_extensions(controller: &mut Controller, port: Port) -> Result<DeviceType, Error> {
+    let mut device = DeviceType::StandardMouse;
+    for sequence in [WHEEL_SEQUENCE, FIVE_BUTTONS_SEQUENCE] {
+        for rate in sequence {
+            set_sample_rate(controller, port, rate)?;
+        }
+        match identify(controller, port)? {
+            DeviceType::Unknown => break,
+            identified if identified == device => break,
+            identified => device = identified,
+        }
+    }
+
+    set_sample_rate(controller, port, SAMPLE_RATE)?;
+    Ok(device)
+}
+
+fn set_sample_rate(controller: &mut Controller, port: Port, rate: u8) -> Result<(), Error> {
+    controller.send(port, SET_SAMPLE_RATE)?;
+    controller.send(port, rate)
+}
@@ -0,0 +1,128 @@
+use log::warn;
+use spin::Once;
+use utils::sync::IrqSpinlock;
+
+use super::controller::{Configuration, Controller, Error, Port};
+use super::device::{self, DeviceType};
+use crate::interrupts::handlers::{keyboard_interrupt, mouse_interrupt};
+use crate::interrupts::irq;
+use crate::mouse;
+
+static CONTROLLER: IrqSpinlock<Controller> = IrqSpinlock::new("PS/2 controller", Controller::new());
+
+/// Device found on each port by [`init`].
+static DEVICES: Once<[Option<DeviceType>; 2]> = Once::new();
+
+/// Initializes the PS/2 controller and its devices, and routes the interrupts of the keyboard
+/// and of the mouse.
+///
+/// Must be called once the interrupts are set up and the TSC is calibrated, before enabling
+/// interrupts. Without a working controller, there is no keyboard nor mouse.
+pub fn init() {
+    let devices = {
+        let mut controller = CONTROLLER.lock();
+        initialize(&mut controller).unwrap_or_else(|error| {
+            warn!("{error}");
+            [None, None]
+        })
+    };
+    DEVICES.call_once(|| devices);
+
+    for port in Port::ALL {
+        let Some(device) = devices[port.index()] else {
+            continue;
+        };
+
+        match (port, device) {
+            (Port::First, device) if device.is_keyboard() => {
+                irq::register_isa("keyboard", irq::KEYBOARD, keyboard_interrupt::handler);
+            }
+            (Port::Second, device) if device.packet_format().is_some() => {
+                mouse::init(device.packet_format().unwrap());
+                irq::register_isa("mouse", irq::MOUSE, mouse_interrupt::handler);
+            }
+            _ => warn!("unsupported {device} on the {port} port"),
+        }
+    }
+}
+
+/// Resets and tests the controller, then enables and identifies the devices on the ports that
+/// work, and enables the interrupts of those ports.
+fn initialize(controller: &mut Controller) -> Result<[Option<DeviceType>; 2], Error> {
+    if controller.is_missing() {
+        return Err(Error::NoController);
+    }
+
+    // the devices must not send anything while the controller is set up
+    controller.disable_port(Port::First)?;
+    controller.disable_port(Port::Second)?;
+    controller.flush();
+
+    let mut configuration = controller.configuration()?;
+    configuration.remove(
+        Configuration::FIRST_PORT_INTERRUPT
+            | Configuration::SECOND_PORT_INTERRUPT
+            | Configuration::FIRST_PORT_CLOCK_DISABLED,
+    );
+    // the keyboard decoder expects scancode set 1
+    configuration.insert(Configuration::FIRST_PORT_TRANSLATION);
+    controller.set_configuration(configuration)?;
+
+    controller.test()?;
+    // some controllers are reset by their self-test
+    controller.set_configuration(configuration)?;
+
+    // the clock of the second port only follows the enable command on dual-port controllers
+    controller.enable_port(Port::Second)?;
+    let dual_port = !controller
+        .configuration()?
+        .contains(Configuration::SECOND_PORT_CLOCK_DISABLED);
+    controller.disable_port(Port::Second)?;
+
+    let mut devices = [None, None];
+    for port in Port::ALL {
+        if port == Port::Second && !dual_port {
+            continue;
+        }
+        if let Err(error) = controller.test_port(port) {
+            warn!("{error}");
+            continue;
+        }
+
+        controller.enable_port(port)?;
+        match device::init(controller, port) {
+            Ok(device) => devices[port.index()] = Some(device),
+            // nothing is plugged in
+            Err(Error::Timeout) => controller.disable_port(port)?,
+            Err(error) => {
+                warn!("{error}");
+                controller.disable_port(port)?;
+            }
+        }
+    }
+
+    let mut configuration = controller.configuration()?;
+    configuration.set(
+        Configuration::FIRST_PORT_INTERRUPT,
+        devices[Port::First.index()].is_some(),
+    );
+    configuration.set(
+        Configuration::SECOND_PORT_INTERRUPT,
+        devices[Port::Second.index()].is_some(),
+    );
+    controller.set_configuration(configuration)?;
+    controller.flush();
+
+    Ok(devices)
+}
+
+/// Returns the device found on `port`, or `None` if there is none or before [`init`].
+pub fn device(port: Port) -> Option<DeviceType> {
+    DEVICES.r#try()?[port.index()]
+}
+
+/// Sends `byte` to the device on `port`, without waiting for its response, which its interrupt
+/// handler receives.
+pub fn write(port: Port, byte: u8) -> Result<(), Error> {
+    CONTROLLER.lock().write(port, byte)
+}
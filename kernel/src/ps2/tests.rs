@@ -0,0 +1,37 @@
+use super::DeviceType;
+use crate::mouse::PacketFormat;
+
+#[test_case]
+fn test_identify_devices() {
+    assert_eq!(DeviceType::from_identity(&[]), DeviceType::AtKeyboard);
+    assert_eq!(
+        DeviceType::from_identity(&[0xab, 0x83]),
+        DeviceType::Mf2Keyboard
+    );
+    // the controller translates the identity of the keyboards on the first port
+    assert_eq!(
+        DeviceType::from_identity(&[0xab, 0x41]),
+        DeviceType::Mf2Keyboard
+    );
+    assert_eq!(
+        DeviceType::from_identity(&[0x00]),
+        DeviceType::StandardMouse
+    );
+    assert_eq!(DeviceType::from_identity(&[0x03]), DeviceType::WheelMouse);
+    assert_eq!(
+        DeviceType::from_identity(&[0x04]),
+        DeviceType::FiveButtonMouse
+    );
+    assert_eq!(DeviceType::from_identity(&[0xab]), DeviceType::Unknown);
+}
+
+#[test_case]
+fn test_device_kinds() {
+    assert!(DeviceType::Mf2Keyboard.is_keyboard());
+    assert!(!DeviceType::WheelMouse.is_keyboard());
+    assert_eq!(DeviceType::AtKeyboard.packet_format(), None);
+    assert_eq!(
+        DeviceType::WheelMouse.packet_format(),
+        Some(PacketFormat::Wheel)
+    );
+}
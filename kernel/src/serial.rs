@@ -0,0 +1,4 @@
+mod inner;
+
+pub(crate) use inner::receive;
+pub use inner::{init, BAUD_RATE_KEY};
@@ -0,0 +1,57 @@
+use drivers::serial::{self, ComPort, DEFAULT_BAUD_RATE};
+use log::{info, warn};
+use x86_64::structures::idt::HandlerFunc;
+
+use crate::interrupts::handlers::serial_interrupt;
+use crate::interrupts::irq;
+use crate::{config, keyboard};
+
+/// Option of the boot configuration that sets the baud rate of the serial ports.
+pub const BAUD_RATE_KEY: &str = "serial.baud";
+
+/// Sets up the serial ports at the baud rate of the boot configuration, and receives their
+/// bytes on their IRQ.
+///
+/// The first serial port types into the keyboard input, so the shell can be driven over it.
+pub fn init() {
+    let baud_rate = match config::get(BAUD_RATE_KEY).map(str::parse) {
+        None => DEFAULT_BAUD_RATE,
+        Some(Ok(baud_rate)) => baud_rate,
+        Some(Err(_)) => {
+            warn!("invalid baud rate, using {DEFAULT_BAUD_RATE}");
+            DEFAULT_BAUD_RATE
+        }
+    };
+
+    let ports: [(ComPort, u8, HandlerFunc); 2] = [
+        (ComPort::Com1, irq::COM1, serial_interrupt::com1_handler),
+        (ComPort::Com2, irq::COM2, serial_interrupt::com2_handler),
+    ];
+    for (port, isa_irq, handler) in ports {
+        if let Err(error) = serial::port(port).lock().init(baud_rate) {
+            info!("{port}: {error}");
+            continue;
+        }
+
+        irq::register_isa(port.name(), isa_irq, handler);
+        info!("{port} at {baud_rate} bauds");
+    }
+}
+
+/// Receives the bytes of `port`, when it raises its IRQ.
+pub(crate) fn receive(port: ComPort) {
+    serial::port(port).lock().handle_interrupt();
+    // The bytes of the second port are left for its readers
+    if port != ComPort::Com1 {
+        return;
+    }
+
+    let mut buffer = [0; 64];
+    loop {
+        let count = serial::port(port).lock().read(&mut buffer);
+        if count == 0 {
+            return;
+        }
+        keyboard::add_input(&buffer[..count]);
+    }
+}
@@ -0,0 +1,10 @@
+mod commands;
+mod history;
+mod inner;
+mod key;
+mod line_editor;
+
+pub use inner::{run, spawn};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,429 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use drivers::display::frame_buffer::{Font, WRITER};
+use drivers::fs::ext2::{Inode, Permissions, Type};
+use drivers::{print, println};
+use utils::posix::time::Time;
+
+use super::inner::Shell;
+use crate::heap::allocator::ALLOCATOR;
+use crate::heap::constants::HEAP_START;
+use crate::memory::constants::PAGE_SIZE;
+use crate::memory::global;
+use crate::{fs, interrupts, logger, power, time};
+
+/// A built-in command of the shell.
+struct Command {
+    name: &'static str,
+    /// Arguments, shown by `help` and when the command is misused
+    usage: &'static str,
+    description: &'static str,
+    run: fn(&mut Shell, &[&str]) -> Result<(), Error>,
+}
+
+/// Why a command failed.
+enum Error {
+    /// The command was given the wrong arguments.
+    Usage,
+    /// The file operation on the path failed.
+    File(String, fs::Error),
+}
+
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "cat",
+        usage: "FILE...",
+        description: "prints files",
+        run: cat,
+    },
+    Command {
+        name: "cd",
+        usage: "[DIRECTORY]",
+        description: "changes the working directory, to the root by default",
+        run: cd,
+    },
+    Command {
+        name: "dmesg",
+        usage: "",
+        description: "prints the kernel log",
+        run: dmesg,
+    },
+    Command {
+        name: "font",
+        usage: "[SIZE] [WEIGHT]",
+        description: "shows or changes the font of the screen",
+        run: font,
+    },
+    Command {
+        name: "heap",
+        usage: "",
+        description: "shows the usage of the kernel heap",
+        run: heap,
+    },
+    Command {
+        name: "help",
+        usage: "",
+        description: "lists the commands",
+        run: help,
+    },
+    Command {
+        name: "ls",
+        usage: "[DIRECTORY]",
+        description: "lists the files of a directory, the working one by default",
+        run: ls,
+    },
+    Command {
+        name: "lsirq",
+        usage: "",
+        description: "lists the device interrupts",
+        run: lsirq,
+    },
+    Command {
+        name: "mem",
+        usage: "",
+        description: "shows the usage of the physical memory",
+        run: mem,
+    },
+    Command {
+        name: "reboot",
+        usage: "",
+        description: "restarts the computer",
+        run: reboot,
+    },
+    Command {
+        name: "stat",
+        usage: "FILE...",
+        description: "shows the details of files",
+        run: stat,
+    },
+    Command {
+        name: "uptime",
+        usage: "",
+        description: "shows the time elapsed since boot",
+        run: uptime,
+    },
+];
+
+/// Runs the command `line`, a command name followed by its arguments separated by spaces, and
+/// prints its errors.
+pub(super) fn run(shell: &mut Shell, line: &str) {
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return;
+    };
+    let arguments: Vec<&str> = words.collect();
+
+    let Some(command) = COMMANDS.iter().find(|command| command.name == name) else {
+        println!("{name}: command not found, try `help`");
+        return;
+    };
+
+    match (command.run)(shell, &arguments) {
+        Ok(()) => {}
+        Err(Error::Usage) => println!("usage: {} {}", command.name, command.usage),
+        Err(Error::File(path, error)) => println!("{}: {path}: {error}", command.name),
+    }
+}
+
+fn cat(shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    for &path in arguments {
+        let contents = lookup(shell, path).and_then(fs::contents);
+        let contents = contents.map_err(|error| Error::File(path.into(), error))?;
+        print!("{}", String::from_utf8_lossy(&contents));
+    }
+    Ok(())
+}
+
+fn cd(shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    let path = match arguments {
+        [] => "/",
+        [path] => path,
+        _ => return Err(Error::Usage),
+    };
+
+    shell
+        .directory
+        .change(path)
+        .map_err(|error| Error::File(path.into(), error))
+}
+
+fn dmesg(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if !arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    logger::write_contents(|text| print!("{text}"));
+    Ok(())
+}
+
+fn font(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    let Some(writer) = WRITER.r#try() else {
+        println!("font: no frame buffer");
+        return Ok(());
+    };
+    let mut font = writer.lock().font();
+
+    if arguments.is_empty() {
+        let sizes: Vec<_> = Font::HEIGHTS.iter().map(|height| height.val()).collect();
+        println!("font: {font}");
+        println!("sizes: {sizes:?}, weights: light, regular, bold");
+        return Ok(());
+    }
+    for argument in arguments {
+        match argument.parse() {
+            Ok(pixels) => font.height = Font::height_from_pixels(pixels).ok_or(Error::Usage)?,
+            Err(_) => font.weight = Font::weight_from_name(argument).ok_or(Error::Usage)?,
+        }
+    }
+
+    writer.lock().set_font(font);
+    Ok(())
+}
+
+fn heap(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if !arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    let usage = ALLOCATOR.usage();
+    println!("heap at {HEAP_START:#x}");
+    println!("  size: {}", Size(usage.size as u64));
+    println!("  used: {}", Size(usage.used as u64));
+    println!("  free: {}", Size(usage.free as u64));
+    Ok(())
+}
+
+fn help(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if !arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    println!("commands:");
+    for command in COMMANDS {
+        let usage = alloc::format!("{} {}", command.name, command.usage);
+        println!("  {usage:<20} {}", command.description);
+    }
+    Ok(())
+}
+
+fn ls(shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    let path = match arguments {
+        [] => ".",
+        [path] => path,
+        _ => return Err(Error::Usage),
+    };
+
+    let entries = lookup(shell, path).and_then(fs::entries);
+    let mut entries = entries.map_err(|error| Error::File(path.into(), error))?;
+    entries.sort_by(|(first, _), (second, _)| first.cmp(second));
+
+    // hidden files, including `.` and `..`, are not listed
+    for (name, inode) in entries.iter().filter(|(name, _)| !name.starts_with('.')) {
+        let suffix = if let Type::Directory = inode.file_type() {
+            "/"
+        } else {
+            ""
+        };
+        println!("{} {:>10} {name}{suffix}", Mode(inode), inode.size());
+    }
+    Ok(())
+}
+
+fn lsirq(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if !arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    println!("VECTOR  IRQ  NAME");
+    for irq in interrupts::irq::registered() {
+        println!("{:>6}  {:>3}  {}", irq.vector, irq.isa_irq, irq.name);
+    }
+    Ok(())
+}
+
+fn mem(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if !arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    let (usable, allocated) = global::with_frame_allocator(|frame_allocator| {
+        (
+            frame_allocator.usable_frame_count() as u64,
+            frame_allocator.allocated_frame_count() as u64,
+        )
+    });
+    println!("physical memory, in frames of {}", Size(PAGE_SIZE));
+    println!("  usable: {} ({usable} frames)", Size(usable * PAGE_SIZE));
+    println!(
+        "  used:   {} ({allocated} frames)",
+        Size(allocated * PAGE_SIZE)
+    );
+    println!(
+        "  free:   {} ({} frames)",
+        Size((usable - allocated) * PAGE_SIZE),
+        usable - allocated
+    );
+    Ok(())
+}
+
+fn reboot(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if !arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    power::reboot()
+}
+
+fn stat(shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    for &path in arguments {
+        let inode = lookup(shell, path).map_err(|error| Error::File(path.into(), error))?;
+        let (user_id, group_id, hard_links) = (inode.user_id, inode.group_id, inode.hard_links);
+
+        println!("  file: {path}");
+        println!("  type: {}", type_name(inode.file_type()));
+        println!("  size: {}", inode.size());
+        println!(
+            "  mode: {:04o} ({})",
+            inode.permissions().bits(),
+            Mode(inode)
+        );
+        println!(" links: {hard_links}");
+        println!("   uid: {user_id}  gid: {group_id}");
+        println!("access: {}", Date(inode.last_access));
+        println!("modify: {}", Date(inode.last_modification));
+        println!("create: {}", Date(inode.creation_time));
+    }
+    Ok(())
+}
+
+fn uptime(_shell: &mut Shell, arguments: &[&str]) -> Result<(), Error> {
+    if !arguments.is_empty() {
+        return Err(Error::Usage);
+    }
+
+    let uptime = time::uptime();
+    let seconds = uptime.as_secs();
+    println!(
+        "up {}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        uptime.subsec_millis()
+    );
+    Ok(())
+}
+
+/// Returns the inode of `path`, relative to the working directory of the shell unless it is
+/// absolute.
+fn lookup(shell: &Shell, path: &str) -> Result<&'static Inode, fs::Error> {
+    fs::lookup(path, &shell.directory)
+}
+
+fn type_name(file_type: Type) -> &'static str {
+    match file_type {
+        Type::Fifo => "fifo",
+        Type::Character => "character device",
+        Type::Directory => "directory",
+        Type::Block => "block device",
+        Type::File => "regular file",
+        Type::Symlink => "symbolic link",
+        Type::Socket => "socket",
+    }
+}
+
+/// Type and permissions of a file, shown like `ls -l` does, as in `drwxr-xr-x`.
+pub(super) struct Mode<'a>(pub(super) &'a Inode);
+
+impl fmt::Display for Mode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let type_char = match self.0.file_type() {
+            Type::Fifo => 'p',
+            Type::Character => 'c',
+            Type::Directory => 'd',
+            Type::Block => 'b',
+            Type::File => '-',
+            Type::Symlink => 'l',
+            Type::Socket => 's',
+        };
+        write!(f, "{type_char}{}", permissions_string(self.0.permissions()))
+    }
+}
+
+/// Returns the permissions of the user, the group and the others, as in `rwxr-xr-x`.
+pub(super) fn permissions_string(permissions: Permissions) -> String {
+    let bits = [
+        (Permissions::USER_READ, 'r'),
+        (Permissions::USER_WRITE, 'w'),
+        (Permissions::USER_EXECUTE, 'x'),
+        (Permissions::GROUP_READ, 'r'),
+        (Permissions::GROUP_WRITE, 'w'),
+        (Permissions::GROUP_EXECUTE, 'x'),
+        (Permissions::OTHER_READ, 'r'),
+        (Permissions::OTHER_WRITE, 'w'),
+        (Permissions::OTHER_EXECUTE, 'x'),
+    ];
+
+    bits.iter()
+        .map(|&(bit, character)| {
+            if permissions.contains(bit) {
+                character
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// A size in bytes, shown in the largest binary unit in which it is at least 1.
+pub(super) struct Size(pub(super) u64);
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+        let mut unit = 0;
+        while unit + 1 < UNITS.len() && self.0 >> (10 * (unit + 1)) > 0 {
+            unit += 1;
+        }
+
+        let whole = self.0 >> (10 * unit);
+        if unit == 0 {
+            write!(f, "{whole} B")
+        } else {
+            let tenths = ((self.0 - (whole << (10 * unit))) * 10) >> (10 * unit);
+            write!(f, "{whole}.{tenths} {}", UNITS[unit])
+        }
+    }
+}
+
+/// A POSIX timestamp of the file system, shown as a UTC date.
+struct Date(u32);
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Ok(date_time) = Time::from_unix_timestamp(self.0).date_time() else {
+            return write!(f, "{} (invalid date)", self.0);
+        };
+
+        write!(
+            f,
+            "{}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+            date_time.year(),
+            date_time.month() as u8,
+            date_time.day(),
+            date_time.hour(),
+            date_time.minute(),
+            date_time.second()
+        )
+    }
+}
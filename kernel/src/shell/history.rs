@@ -0,0 +1,31 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+
+/// The lines entered in the shell, from the oldest to the newest.
+#[derive(Debug, Default)]
+pub(super) struct History {
+    lines: VecDeque<String>,
+}
+
+impl History {
+    /// Number of lines kept, the oldest ones are forgotten.
+    pub(super) const MAX_LINES: usize = 64;
+
+    /// Adds `line` as the newest line, unless it is blank or the same as the newest one.
+    pub(super) fn add(&mut self, line: &str) {
+        if line.trim().is_empty() || self.lines.back().is_some_and(|last| last == line) {
+            return;
+        }
+
+        if self.lines.len() == Self::MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(String::from(line));
+    }
+
+    /// Returns the line entered `age` lines ago, 0 being the newest one.
+    pub(super) fn get(&self, age: usize) -> Option<&str> {
+        let index = self.lines.len().checked_sub(age + 1)?;
+        Some(&self.lines[index])
+    }
+}
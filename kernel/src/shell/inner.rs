@@ -0,0 +1,85 @@
+use alloc::string::String;
+
+use drivers::display::frame_buffer::WRITER;
+use drivers::print;
+use drivers::serial::{Mode, COM1};
+
+use super::commands;
+use super::key::{Key, KeyDecoder};
+use super::line_editor::LineEditor;
+use crate::fs::WorkingDirectory;
+use crate::keyboard;
+use crate::thread::{self, JoinHandle};
+
+/// State of the shell that the commands change.
+#[derive(Debug)]
+pub(super) struct Shell {
+    pub(super) directory: WorkingDirectory,
+}
+
+/// Starts the shell on a new kernel thread.
+pub fn spawn() -> JoinHandle {
+    thread::spawn("shell", || run())
+}
+
+/// Reads commands typed on the keyboard or the serial terminal and runs them, forever.
+///
+/// The line is edited with the arrows, Home, End, Backspace and Delete keys, and the previous
+/// lines are recalled with the up and down arrows.
+pub fn run() -> ! {
+    let mut shell = Shell {
+        directory: WorkingDirectory::root(),
+    };
+    let mut editor = LineEditor::default();
+    let mut keys = Keys::default();
+
+    if let Some(writer) = WRITER.r#try() {
+        writer.lock().set_cursor_visible(true);
+    }
+    // The line is edited here, so the serial terminal passes the keys as they are typed
+    COM1.lock().set_mode(Mode::Raw);
+    commands::run(&mut shell, "help");
+
+    loop {
+        print!("{}> ", shell.directory.path());
+
+        let line = loop {
+            let mut output = String::new();
+            let line = editor.handle(keys.next(), &mut output);
+            print!("{output}");
+
+            if let Some(line) = line {
+                break line;
+            }
+        };
+        commands::run(&mut shell, &line);
+    }
+}
+
+/// The keys typed on the keyboard.
+#[derive(Debug, Default)]
+struct Keys {
+    decoder: KeyDecoder,
+    /// Bytes read from the keyboard, `start..end` are not decoded yet
+    buffer: [u8; 32],
+    start: usize,
+    end: usize,
+}
+
+impl Keys {
+    /// Waits for the next key.
+    fn next(&mut self) -> Key {
+        loop {
+            if self.start == self.end {
+                self.end = keyboard::read(&mut self.buffer);
+                self.start = 0;
+            }
+
+            let byte = self.buffer[self.start];
+            self.start += 1;
+            if let Some(key) = self.decoder.push(byte) {
+                return key;
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use alloc::vec::Vec;
+
+/// A key typed on the keyboard, decoded from the keyboard input by a [`KeyDecoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+}
+
+/// Decodes the bytes of the keyboard input into keys: UTF-8 characters, and the escape sequences
+/// of ANSI terminals for the keys that move the cursor.
+#[derive(Debug, Default)]
+pub(super) struct KeyDecoder {
+    /// Bytes of a character or an escape sequence that is not complete yet
+    pending: Vec<u8>,
+}
+
+impl KeyDecoder {
+    /// Longest escape sequence, longer ones are dropped.
+    const MAX_SEQUENCE_LENGTH: usize = 8;
+
+    /// Adds the next byte of the input, and returns the key that it completes.
+    ///
+    /// Invalid bytes and unknown escape sequences are dropped.
+    pub(super) fn push(&mut self, byte: u8) -> Option<Key> {
+        self.pending.push(byte);
+
+        let key = match self.pending.as_slice() {
+            [b'\x1b'] | [b'\x1b', b'['] => return None,
+            // parameters, such as digits and `;`, come before the final byte
+            [b'\x1b', b'[', .., b'0'..=b'?'] if self.pending.len() < Self::MAX_SEQUENCE_LENGTH => {
+                return None;
+            }
+            [b'\x1b', b'[', sequence @ ..] => decode_sequence(sequence),
+            [b'\x1b', ..] => None,
+            [b'\n' | b'\r'] => Some(Key::Enter),
+            [b'\x08' | b'\x7f'] => Some(Key::Backspace),
+            [first, ..] if self.pending.len() < utf8_length(*first) => return None,
+            bytes => core::str::from_utf8(bytes)
+                .ok()
+                .and_then(|string| string.chars().next())
+                .map(Key::Char),
+        };
+
+        self.pending.clear();
+        key
+    }
+}
+
+/// Returns the key of the escape sequence `ESC [ sequence`.
+fn decode_sequence(sequence: &[u8]) -> Option<Key> {
+    match sequence {
+        b"A" => Some(Key::Up),
+        b"B" => Some(Key::Down),
+        b"C" => Some(Key::Right),
+        b"D" => Some(Key::Left),
+        b"H" | b"1~" | b"7~" => Some(Key::Home),
+        b"F" | b"4~" | b"8~" => Some(Key::End),
+        b"3~" => Some(Key::Delete),
+        _ => None,
+    }
+}
+
+/// Returns the length of the UTF-8 character that starts with `first`, 1 for invalid bytes.
+fn utf8_length(first: u8) -> usize {
+    match first.leading_ones() {
+        2 => 2,
+        3 => 3,
+        4 => 4,
+        _ => 1,
+    }
+}
@@ -0,0 +1,154 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use super::history::History;
+use super::key::Key;
+
+/// Edits the line typed after the prompt of the shell, and keeps the [`History`] of the lines.
+///
+/// The line is shown on a screen on which the output is written, starting with the cursor right
+/// after the prompt. The cursor is only moved back with backspaces, which do not erase, and
+/// forward by writing again the chars it moves over.
+#[derive(Debug, Default)]
+pub(super) struct LineEditor {
+    line: Vec<char>,
+    /// Index in `line` of the char under the cursor
+    cursor: usize,
+    history: History,
+    /// Age of the line of the history that is shown, while browsing it
+    browsing: Option<usize>,
+    /// The line that was being typed before browsing the history
+    draft: Vec<char>,
+}
+
+impl LineEditor {
+    /// Maximum length of a line, the chars typed after it are ignored.
+    pub(super) const MAX_LENGTH: usize = 256;
+
+    /// Applies `key` to the line, and writes to `output` what updates the screen.
+    ///
+    /// Returns the line once it is entered, and starts a new one.
+    pub(super) fn handle(&mut self, key: Key, output: &mut impl Write) -> Option<String> {
+        // writing to the screen cannot fail
+        let _ = match key {
+            Key::Enter => return Some(self.enter(output)),
+            Key::Char(character) => self.insert(character, output),
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                output.write_char('\x08').and_then(|()| self.delete(output))
+            }
+            Key::Delete if self.cursor < self.line.len() => self.delete(output),
+            Key::Left if self.cursor > 0 => {
+                self.cursor -= 1;
+                output.write_char('\x08')
+            }
+            Key::Right if self.cursor < self.line.len() => {
+                self.cursor += 1;
+                output.write_char(self.line[self.cursor - 1])
+            }
+            Key::Home => self.move_to(0, output),
+            Key::End => self.move_to(self.line.len(), output),
+            Key::Up => self.browse(self.browsing.map_or(0, |age| age + 1), output),
+            Key::Down => match self.browsing {
+                Some(0) => {
+                    let draft = core::mem::take(&mut self.draft);
+                    self.browsing = None;
+                    self.replace(draft, output)
+                }
+                Some(age) => self.browse(age - 1, output),
+                None => Ok(()),
+            },
+            Key::Backspace | Key::Delete | Key::Left | Key::Right => Ok(()),
+        };
+
+        None
+    }
+
+    /// Returns the line that is typed.
+    pub(super) fn line(&self) -> String {
+        self.line.iter().collect()
+    }
+
+    /// Moves to the next line, and returns the line that was typed after adding it to the history.
+    fn enter(&mut self, output: &mut impl Write) -> String {
+        let line = self.line();
+        let _ = self.move_to(self.line.len(), output);
+        let _ = output.write_char('\n');
+
+        self.history.add(&line);
+        self.line.clear();
+        self.cursor = 0;
+        self.browsing = None;
+        self.draft.clear();
+        line
+    }
+
+    /// Inserts `character` at the cursor, control characters are ignored.
+    fn insert(&mut self, character: char, output: &mut impl Write) -> fmt::Result {
+        if character.is_control() || self.line.len() == Self::MAX_LENGTH {
+            return Ok(());
+        }
+
+        self.line.insert(self.cursor, character);
+        self.cursor += 1;
+        self.write_end(self.cursor - 1, 0, output)
+    }
+
+    /// Removes the char under the cursor.
+    fn delete(&mut self, output: &mut impl Write) -> fmt::Result {
+        self.line.remove(self.cursor);
+        self.write_end(self.cursor, 1, output)
+    }
+
+    /// Writes the line from `start`, erases `erased` more chars after it, and moves back to the
+    /// cursor.
+    fn write_end(&self, start: usize, erased: usize, output: &mut impl Write) -> fmt::Result {
+        for &character in &self.line[start..] {
+            output.write_char(character)?;
+        }
+        for _ in 0..erased {
+            output.write_char(' ')?;
+        }
+        for _ in self.cursor..self.line.len() + erased {
+            output.write_char('\x08')?;
+        }
+        Ok(())
+    }
+
+    fn move_to(&mut self, position: usize, output: &mut impl Write) -> fmt::Result {
+        while self.cursor > position {
+            self.cursor -= 1;
+            output.write_char('\x08')?;
+        }
+        while self.cursor < position {
+            output.write_char(self.line[self.cursor])?;
+            self.cursor += 1;
+        }
+        Ok(())
+    }
+
+    /// Shows the line of the history entered `age` lines ago, if there is one.
+    fn browse(&mut self, age: usize, output: &mut impl Write) -> fmt::Result {
+        let Some(line) = self.history.get(age) else {
+            return Ok(());
+        };
+        let line = line.chars().collect();
+
+        if self.browsing.is_none() {
+            self.draft = self.line.clone();
+        }
+        self.browsing = Some(age);
+        self.replace(line, output)
+    }
+
+    /// Replaces the whole line by `line`, with the cursor at its end.
+    fn replace(&mut self, line: Vec<char>, output: &mut impl Write) -> fmt::Result {
+        self.move_to(0, output)?;
+        let erased = self.line.len().saturating_sub(line.len());
+
+        self.line = line;
+        self.cursor = self.line.len();
+        self.write_end(0, erased, output)
+    }
+}
@@ -0,0 +1,153 @@
+use alloc::string::String;
+
+use drivers::fs::ext2::Permissions;
+
+use super::commands::{permissions_string, Size};
+use super::history::History;
+use super::key::{Key, KeyDecoder};
+use super::line_editor::LineEditor;
+
+fn decode(input: &[u8]) -> alloc::vec::Vec<Key> {
+    let mut decoder = KeyDecoder::default();
+    input
+        .iter()
+        .filter_map(|&byte| decoder.push(byte))
+        .collect()
+}
+
+/// Types `keys`, and returns the line and what was written to the screen.
+fn edit(editor: &mut LineEditor, keys: &[Key]) -> (Option<String>, String) {
+    let mut output = String::new();
+    let mut entered = None;
+    for &key in keys {
+        if let Some(line) = editor.handle(key, &mut output) {
+            entered = Some(line);
+        }
+    }
+    (entered, output)
+}
+
+fn chars(text: &str) -> alloc::vec::Vec<Key> {
+    text.chars().map(Key::Char).collect()
+}
+
+#[test_case]
+fn test_decode_keys() {
+    assert_eq!(
+        decode("aé\r\x08".as_bytes()),
+        [Key::Char('a'), Key::Char('é'), Key::Enter, Key::Backspace]
+    );
+    assert_eq!(
+        decode(b"\x1b[A\x1b[B\x1b[C\x1b[D\x1b[H\x1b[F\x1b[3~"),
+        [
+            Key::Up,
+            Key::Down,
+            Key::Right,
+            Key::Left,
+            Key::Home,
+            Key::End,
+            Key::Delete
+        ]
+    );
+}
+
+#[test_case]
+fn test_decode_unknown_sequence() {
+    assert_eq!(
+        decode(b"\x1b[1;5Cx\x1b[99~y"),
+        [Key::Char('x'), Key::Char('y')]
+    );
+}
+
+#[test_case]
+fn test_insert_in_the_middle() {
+    let mut editor = LineEditor::default();
+    let mut keys = chars("ac");
+    keys.extend([Key::Left, Key::Char('b')]);
+
+    let (entered, output) = edit(&mut editor, &keys);
+    assert_eq!(entered, None);
+    assert_eq!(editor.line(), "abc");
+    assert_eq!(output, "ac\x08bc\x08");
+}
+
+#[test_case]
+fn test_backspace_and_delete() {
+    let mut editor = LineEditor::default();
+    let mut keys = chars("abcd");
+    keys.extend([Key::Left, Key::Backspace, Key::Home, Key::Delete]);
+
+    let (_, output) = edit(&mut editor, &keys);
+    assert_eq!(editor.line(), "bd");
+    assert_eq!(output, "abcd\x08\x08d \x08\x08\x08\x08bd \x08\x08\x08");
+}
+
+#[test_case]
+fn test_enter_from_the_middle() {
+    let mut editor = LineEditor::default();
+    let mut keys = chars("ls");
+    keys.extend([Key::Home, Key::Enter]);
+
+    let (entered, output) = edit(&mut editor, &keys);
+    assert_eq!(entered.as_deref(), Some("ls"));
+    assert_eq!(output, "ls\x08\x08ls\n");
+    assert_eq!(editor.line(), "");
+}
+
+#[test_case]
+fn test_browse_history() {
+    let mut editor = LineEditor::default();
+    let mut keys = chars("first");
+    keys.push(Key::Enter);
+    keys.extend(chars("second"));
+    keys.push(Key::Enter);
+    keys.extend(chars("dr"));
+    edit(&mut editor, &keys);
+
+    edit(&mut editor, &[Key::Up]);
+    assert_eq!(editor.line(), "second");
+    edit(&mut editor, &[Key::Up, Key::Up]);
+    assert_eq!(editor.line(), "first");
+    edit(&mut editor, &[Key::Down]);
+    assert_eq!(editor.line(), "second");
+
+    let (_, output) = edit(&mut editor, &[Key::Down]);
+    assert_eq!(editor.line(), "dr");
+    assert_eq!(output, "\x08\x08\x08\x08\x08\x08dr    \x08\x08\x08\x08");
+}
+
+#[test_case]
+fn test_history_skips_repeated_lines() {
+    let mut history = History::default();
+    history.add("ls");
+    history.add("ls");
+    history.add("  ");
+    history.add("cd /");
+    assert_eq!(history.get(0), Some("cd /"));
+    assert_eq!(history.get(1), Some("ls"));
+    assert_eq!(history.get(2), None);
+}
+
+#[test_case]
+fn test_history_forgets_oldest_lines() {
+    let mut history = History::default();
+    for index in 0..History::MAX_LINES + 1 {
+        history.add(&alloc::format!("line {index}"));
+    }
+    assert_eq!(history.get(History::MAX_LINES - 1), Some("line 1"));
+    assert_eq!(history.get(History::MAX_LINES), None);
+}
+
+#[test_case]
+fn test_permissions_string() {
+    let permissions = Permissions::from_bits_truncate(0o754);
+    assert_eq!(permissions_string(permissions), "rwxr-xr--");
+}
+
+#[test_case]
+fn test_size() {
+    assert_eq!(alloc::format!("{}", Size(512)), "512 B");
+    assert_eq!(alloc::format!("{}", Size(1536)), "1.5 KiB");
+    assert_eq!(alloc::format!("{}", Size(100 * 1024)), "100.0 KiB");
+    assert_eq!(alloc::format!("{}", Size(3 << 30)), "3.0 GiB");
+}
@@ -0,0 +1,9 @@
+mod percpu;
+mod startup;
+mod trampoline;
+
+pub use percpu::{cpu_count, current, init, KernelGs, PerCpu};
+pub use startup::start_application_processors;
+
+#[cfg(test)]
+mod tests;
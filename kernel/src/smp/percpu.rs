@@ -0,0 +1,114 @@
+use alloc::boxed::Box;
+use core::arch::asm;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use x86_64::instructions::segmentation::GS;
+use x86_64::registers::model_specific::GsBase;
+use x86_64::structures::idt::InterruptStackFrame;
+use x86_64::structures::tss::TaskStateSegment;
+use x86_64::VirtAddr;
+
+/// Number of processors whose per-CPU data is installed.
+static CPU_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Data private to a processor, found through its GS base.
+///
+/// While user code runs, the GS base is swapped with the kernel GS base: every entry point from
+/// user mode must execute `swapgs` before using it, and again before returning.
+#[derive(Debug)]
+#[repr(C)]
+pub struct PerCpu {
+    /// Address of the structure itself, so that it can be read from `gs:0`.
+    this: *const PerCpu,
+    /// Stack pointer loaded by the system call entry point.
+    pub(crate) kernel_stack: AtomicU64,
+    /// Stack pointer of the user code, saved by the system call entry point.
+    pub(crate) user_stack: AtomicU64,
+    tss: *mut TaskStateSegment,
+    index: usize,
+    apic_id: u8,
+}
+
+impl PerCpu {
+    /// Index of the processor: the bootstrap processor is 0, and the application processors
+    /// are numbered in the order they start.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Local APIC ID of the processor.
+    pub fn apic_id(&self) -> u8 {
+        self.apic_id
+    }
+
+    /// Sets the stack on which the processor enters the kernel from user mode, through an
+    /// interrupt or a system call.
+    ///
+    /// Interrupts must be disabled, so that the current thread stays on this processor.
+    pub fn set_kernel_stack(&self, stack_end: VirtAddr) {
+        self.kernel_stack
+            .store(stack_end.as_u64(), Ordering::Relaxed);
+        unsafe { (*self.tss).privilege_stack_table[0] = stack_end };
+    }
+}
+
+/// Allocates the per-CPU data of the current processor, whose TSS is `tss`, and points its GS
+/// base to it.
+///
+/// Must be called once on every processor, once the heap is initialized.
+pub fn init(tss: *mut TaskStateSegment) -> &'static PerCpu {
+    let per_cpu = Box::leak(Box::new(PerCpu {
+        this: core::ptr::null(),
+        kernel_stack: AtomicU64::new(0),
+        user_stack: AtomicU64::new(0),
+        tss,
+        index: CPU_COUNT.fetch_add(1, Ordering::Relaxed),
+        apic_id: (core::arch::x86_64::__cpuid(1).ebx >> 24) as u8,
+    }));
+    per_cpu.this = per_cpu;
+
+    GsBase::write(VirtAddr::from_ptr(per_cpu));
+    per_cpu
+}
+
+/// Returns the per-CPU data of the current processor.
+///
+/// [`init`] must have been called on the current processor.
+pub fn current() -> &'static PerCpu {
+    let this: *const PerCpu;
+    unsafe {
+        asm!("mov {}, gs:[0]", out(reg) this, options(nostack, preserves_flags, readonly));
+        &*this
+    }
+}
+
+/// Returns the number of processors that are online.
+pub fn cpu_count() -> usize {
+    CPU_COUNT.load(Ordering::Relaxed)
+}
+
+/// Swaps the GS base of an interrupt handler entered from user mode with the kernel one for as
+/// long as it is alive, so that the handler can use the per-CPU data.
+///
+/// Only needed by handlers that don't use `trap_stub!`, which does it on its own.
+pub struct KernelGs {
+    swapped: bool,
+}
+
+impl KernelGs {
+    pub fn enter(stack_frame: &InterruptStackFrame) -> Self {
+        let swapped = stack_frame.code_segment & 3 == 3;
+        if swapped {
+            unsafe { GS::swap() };
+        }
+        Self { swapped }
+    }
+}
+
+impl Drop for KernelGs {
+    fn drop(&mut self) {
+        if self.swapped {
+            unsafe { GS::swap() };
+        }
+    }
+}
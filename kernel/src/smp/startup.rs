@@ -0,0 +1,135 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use log::{error, info, warn};
+use x86_64::registers::control::Cr3;
+
+use super::percpu;
+use super::trampoline::{Parameters, Trampoline};
+use crate::acpi::find_table;
+use crate::acpi::madt::{Entry, LocalApicFlags, Madt};
+use crate::interrupts::{apic, idt};
+use crate::memory::constants::KERNEL_STACK_SIZE;
+use crate::memory::stack::{self, StackBounds};
+use crate::time::pit;
+use crate::{gdt, syscall, thread};
+
+/// PIT oscillations to wait after the INIT interrupt (10 ms).
+const INIT_DELAY: u16 = (pit::BASE_FREQUENCY / 100) as u16;
+/// PIT oscillations to wait after a startup interrupt (200 µs).
+const STARTUP_DELAY: u16 = (pit::BASE_FREQUENCY / 5000) as u16;
+/// PIT oscillations in a millisecond.
+const MILLISECOND: u16 = (pit::BASE_FREQUENCY / 1000) as u16;
+/// Milliseconds to wait for a processor to come online before giving up on it.
+const ONLINE_TIMEOUT: u32 = 100;
+
+/// Handed to an application processor through the trampoline.
+struct Startup {
+    /// Stack the processor starts on, which becomes the stack of its idle thread.
+    stack: StackBounds,
+    /// Set by the processor once it is online, after which it no longer reads this structure.
+    online: AtomicBool,
+}
+
+/// Starts the application processors listed in the MADT with the INIT-SIPI-SIPI sequence, one
+/// at a time, and waits for each of them to join the scheduler.
+///
+/// Must be called on the bootstrap processor once the scheduler is running.
+pub fn start_application_processors() {
+    let Some(madt) = find_table::<Madt>() else {
+        return;
+    };
+
+    let bootstrap_apic_id = percpu::current().apic_id();
+    let apic_ids: Vec<u8> = madt
+        .entries()
+        .filter_map(|entry| match entry {
+            Entry::LocalApic(local_apic) => {
+                let flags = local_apic.flags;
+                (flags.contains(LocalApicFlags::ENABLED) && local_apic.apic_id != bootstrap_apic_id)
+                    .then_some(local_apic.apic_id)
+            }
+            _ => None,
+        })
+        .collect();
+    if apic_ids.is_empty() {
+        return;
+    }
+
+    let mut trampoline = match Trampoline::install() {
+        Ok(trampoline) => trampoline,
+        Err(error) => {
+            error!("cannot start the application processors: {error}");
+            return;
+        }
+    };
+    let local_apic = apic::local_apic().expect("APIC not initialized");
+    let (page_table, _) = Cr3::read();
+
+    for apic_id in apic_ids {
+        let stack = stack::allocate("application processor", KERNEL_STACK_SIZE)
+            .expect("application processor stack allocation failed");
+        // leaked if the processor does not start, as it may still run the trampoline later
+        let startup = Box::into_raw(Box::new(Startup {
+            stack,
+            online: AtomicBool::new(false),
+        }));
+        // the flag is per processor so that one starting late cannot be taken for the next one
+        let online = unsafe { &(*startup).online };
+
+        trampoline.set_parameters(Parameters {
+            page_table: page_table.start_address().as_u64(),
+            stack_end: stack.end().as_u64(),
+            entry: application_processor_entry,
+            argument: startup as u64,
+        });
+
+        unsafe {
+            local_apic.send_init(apic_id);
+            pit::wait(INIT_DELAY);
+
+            // the second startup interrupt is ignored if the first one was received
+            for _ in 0..2 {
+                local_apic.send_startup(apic_id, trampoline.startup_page());
+                pit::wait(STARTUP_DELAY);
+            }
+        }
+
+        let mut waited = 0;
+        while !online.load(Ordering::Acquire) && waited < ONLINE_TIMEOUT {
+            pit::wait(MILLISECOND);
+            waited += 1;
+        }
+        if online.load(Ordering::Acquire) {
+            drop(unsafe { Box::from_raw(startup) });
+        } else {
+            warn!("the processor with APIC ID {apic_id} did not start");
+        }
+    }
+
+    info!("{} CPUs online", percpu::cpu_count());
+}
+
+/// First Rust code run by an application processor, in long mode with interrupts disabled and
+/// the GDT of the trampoline.
+extern "C" fn application_processor_entry(startup: u64) -> ! {
+    // freed by the bootstrap processor once `online` is set
+    let startup = unsafe { &*(startup as *const Startup) };
+    let stack = startup.stack;
+
+    let tss = gdt::init_application_processor().expect("interrupt stacks initialization failed");
+    idt::init();
+    let per_cpu = percpu::init(tss);
+    syscall::init();
+    apic::init_application_processor();
+
+    info!(
+        "CPU {} online (APIC ID {})",
+        per_cpu.index(),
+        per_cpu.apic_id()
+    );
+    startup.online.store(true, Ordering::Release);
+
+    thread::start_processor(stack)
+}
@@ -0,0 +1,28 @@
+use core::mem::offset_of;
+
+use x86_64::instructions::interrupts;
+
+use super::trampoline::Parameters;
+use super::{cpu_count, current};
+
+#[test_case]
+fn test_current_cpu() {
+    // the thread must not move to another processor in between
+    interrupts::without_interrupts(|| {
+        let cpu = current();
+        assert!(cpu.index() < cpu_count());
+        assert_eq!(
+            cpu.apic_id(),
+            (core::arch::x86_64::__cpuid(1).ebx >> 24) as u8
+        );
+    });
+}
+
+#[test_case]
+fn test_trampoline_parameters_layout() {
+    // offsets used by the trampoline code
+    assert_eq!(offset_of!(Parameters, page_table), 0);
+    assert_eq!(offset_of!(Parameters, stack_end), 8);
+    assert_eq!(offset_of!(Parameters, entry), 16);
+    assert_eq!(offset_of!(Parameters, argument), 24);
+}
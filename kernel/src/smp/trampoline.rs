@@ -0,0 +1,179 @@
+use core::arch::global_asm;
+use core::ptr::addr_of;
+
+use x86_64::registers::control::Cr3;
+use x86_64::structures::paging::{Mapper, Page, PageTableFlags, PhysFrame, Size4KiB};
+use x86_64::{PhysAddr, VirtAddr};
+
+use crate::memory::global::{self, phys_to_virt};
+
+/// Physical address at which the trampoline is copied. Application processors start there in
+/// real mode, so it must be page aligned and below 1 MiB.
+pub const ADDRESS: u64 = 0x8000;
+
+/// Values read by an application processor running the trampoline, at `smp_trampoline_parameters`.
+#[derive(Debug)]
+#[repr(C)]
+pub struct Parameters {
+    /// Physical address of the level 4 page table, which must be below 4 GiB.
+    pub page_table: u64,
+    /// Initial stack pointer.
+    pub stack_end: u64,
+    /// Called in 64-bit mode with `argument`, it must not return.
+    pub entry: extern "C" fn(u64) -> !,
+    pub argument: u64,
+}
+
+// Switches from real mode to protected mode, then enables paging with the kernel page table to
+// enter long mode. The trampoline runs at `ADDRESS`, where it is identity mapped, so every
+// address is computed from its offset in the trampoline.
+global_asm!(
+    ".pushsection .rodata.smp_trampoline, \"a\"",
+    ".balign 8",
+    ".global smp_trampoline_start",
+    "smp_trampoline_start:",
+    ".code16",
+    "    cli",
+    "    cld",
+    "    jmp .Lsmp_real_mode",
+    ".balign 8",
+    ".global smp_trampoline_parameters",
+    "smp_trampoline_parameters:",
+    "    .fill 4, 8, 0",
+    ".Lsmp_real_mode:",
+    // the processor starts with `cs` pointing to the trampoline
+    "    mov %cs, %ax",
+    "    mov %ax, %ds",
+    "    lgdtl .Lsmp_gdt_pointer - smp_trampoline_start",
+    "    mov %cr0, %eax",
+    "    or $1, %eax",
+    "    mov %eax, %cr0",
+    "    ljmpl $0x08, ${address} + .Lsmp_protected_mode - smp_trampoline_start",
+    ".code32",
+    ".Lsmp_protected_mode:",
+    "    mov $0x10, %ax",
+    "    mov %ax, %ds",
+    "    mov %ax, %es",
+    "    mov %ax, %ss",
+    // physical address extension
+    "    mov %cr4, %eax",
+    "    or $(1 << 5), %eax",
+    "    mov %eax, %cr4",
+    "    mov {address} + smp_trampoline_parameters - smp_trampoline_start, %eax",
+    "    mov %eax, %cr3",
+    // long mode and no-execute enable, in the EFER
+    "    mov $0xc0000080, %ecx",
+    "    rdmsr",
+    "    or $((1 << 8) | (1 << 11)), %eax",
+    "    wrmsr",
+    // paging and write protection
+    "    mov %cr0, %eax",
+    "    or $((1 << 31) | (1 << 16)), %eax",
+    "    mov %eax, %cr0",
+    "    ljmpl $0x18, ${address} + .Lsmp_long_mode - smp_trampoline_start",
+    ".code64",
+    ".Lsmp_long_mode:",
+    "    mov {address} + smp_trampoline_parameters - smp_trampoline_start + 8, %rsp",
+    "    mov {address} + smp_trampoline_parameters - smp_trampoline_start + 16, %rax",
+    "    mov {address} + smp_trampoline_parameters - smp_trampoline_start + 24, %rdi",
+    // end of the frame pointer chain
+    "    xor %ebp, %ebp",
+    "    call *%rax",
+    "    ud2",
+    ".balign 8",
+    ".Lsmp_gdt:",
+    "    .quad 0",
+    // 32-bit code, 32-bit data and 64-bit code segments
+    "    .quad 0x00cf9a000000ffff",
+    "    .quad 0x00cf92000000ffff",
+    "    .quad 0x00af9a000000ffff",
+    ".Lsmp_gdt_pointer:",
+    "    .word .Lsmp_gdt_pointer - .Lsmp_gdt - 1",
+    "    .long {address} + .Lsmp_gdt - smp_trampoline_start",
+    ".global smp_trampoline_end",
+    "smp_trampoline_end:",
+    ".popsection",
+    address = const ADDRESS,
+    options(att_syntax),
+);
+
+extern "C" {
+    static smp_trampoline_start: u8;
+    static smp_trampoline_parameters: u8;
+    static smp_trampoline_end: u8;
+}
+
+/// The trampoline, copied to [`ADDRESS`] and identity mapped in the kernel page table so that
+/// application processors keep running it once they enable paging. It is unmapped when dropped.
+#[derive(Debug)]
+pub struct Trampoline {
+    page: Page<Size4KiB>,
+}
+
+impl Trampoline {
+    /// Copies the trampoline to [`ADDRESS`] and identity maps it.
+    ///
+    /// Must be called once the ACPI tables are located, by a single processor at a time.
+    pub fn install() -> Result<Self, &'static str> {
+        let (page_table, _) = Cr3::read();
+        if page_table.start_address().as_u64() > u32::MAX as u64 {
+            return Err("the kernel page table is above 4 GiB");
+        }
+
+        let frame = PhysFrame::<Size4KiB>::containing_address(PhysAddr::new(ADDRESS));
+        let page = Page::containing_address(VirtAddr::new(ADDRESS));
+
+        global::with(|mapper, frame_allocator| {
+            if !frame_allocator.is_usable(frame) {
+                return Err("the trampoline frame is not usable");
+            }
+
+            let flags = PageTableFlags::PRESENT | PageTableFlags::WRITABLE;
+            unsafe { mapper.map_to(page, frame, flags, frame_allocator) }
+                .map_err(|_| "the trampoline page cannot be mapped")?
+                .flush();
+            Ok(())
+        })?;
+
+        let code = unsafe {
+            let start = addr_of!(smp_trampoline_start);
+            let end = addr_of!(smp_trampoline_end);
+            core::slice::from_raw_parts(start, end.offset_from(start) as usize)
+        };
+        unsafe {
+            let destination = phys_to_virt(frame.start_address()).as_mut_ptr::<u8>();
+            core::ptr::copy_nonoverlapping(code.as_ptr(), destination, code.len());
+        }
+
+        Ok(Self { page })
+    }
+
+    /// Sets the parameters read by the next application processor that runs the trampoline.
+    pub fn set_parameters(&mut self, parameters: Parameters) {
+        let offset = unsafe {
+            addr_of!(smp_trampoline_parameters).offset_from(addr_of!(smp_trampoline_start))
+        };
+        let address = phys_to_virt(PhysAddr::new(ADDRESS)) + offset as u64;
+
+        unsafe {
+            address
+                .as_mut_ptr::<Parameters>()
+                .write_volatile(parameters)
+        };
+    }
+
+    /// Physical page at which application processors must start, for the startup interrupt.
+    pub fn startup_page(&self) -> u8 {
+        (ADDRESS / 4096) as u8
+    }
+}
+
+impl Drop for Trampoline {
+    fn drop(&mut self) {
+        global::with(|mapper, _| {
+            if let Ok((_, flush)) = mapper.unmap(self.page) {
+                flush.flush();
+            }
+        });
+    }
+}
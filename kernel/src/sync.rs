@@ -0,0 +1,15 @@
+mod condvar;
+mod mutex;
+mod rwlock;
+mod semaphore;
+mod wait_queue;
+
+pub use condvar::Condvar;
+pub use mutex::{Mutex, MutexGuard};
+pub use rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+pub use semaphore::Semaphore;
+pub use utils::sync::{IrqSpinlock, IrqSpinlockGuard};
+pub use wait_queue::WaitQueue;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,52 @@
+use super::{MutexGuard, WaitQueue};
+
+/// A condition variable, to wait for a condition on the data protected by a
+/// [`Mutex`](super::Mutex).
+pub struct Condvar {
+    waiters: WaitQueue,
+}
+
+impl Condvar {
+    pub const fn new() -> Self {
+        Self {
+            waiters: WaitQueue::new(),
+        }
+    }
+
+    /// Unlocks the mutex, waits for a notification and locks the mutex again.
+    ///
+    /// Wakeups can be spurious, so the condition must be checked again, see [`Self::wait_while`].
+    pub fn wait<'a, T: ?Sized>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        let mutex = MutexGuard::mutex(&guard);
+
+        // queued before unlocking, so that a notification sent right after is not lost
+        self.waiters.park_after(|| drop(guard));
+        mutex.lock()
+    }
+
+    /// Waits while `condition` returns `true`.
+    pub fn wait_while<'a, T: ?Sized>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> MutexGuard<'a, T> {
+        while condition(&mut guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    pub fn notify_one(&self) {
+        self.waiters.wake_one();
+    }
+
+    pub fn notify_all(&self) {
+        self.waiters.wake_all();
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
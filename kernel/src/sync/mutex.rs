@@ -0,0 +1,137 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+#[cfg(debug_assertions)]
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::WaitQueue;
+#[cfg(debug_assertions)]
+use crate::thread;
+
+/// A mutual exclusion lock that parks the threads waiting for it instead of spinning.
+///
+/// It must not be used in interrupt handlers, which cannot park, see
+/// [`IrqSpinlock`](super::IrqSpinlock). With debug assertions, locking it again from the thread
+/// that holds it panics with its name.
+pub struct Mutex<T: ?Sized> {
+    name: &'static str,
+    locked: AtomicBool,
+    waiters: WaitQueue,
+    /// ID + 1 of the thread holding the lock, 0 if it is free.
+    #[cfg(debug_assertions)]
+    owner: AtomicU64,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Sync for Mutex<T> {}
+unsafe impl<T: ?Sized + Send> Send for Mutex<T> {}
+
+pub struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<T> Mutex<T> {
+    pub const fn new(name: &'static str, data: T) -> Self {
+        Self {
+            name,
+            locked: AtomicBool::new(false),
+            waiters: WaitQueue::new(),
+            #[cfg(debug_assertions)]
+            owner: AtomicU64::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Parks the current thread until the lock is acquired.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        #[cfg(debug_assertions)]
+        if thread::is_running()
+            && self.owner.load(Ordering::Relaxed) == thread::current().as_u64() + 1
+        {
+            panic!(
+                "deadlock: mutex `{}` locked twice by the same thread",
+                self.name
+            );
+        }
+
+        self.waiters.wait_until(|| self.acquire());
+        self.guard()
+    }
+
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        self.acquire().then(|| self.guard())
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    fn acquire(&self) -> bool {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    fn guard(&self) -> MutexGuard<'_, T> {
+        #[cfg(debug_assertions)]
+        if thread::is_running() {
+            self.owner
+                .store(thread::current().as_u64() + 1, Ordering::Relaxed);
+        }
+
+        MutexGuard { mutex: self }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Mutex");
+        debug.field("name", &self.name);
+        match self.try_lock() {
+            Some(guard) => debug.field("data", &&*guard),
+            None => debug.field("data", &format_args!("<locked>")),
+        };
+        debug.finish()
+    }
+}
+
+impl<'a, T: ?Sized> MutexGuard<'a, T> {
+    /// Returns the mutex the guard locks, to lock it again after unlocking it.
+    pub(super) fn mutex(guard: &Self) -> &'a Mutex<T> {
+        guard.mutex
+    }
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        self.mutex.owner.store(0, Ordering::Relaxed);
+        self.mutex.locked.store(false, Ordering::Release);
+        self.mutex.waiters.wake_one();
+    }
+}
@@ -0,0 +1,90 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use super::{Condvar, IrqSpinlock, Mutex, RwLock, Semaphore};
+use crate::thread;
+
+#[test_case]
+fn test_irq_spinlock_restores_interrupts() {
+    use x86_64::instructions::interrupts;
+
+    let lock = IrqSpinlock::new("test", 0);
+    assert!(interrupts::are_enabled());
+    {
+        let mut guard = lock.lock();
+        assert!(!interrupts::are_enabled());
+        assert!(lock.try_lock().is_none());
+        *guard += 1;
+    }
+    assert!(interrupts::are_enabled());
+    assert_eq!(*lock.lock(), 1);
+}
+
+#[test_case]
+fn test_mutex_between_threads() {
+    let counter = Arc::new(Mutex::new("test", 0));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let counter = counter.clone();
+            thread::spawn("mutex test", move || {
+                for _ in 0..100 {
+                    let mut guard = counter.lock();
+                    let value = *guard;
+                    // let other threads run while the lock is held
+                    thread::yield_now();
+                    *guard = value + 1;
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join();
+    }
+    assert_eq!(*counter.lock(), 400);
+}
+
+#[test_case]
+fn test_condvar() {
+    let pair = Arc::new((Mutex::new("test", false), Condvar::new()));
+
+    let notifier = pair.clone();
+    let handle = thread::spawn("condvar test", move || {
+        let (ready, condvar) = &*notifier;
+        *ready.lock() = true;
+        condvar.notify_all();
+    });
+
+    let (ready, condvar) = &*pair;
+    let guard = condvar.wait_while(ready.lock(), |ready| !*ready);
+    assert!(*guard);
+    drop(guard);
+    handle.join();
+}
+
+#[test_case]
+fn test_semaphore() {
+    let semaphore = Semaphore::new(2);
+    assert!(semaphore.try_acquire());
+    semaphore.acquire();
+    assert!(!semaphore.try_acquire());
+
+    semaphore.release();
+    assert_eq!(semaphore.available_permits(), 1);
+}
+
+#[test_case]
+fn test_rwlock() {
+    let lock = RwLock::new("test", 1);
+    {
+        let first = lock.read();
+        let second = lock.read();
+        assert_eq!(*first + *second, 2);
+        assert!(lock.try_write().is_none());
+    }
+
+    *lock.write() = 2;
+    assert!(lock.try_read().is_some());
+    assert_eq!(*lock.read(), 2);
+}
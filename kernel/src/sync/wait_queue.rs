@@ -0,0 +1,79 @@
+use alloc::collections::VecDeque;
+
+use utils::sync::IrqSpinlock;
+
+use crate::thread::{self, ThreadId};
+
+/// Threads waiting for a condition, parked until another thread wakes them up.
+pub struct WaitQueue {
+    waiters: IrqSpinlock<VecDeque<ThreadId>>,
+}
+
+impl WaitQueue {
+    pub const fn new() -> Self {
+        Self {
+            waiters: IrqSpinlock::new("WaitQueue", VecDeque::new()),
+        }
+    }
+
+    /// Parks the current thread until `condition` returns `true`.
+    ///
+    /// The condition is checked again after the thread is queued, so a wakeup that happens in
+    /// between is not lost. Before the scheduler runs, this spins instead.
+    pub fn wait_until(&self, mut condition: impl FnMut() -> bool) {
+        while !condition() {
+            if !thread::is_running() {
+                core::hint::spin_loop();
+                continue;
+            }
+
+            let current = thread::current();
+            self.waiters.lock().push_back(current);
+
+            if condition() {
+                self.remove(current);
+                return;
+            }
+            thread::park();
+            self.remove(current);
+        }
+    }
+
+    /// Queues the current thread, calls `before_park` and parks the thread once.
+    ///
+    /// The thread may wake up spuriously, callers must check their condition again.
+    pub fn park_after(&self, before_park: impl FnOnce()) {
+        let current = thread::current();
+        self.waiters.lock().push_back(current);
+
+        before_park();
+        thread::park();
+        self.remove(current);
+    }
+
+    /// Wakes the thread that has waited the longest up, and returns `false` if there was none.
+    pub fn wake_one(&self) -> bool {
+        let waiter = self.waiters.lock().pop_front();
+        if let Some(waiter) = waiter {
+            thread::unpark(waiter);
+        }
+        waiter.is_some()
+    }
+
+    pub fn wake_all(&self) {
+        let waiters = core::mem::take(&mut *self.waiters.lock());
+        for waiter in waiters {
+            thread::unpark(waiter);
+        }
+    }
+
+    fn remove(&self, id: ThreadId) {
+        self.waiters.lock().retain(|&waiter| waiter != id);
+    }
+}
+
+impl Default for WaitQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
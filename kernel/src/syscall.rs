@@ -0,0 +1,11 @@
+mod entry;
+mod handlers;
+mod init;
+mod table;
+
+pub use entry::{return_to_user, SyscallFrame};
+pub use init::init;
+pub use table::{dispatch, number, open_flag, wait_option, Error};
+
+#[cfg(test)]
+mod tests;
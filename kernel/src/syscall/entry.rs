@@ -0,0 +1,111 @@
+use core::arch::naked_asm;
+use core::mem::offset_of;
+
+use crate::smp::PerCpu;
+
+/// Everything saved on the kernel stack by [`entry`], passed to [`super::dispatch`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SyscallFrame {
+    /// The registers preserved by the calls of the kernel, saved anyway so that the frame holds
+    /// every register of the user code, which `fork` copies.
+    pub r15: u64,
+    pub r14: u64,
+    pub r13: u64,
+    pub r12: u64,
+    pub rbx: u64,
+    /// Saved so that it can be cleared, which ends backtraces at the system call.
+    pub rbp: u64,
+    /// The arguments, passed in `rdi`, `rsi`, `rdx`, `r10`, `r8` and `r9`.
+    pub arguments: [u64; 6],
+    /// The system call number, passed in `rax`.
+    pub number: u64,
+    /// The flags of the user code, saved in `r11` by `syscall`.
+    pub rflags: u64,
+    /// The return address, saved in `rcx` by `syscall`.
+    pub rip: u64,
+    /// The stack pointer of the user code.
+    pub rsp: u64,
+}
+
+/// Restores the registers saved in a [`SyscallFrame`] at `rsp` but `rax`, and returns to the
+/// user code.
+macro_rules! return_to_user {
+    () => {
+        concat!(
+            "pop r15\n",
+            "pop r14\n",
+            "pop r13\n",
+            "pop r12\n",
+            "pop rbx\n",
+            "pop rbp\n",
+            "pop rdi\n",
+            "pop rsi\n",
+            "pop rdx\n",
+            "pop r10\n",
+            "pop r8\n",
+            "pop r9\n",
+            // skip the number, `rax` holds the return value
+            "add rsp, 8\n",
+            "pop r11\n",
+            "pop rcx\n",
+            "pop rsp\n",
+            "swapgs\n",
+            "sysretq\n",
+        )
+    };
+}
+
+/// Entry point of the `syscall` instruction.
+///
+/// It switches to the kernel GS base and to the kernel stack of the current thread, saves a
+/// [`SyscallFrame`] and calls [`super::dispatch`] with interrupts enabled. The return value goes
+/// back to the user code in `rax`, and only `rcx` and `r11` are clobbered, as `sysret` uses them.
+#[unsafe(naked)]
+pub(super) unsafe extern "C" fn entry() {
+    naked_asm!(
+        // interrupts are masked through `SFMASK`, until the kernel stack is ready
+        "swapgs",
+        "mov gs:[{user_stack}], rsp",
+        "mov rsp, gs:[{kernel_stack}]",
+        "push qword ptr gs:[{user_stack}]",
+        "push rcx",
+        "push r11",
+        "push rax",
+        "push r9",
+        "push r8",
+        "push r10",
+        "push rdx",
+        "push rsi",
+        "push rdi",
+        "push rbp",
+        "push rbx",
+        "push r12",
+        "push r13",
+        "push r14",
+        "push r15",
+        "xor ebp, ebp",
+        "mov rdi, rsp",
+        // 16 registers were pushed on the 16-byte aligned stack
+        "sti",
+        "call {dispatch}",
+        "cli",
+        return_to_user!(),
+        user_stack = const offset_of!(PerCpu, user_stack),
+        kernel_stack = const offset_of!(PerCpu, kernel_stack),
+        dispatch = sym super::dispatch,
+    );
+}
+
+/// Returns to the user code with the registers saved in `frame`, and `rax` set to `result`, as if
+/// the system call that saved the frame returned `result`.
+///
+/// # Safety
+///
+/// Interrupts must be disabled, the active GS base must be the kernel one, and the frame must
+/// come from a system call of the program that runs in the active address space. The current
+/// stack is abandoned.
+#[unsafe(naked)]
+pub unsafe extern "C" fn return_to_user(frame: &SyscallFrame, result: u64) -> ! {
+    naked_asm!("mov rax, rsi", "mov rsp, rdi", return_to_user!());
+}
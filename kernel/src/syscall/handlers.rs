@@ -0,0 +1,242 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use super::entry::SyscallFrame;
+use super::table::{open_flag, wait_option, Error};
+use crate::fs::{FileTable, OpenFile};
+use crate::memory::constants::PAGE_SIZE;
+use crate::memory::{cow, user};
+use crate::process::{self, ExitStatus, Pid};
+use crate::time;
+
+/// Maximum length of a path, with its null terminator.
+const PATH_MAX: usize = 4096;
+/// Maximum length of an argument or environment string, with its null terminator.
+const STRING_MAX: usize = 4096;
+/// Maximum number of arguments or environment strings.
+const STRINGS_MAX: usize = 256;
+/// Longest sleep, in milliseconds, longer ones being clamped to it (about 49 days).
+const SLEEP_MAX: u64 = u32::MAX as u64;
+
+pub(super) fn unknown(_frame: &SyscallFrame) -> Result<u64, Error> {
+    Err(Error::NoSuchSystemCall)
+}
+
+pub(super) fn read(frame: &SyscallFrame) -> Result<u64, Error> {
+    let [fd, buffer, length, ..] = frame.arguments;
+    let file = file(fd)?;
+    if !file.is_readable() {
+        return Err(Error::BadFileDescriptor);
+    }
+
+    let buffer = user_slice_mut(buffer, length)?;
+    Ok(file.read(buffer)? as u64)
+}
+
+pub(super) fn write(frame: &SyscallFrame) -> Result<u64, Error> {
+    let [fd, buffer, length, ..] = frame.arguments;
+    let file = file(fd)?;
+    if !file.is_writable() {
+        return Err(Error::BadFileDescriptor);
+    }
+
+    let buffer = user_slice(buffer, length)?;
+    Ok(file.write(buffer)? as u64)
+}
+
+pub(super) fn exit(frame: &SyscallFrame) -> Result<u64, Error> {
+    if process::current().is_none() {
+        return Err(Error::NoSuchProcess);
+    }
+    process::exit(ExitStatus::Exited(frame.arguments[0] as u8))
+}
+
+/// Kernel threads get 0, the identifier of the kernel.
+pub(super) fn getpid(_frame: &SyscallFrame) -> Result<u64, Error> {
+    Ok(process::current().unwrap_or(Pid::KERNEL).as_u64())
+}
+
+pub(super) fn sleep(frame: &SyscallFrame) -> Result<u64, Error> {
+    let milliseconds = frame.arguments[0].min(SLEEP_MAX);
+    time::sleep(Duration::from_millis(milliseconds));
+    Ok(0)
+}
+
+pub(super) fn fork(frame: &SyscallFrame) -> Result<u64, Error> {
+    Ok(process::fork(frame)?.as_u64())
+}
+
+pub(super) fn execve(frame: &SyscallFrame) -> Result<u64, Error> {
+    let [path, arguments, environment, ..] = frame.arguments;
+    if process::current().is_none() {
+        return Err(Error::NoSuchProcess);
+    }
+
+    let path = user_str(path, PATH_MAX)?;
+    let arguments = user_strings(arguments)?;
+    let environment = user_strings(environment)?;
+    Err(process::execve(path, arguments, environment).into())
+}
+
+pub(super) fn waitpid(frame: &SyscallFrame) -> Result<u64, Error> {
+    let [pid, status, options, ..] = frame.arguments;
+    let target = match pid as i64 {
+        -1 => None,
+        1.. => Some(Pid::new(pid)),
+        _ => return Err(Error::InvalidArgument),
+    };
+    if options & !wait_option::WNOHANG != 0 {
+        return Err(Error::InvalidArgument);
+    }
+    // checked before waiting, so that the child is not freed if it cannot be stored
+    let status = match status {
+        0 => None,
+        status => Some(user_slice_mut(status, size_of::<u32>() as u64)?),
+    };
+
+    let exited = if options & wait_option::WNOHANG != 0 {
+        process::try_wait(target)?
+    } else {
+        Some(process::wait(target)?)
+    };
+
+    let Some((pid, exit_status)) = exited else {
+        return Ok(0);
+    };
+    if let Some(status) = status {
+        status.copy_from_slice(&exit_status.to_wait_status().to_ne_bytes());
+    }
+    Ok(pid.as_u64())
+}
+
+pub(super) fn getppid(_frame: &SyscallFrame) -> Result<u64, Error> {
+    Ok(process::parent()?.as_u64())
+}
+
+pub(super) fn open(frame: &SyscallFrame) -> Result<u64, Error> {
+    let [path, flags, ..] = frame.arguments;
+    let path = user_str(path, PATH_MAX)?;
+    if flags & open_flag::O_ACCMODE != open_flag::O_RDONLY
+        || flags & (open_flag::O_CREAT | open_flag::O_TRUNC) != 0
+    {
+        return Err(Error::ReadOnlyFileSystem);
+    }
+
+    let directory = process::with_directory(|directory| directory.clone())?;
+    let file = Arc::new(OpenFile::open(path, &directory)?);
+    Ok(process::with_files(|files| files.insert(file))?? as u64)
+}
+
+pub(super) fn close(frame: &SyscallFrame) -> Result<u64, Error> {
+    let fd = usize::try_from(frame.arguments[0]).map_err(|_| Error::BadFileDescriptor)?;
+    process::with_files(|files| files.remove(fd))?.ok_or(Error::BadFileDescriptor)?;
+    Ok(0)
+}
+
+pub(super) fn chdir(frame: &SyscallFrame) -> Result<u64, Error> {
+    let path = user_str(frame.arguments[0], PATH_MAX)?;
+    process::with_directory(|directory| directory.change(path))??;
+    Ok(0)
+}
+
+pub(super) fn getcwd(frame: &SyscallFrame) -> Result<u64, Error> {
+    let [buffer, size, ..] = frame.arguments;
+    let path = process::with_directory(|directory| String::from(directory.path()))?;
+    if (size as usize) < path.len() + 1 {
+        return Err(Error::Range);
+    }
+
+    let buffer = user_slice_mut(buffer, path.len() as u64 + 1)?;
+    buffer[..path.len()].copy_from_slice(path.as_bytes());
+    buffer[path.len()] = 0;
+    Ok(path.len() as u64)
+}
+
+/// Returns the file opened as `fd` by the current process.
+///
+/// Kernel threads only have the standard streams.
+fn file(fd: u64) -> Result<Arc<OpenFile>, Error> {
+    let fd = usize::try_from(fd).map_err(|_| Error::BadFileDescriptor)?;
+    let file = match process::with_files(|files| files.get(fd)) {
+        Ok(file) => file,
+        Err(_) => FileTable::standard().get(fd),
+    };
+    file.ok_or(Error::BadFileDescriptor)
+}
+
+/// Returns the `length` bytes at `addr`, if the user code can read them.
+///
+/// The memory of a process is only freed once its single thread exits or replaces its program, so
+/// it stays valid for the whole system call.
+fn user_slice(addr: u64, length: u64) -> Result<&'static [u8], Error> {
+    if !user::is_accessible(addr, length, false) {
+        return Err(Error::BadAddress);
+    }
+    Ok(unsafe { core::slice::from_raw_parts(addr as *const u8, length as usize) })
+}
+
+/// Like [`user_slice`], for bytes that the user code can write.
+///
+/// Their copy-on-write pages are copied first, so that the kernel doesn't fault on them.
+fn user_slice_mut(addr: u64, length: u64) -> Result<&'static mut [u8], Error> {
+    if !user::is_accessible(addr, length, true) {
+        return Err(Error::BadAddress);
+    }
+    if !cow::resolve_user_range(addr, length) {
+        return Err(Error::OutOfMemory);
+    }
+    Ok(unsafe { core::slice::from_raw_parts_mut(addr as *mut u8, length as usize) })
+}
+
+/// Returns the null-terminated UTF-8 string at `addr`, which must be shorter than `max_length`
+/// with its terminator.
+fn user_str(addr: u64, max_length: usize) -> Result<&'static str, Error> {
+    let mut length = 0;
+
+    // checked page by page, as the string may end before an inaccessible page
+    while length < max_length {
+        let chunk_addr = addr.checked_add(length as u64).ok_or(Error::BadAddress)?;
+        let chunk_size = (PAGE_SIZE - chunk_addr % PAGE_SIZE).min((max_length - length) as u64);
+        let chunk = user_slice(chunk_addr, chunk_size)?;
+
+        if let Some(end) = chunk.iter().position(|&byte| byte == 0) {
+            let bytes = user_slice(addr, (length + end) as u64)?;
+            return core::str::from_utf8(bytes).map_err(|_| Error::InvalidArgument);
+        }
+        length += chunk.len();
+    }
+
+    Err(Error::NameTooLong)
+}
+
+/// Returns copies of the strings of the null-terminated array of pointers at `addr`, or no
+/// strings if it is null.
+fn user_strings(addr: u64) -> Result<Vec<String>, Error> {
+    let mut strings = Vec::new();
+    if addr == 0 {
+        return Ok(strings);
+    }
+
+    loop {
+        if strings.len() == STRINGS_MAX {
+            return Err(Error::ArgumentListTooLong);
+        }
+        let pointer_addr = (strings.len() as u64)
+            .checked_mul(8)
+            .and_then(|offset| addr.checked_add(offset))
+            .ok_or(Error::BadAddress)?;
+        let pointer = user_slice(pointer_addr, 8)?;
+        let pointer = u64::from_ne_bytes(pointer.try_into().unwrap());
+        if pointer == 0 {
+            return Ok(strings);
+        }
+
+        let string = user_str(pointer, STRING_MAX).map_err(|error| match error {
+            Error::NameTooLong => Error::ArgumentListTooLong,
+            error => error,
+        })?;
+        strings.push(String::from(string));
+    }
+}
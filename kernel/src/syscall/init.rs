@@ -0,0 +1,32 @@
+use x86_64::registers::model_specific::{Efer, EferFlags, LStar, SFMask, Star};
+use x86_64::registers::rflags::RFlags;
+use x86_64::VirtAddr;
+
+use super::entry;
+use crate::gdt::{
+    KERNEL_CODE_SELECTOR, KERNEL_DATA_SELECTOR, USER_CODE_SELECTOR, USER_DATA_SELECTOR,
+};
+
+/// Enables the `syscall` and `sysret` instructions on the current processor.
+///
+/// Must be called on every processor, once its GDT and per-CPU data are initialized.
+pub fn init() {
+    Star::write(
+        USER_CODE_SELECTOR,
+        USER_DATA_SELECTOR,
+        KERNEL_CODE_SELECTOR,
+        KERNEL_DATA_SELECTOR,
+    )
+    .expect("invalid segment selectors for syscall");
+    LStar::write(VirtAddr::new(entry::entry as *const () as u64));
+    // the entry point runs with interrupts disabled until it switches stacks, and the kernel
+    // expects the direction flag to be cleared
+    SFMask::write(
+        RFlags::INTERRUPT_FLAG
+            | RFlags::TRAP_FLAG
+            | RFlags::DIRECTION_FLAG
+            | RFlags::ALIGNMENT_CHECK,
+    );
+
+    unsafe { Efer::update(|flags| flags.insert(EferFlags::SYSTEM_CALL_EXTENSIONS)) };
+}
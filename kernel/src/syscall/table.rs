@@ -0,0 +1,165 @@
+use super::entry::SyscallFrame;
+use super::handlers;
+use crate::{elf, fs, process};
+
+/// Numbers of the system calls, passed in `rax`.
+pub mod number {
+    /// `read(fd, buffer, length)`: reads from a file, file descriptor 0 is the keyboard.
+    pub const READ: u64 = 0;
+    /// `write(fd, buffer, length)`: writes to a file, file descriptors 1 and 2 are the screen.
+    pub const WRITE: u64 = 1;
+    /// `exit(status)`: ends the process.
+    pub const EXIT: u64 = 2;
+    /// `getpid()`: returns the identifier of the process.
+    pub const GETPID: u64 = 3;
+    /// `sleep(milliseconds)`
+    pub const SLEEP: u64 = 4;
+    /// `fork()`: copies the process, returns the identifier of the child in the parent and 0 in
+    /// the child.
+    pub const FORK: u64 = 5;
+    /// `execve(path, argv, envp)`: replaces the program of the process by the executable `path`,
+    /// with the null-terminated arrays of strings `argv` and `envp`. Only returns on errors.
+    pub const EXECVE: u64 = 6;
+    /// `waitpid(pid, status, options)`: waits for the child `pid` to exit, or for any child if
+    /// `pid` is -1, and returns its identifier. Its wait status is stored at `status` unless it is
+    /// null, and with the `WNOHANG` option, 0 is returned if no child exited yet.
+    pub const WAITPID: u64 = 7;
+    /// `getppid()`: returns the identifier of the parent process, 0 for the kernel.
+    pub const GETPPID: u64 = 8;
+    /// `open(path, flags)`: opens a file, only for reading (`O_RDONLY`), and returns its file
+    /// descriptor.
+    pub const OPEN: u64 = 9;
+    /// `close(fd)`
+    pub const CLOSE: u64 = 10;
+    /// `chdir(path)`: changes the working directory of the process.
+    pub const CHDIR: u64 = 11;
+    /// `getcwd(buffer, size)`: stores the null-terminated path of the working directory, and
+    /// returns its length.
+    pub const GETCWD: u64 = 12;
+}
+
+/// Options of the `waitpid` system call.
+pub mod wait_option {
+    /// Returns 0 instead of waiting if no child exited yet.
+    pub const WNOHANG: u64 = 1;
+}
+
+/// Flags of the `open` system call, only reading is supported.
+pub mod open_flag {
+    pub const O_RDONLY: u64 = 0;
+    pub const O_WRONLY: u64 = 1;
+    pub const O_RDWR: u64 = 2;
+    /// Mask of the access mode, one of the flags above.
+    pub const O_ACCMODE: u64 = 3;
+    pub const O_CREAT: u64 = 0o100;
+    pub const O_TRUNC: u64 = 0o1000;
+}
+
+/// Errors of the system calls, returned to the user code as their negated value, like the POSIX
+/// error numbers whose name they mention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i64)]
+pub enum Error {
+    /// `ENOENT`
+    NoSuchFile = 2,
+    /// `ESRCH`, also returned when a kernel thread makes a system call reserved to processes
+    NoSuchProcess = 3,
+    /// `E2BIG`
+    ArgumentListTooLong = 7,
+    /// `ENOEXEC`
+    NotExecutable = 8,
+    /// `EBADF`
+    BadFileDescriptor = 9,
+    /// `ECHILD`
+    NoChildProcess = 10,
+    /// `ENOMEM`
+    OutOfMemory = 12,
+    /// `EFAULT`
+    BadAddress = 14,
+    /// `ENOTDIR`
+    NotADirectory = 20,
+    /// `EISDIR`
+    IsADirectory = 21,
+    /// `EINVAL`
+    InvalidArgument = 22,
+    /// `EMFILE`
+    TooManyFiles = 24,
+    /// `EROFS`
+    ReadOnlyFileSystem = 30,
+    /// `ERANGE`
+    Range = 34,
+    /// `ENAMETOOLONG`
+    NameTooLong = 36,
+    /// `ENOSYS`
+    NoSuchSystemCall = 38,
+}
+
+impl Error {
+    /// Returns the value of `rax` that reports the error.
+    pub fn to_return_value(self) -> u64 {
+        (-(self as i64)) as u64
+    }
+}
+
+impl From<fs::Error> for Error {
+    fn from(error: fs::Error) -> Self {
+        match error {
+            fs::Error::NotFound => Self::NoSuchFile,
+            fs::Error::NotADirectory => Self::NotADirectory,
+            fs::Error::IsADirectory => Self::IsADirectory,
+            fs::Error::ReadOnly => Self::ReadOnlyFileSystem,
+            fs::Error::NotReadable | fs::Error::NotWritable => Self::BadFileDescriptor,
+            fs::Error::TooManyFiles => Self::TooManyFiles,
+        }
+    }
+}
+
+impl From<process::Error> for Error {
+    fn from(error: process::Error) -> Self {
+        match error {
+            process::Error::NotAProcess => Self::NoSuchProcess,
+            process::Error::NoChild => Self::NoChildProcess,
+            process::Error::OutOfMemory => Self::OutOfMemory,
+            process::Error::File(error) => error.into(),
+            process::Error::Executable(elf::Error::ArgumentsTooLong) => Self::ArgumentListTooLong,
+            process::Error::Executable(elf::Error::Map(_)) => Self::OutOfMemory,
+            process::Error::Executable(_) => Self::NotExecutable,
+        }
+    }
+}
+
+type Handler = fn(&SyscallFrame) -> Result<u64, Error>;
+
+/// Number of system calls.
+const COUNT: usize = 13;
+
+/// Handlers of the system calls, indexed by their number.
+const TABLE: [Handler; COUNT] = {
+    let mut table: [Handler; COUNT] = [handlers::unknown; COUNT];
+    table[number::READ as usize] = handlers::read;
+    table[number::WRITE as usize] = handlers::write;
+    table[number::EXIT as usize] = handlers::exit;
+    table[number::GETPID as usize] = handlers::getpid;
+    table[number::SLEEP as usize] = handlers::sleep;
+    table[number::FORK as usize] = handlers::fork;
+    table[number::EXECVE as usize] = handlers::execve;
+    table[number::WAITPID as usize] = handlers::waitpid;
+    table[number::GETPPID as usize] = handlers::getppid;
+    table[number::OPEN as usize] = handlers::open;
+    table[number::CLOSE as usize] = handlers::close;
+    table[number::CHDIR as usize] = handlers::chdir;
+    table[number::GETCWD as usize] = handlers::getcwd;
+    table
+};
+
+/// Runs the system call described by `frame`, and returns the value of `rax` for the user code.
+///
+/// Called by the system call entry point, with interrupts enabled.
+pub extern "C" fn dispatch(frame: &mut SyscallFrame) -> u64 {
+    let result = match TABLE.get(frame.number as usize) {
+        Some(handler) => handler(frame),
+        None => Err(Error::NoSuchSystemCall),
+    };
+
+    result.unwrap_or_else(Error::to_return_value)
+}
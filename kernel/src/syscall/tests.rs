@@ -0,0 +1,67 @@
+use super::{dispatch, number, Error, SyscallFrame};
+
+fn call(number: u64, arguments: [u64; 6]) -> u64 {
+    let mut frame = SyscallFrame {
+        r15: 0,
+        r14: 0,
+        r13: 0,
+        r12: 0,
+        rbx: 0,
+        rbp: 0,
+        arguments,
+        number,
+        rflags: 0,
+        rip: 0,
+        rsp: 0,
+    };
+    dispatch(&mut frame)
+}
+
+#[test_case]
+fn test_unknown_syscall() {
+    assert_eq!(
+        call(1000, [0; 6]),
+        Error::NoSuchSystemCall.to_return_value()
+    );
+}
+
+#[test_case]
+fn test_getpid() {
+    assert_eq!(call(number::GETPID, [0; 6]), 0);
+}
+
+#[test_case]
+fn test_process_calls_from_kernel_thread() {
+    assert_eq!(
+        call(number::GETPPID, [0; 6]),
+        Error::NoSuchProcess.to_return_value()
+    );
+    assert_eq!(
+        call(number::CLOSE, [0; 6]),
+        Error::NoSuchProcess.to_return_value()
+    );
+}
+
+#[test_case]
+fn test_bad_file_descriptor() {
+    assert_eq!(
+        call(number::WRITE, [7, 0, 0, 0, 0, 0]),
+        Error::BadFileDescriptor.to_return_value()
+    );
+    assert_eq!(
+        call(number::READ, [1, 0, 0, 0, 0, 0]),
+        Error::BadFileDescriptor.to_return_value()
+    );
+}
+
+#[test_case]
+fn test_kernel_buffer_rejected() {
+    let message = b"kernel memory";
+    assert_eq!(
+        call(
+            number::WRITE,
+            [1, message.as_ptr() as u64, message.len() as u64, 0, 0, 0]
+        ),
+        Error::BadAddress.to_return_value()
+    );
+}
@@ -0,0 +1,8 @@
+pub mod executor;
+mod inner;
+
+pub use executor::spawn;
+pub use inner::{Task, TaskId};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,147 @@
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::task::Wake;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::task::{Context, Poll, Waker};
+
+use crossbeam_queue::ArrayQueue;
+use spin::Once;
+use utils::sync::IrqSpinlock;
+
+use super::{Task, TaskId};
+use crate::thread::{self, ThreadId};
+
+/// Maximum number of tasks woken up and not polled yet.
+const READY_QUEUE_SIZE: usize = 100;
+
+/// State shared between the executor thread, the wakers and [`spawn`].
+struct Shared {
+    /// Tasks spawned and not polled yet.
+    spawned: IrqSpinlock<Vec<Task>>,
+    /// Tasks woken up, filled by wakers which can run in interrupt handlers.
+    ready: ArrayQueue<TaskId>,
+    /// The thread running the executor, set once it starts.
+    thread: Once<ThreadId>,
+}
+
+static SHARED: Once<Shared> = Once::new();
+
+impl Shared {
+    /// Wakes the executor thread up, if it is parked.
+    fn notify(&self) {
+        if let Some(&thread) = self.thread.r#try() {
+            thread::unpark(thread);
+        }
+    }
+}
+
+/// Starts the executor on its own kernel thread.
+///
+/// Must be called once the scheduler is initialized.
+pub fn init() {
+    SHARED.call_once(|| Shared {
+        spawned: IrqSpinlock::new("executor spawned tasks", Vec::new()),
+        ready: ArrayQueue::new(READY_QUEUE_SIZE),
+        thread: Once::new(),
+    });
+
+    thread::spawn("executor", || Executor::new().run());
+}
+
+fn shared() -> &'static Shared {
+    SHARED.r#try().expect("executor not initialized")
+}
+
+/// Runs `future` to completion on the executor.
+pub fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    let shared = shared();
+
+    shared.spawned.lock().push(Task::new(future));
+    shared.notify();
+}
+
+/// A cooperative executor, which polls the tasks once they are woken up and parks its thread
+/// while none is.
+struct Executor {
+    tasks: BTreeMap<TaskId, Task>,
+    waker_cache: BTreeMap<TaskId, Waker>,
+}
+
+impl Executor {
+    fn new() -> Self {
+        Self {
+            tasks: BTreeMap::new(),
+            waker_cache: BTreeMap::new(),
+        }
+    }
+
+    fn run(&mut self) -> ! {
+        let shared = shared();
+        shared.thread.call_once(thread::current);
+
+        loop {
+            self.take_spawned_tasks();
+            self.run_ready_tasks();
+
+            // wakeups that happen before parking make `park` return immediately
+            if shared.ready.is_empty() && shared.spawned.lock().is_empty() {
+                thread::park();
+            }
+        }
+    }
+
+    fn take_spawned_tasks(&mut self) {
+        let shared = shared();
+        let spawned = core::mem::take(&mut *shared.spawned.lock());
+
+        for task in spawned {
+            let id = task.id();
+            if self.tasks.insert(id, task).is_some() {
+                panic!("task with same ID already in tasks");
+            }
+            // a full queue only delays the task until it is woken up again
+            let _ = shared.ready.push(id);
+        }
+    }
+
+    fn run_ready_tasks(&mut self) {
+        let shared = shared();
+
+        while let Some(id) = shared.ready.pop() {
+            // the task may have completed since it was woken up
+            let Some(task) = self.tasks.get_mut(&id) else {
+                continue;
+            };
+
+            let waker = self
+                .waker_cache
+                .entry(id)
+                .or_insert_with(|| Waker::from(Arc::new(TaskWaker { id })));
+            let mut context = Context::from_waker(waker);
+
+            if let Poll::Ready(()) = task.poll(&mut context) {
+                self.tasks.remove(&id);
+                self.waker_cache.remove(&id);
+            }
+        }
+    }
+}
+
+struct TaskWaker {
+    id: TaskId,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let shared = shared();
+        if shared.ready.push(self.id).is_err() {
+            panic!("executor ready queue full");
+        }
+        shared.notify();
+    }
+}
@@ -0,0 +1,28 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+use super::spawn;
+use crate::time::sleep;
+
+async fn number() -> usize {
+    42
+}
+
+#[test_case]
+fn test_spawn() {
+    let result = Arc::new(AtomicUsize::new(0));
+
+    let task_result = result.clone();
+    spawn(async move {
+        task_result.store(number().await, Ordering::Relaxed);
+    });
+
+    for _ in 0..100 {
+        if result.load(Ordering::Relaxed) == 42 {
+            return;
+        }
+        sleep(Duration::from_millis(1));
+    }
+    panic!("the task did not run");
+}
@@ -0,0 +1,11 @@
+mod context;
+mod scheduler;
+
+pub(crate) use scheduler::tick;
+pub use scheduler::{
+    current, exit, init, is_running, join, name, park, set_page_table, spawn, start_processor,
+    unpark, yield_now, JoinHandle, ThreadId,
+};
+
+#[cfg(test)]
+mod tests;
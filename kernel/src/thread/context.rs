@@ -0,0 +1,66 @@
+use core::arch::naked_asm;
+use core::sync::atomic::AtomicBool;
+
+use x86_64::VirtAddr;
+
+/// Number of registers saved by [`switch`]: the flags, `rbp`, `rbx` and `r12` to `r15`.
+const SAVED_REGISTERS: u64 = 7;
+
+/// Saves the callee-saved registers and the flags on the current stack, stores the stack pointer
+/// in `old_rsp`, and resumes the thread whose stack pointer is `new_rsp`.
+///
+/// `old_on_cpu` is cleared once the current stack is left, after which another processor may
+/// resume the current thread.
+///
+/// # Safety
+///
+/// `new_rsp` must be a stack pointer saved by `switch`, or returned by [`initial_stack`].
+#[unsafe(naked)]
+pub unsafe extern "C" fn switch(old_rsp: *mut u64, new_rsp: u64, old_on_cpu: *const AtomicBool) {
+    naked_asm!(
+        "pushfq",
+        "push rbp",
+        "push rbx",
+        "push r12",
+        "push r13",
+        "push r14",
+        "push r15",
+        "mov [rdi], rsp",
+        "mov rsp, rsi",
+        // stores are not reordered with older stores, so this releases the saved stack pointer
+        "mov byte ptr [rdx], 0",
+        "pop r15",
+        "pop r14",
+        "pop r13",
+        "pop r12",
+        "pop rbx",
+        "pop rbp",
+        "popfq",
+        "ret",
+    );
+}
+
+/// Prepares the stack that ends at `stack_end` so that switching to it calls `entry`, with
+/// interrupts disabled, and returns the stack pointer to switch to.
+///
+/// # Safety
+///
+/// The stack must be mapped and unused.
+pub unsafe fn initial_stack(stack_end: VirtAddr, entry: extern "C" fn() -> !) -> u64 {
+    let top = stack_end.align_down(16u64).as_mut_ptr::<u64>();
+
+    unsafe {
+        // return address of `entry`, which must never be used, and end of the frame pointer chain
+        top.sub(1).write(0);
+        // returned to by `switch`, 16-byte aligned as if `entry` was called
+        top.sub(2).write(entry as usize as u64);
+        // flags with only the reserved bit set, `rbp` and the other registers
+        let registers = top.sub(2 + SAVED_REGISTERS as usize);
+        for index in 0..SAVED_REGISTERS as usize {
+            registers.add(index).write(0);
+        }
+        registers.add(SAVED_REGISTERS as usize - 1).write(0x2);
+
+        registers as u64
+    }
+}
@@ -0,0 +1,472 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use spin::{Mutex, MutexGuard, Once};
+use x86_64::instructions::interrupts;
+use x86_64::registers::control::Cr3;
+use x86_64::structures::paging::PhysFrame;
+
+use super::context;
+use crate::memory::constants::KERNEL_STACK_SIZE;
+use crate::memory::global;
+use crate::memory::stack::{self, StackBounds};
+use crate::smp;
+
+/// Number of timer ticks a thread runs before being preempted.
+const TIME_SLICE: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ThreadId(u64);
+
+impl ThreadId {
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for ThreadId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    Ready,
+    /// Waiting for [`unpark`]
+    Blocked,
+    Exited,
+}
+
+struct Thread {
+    name: String,
+    state: State,
+    /// Stack pointer saved by the last switch away from the thread.
+    rsp: u64,
+    /// `None` for the boot thread, which runs on the stack given by the bootloader.
+    stack: Option<StackBounds>,
+    /// Function run by the thread, taken when it starts.
+    entry: Option<Box<dyn FnOnce() + Send>>,
+    /// Threads waiting for this one to exit.
+    joiners: Vec<ThreadId>,
+    /// Set by [`unpark`] while the thread is not blocked, so that its next [`park`] returns.
+    unparked: bool,
+    /// Set while a processor runs on the stack of the thread, and cleared by [`context::switch`]
+    /// once its stack pointer is saved, so that no other processor resumes it too early.
+    on_cpu: AtomicBool,
+    /// Level 4 table of the page table in which the thread runs.
+    page_table: PhysFrame,
+}
+
+/// Scheduling state of a processor.
+struct Cpu {
+    current: ThreadId,
+    /// Runs when no other thread is ready, it is never in the run queue.
+    idle: ThreadId,
+    /// Ticks left before the current thread is preempted.
+    ticks_left: u32,
+}
+
+struct Scheduler {
+    /// Threads are boxed so that their saved stack pointer does not move while switching.
+    threads: BTreeMap<ThreadId, Box<Thread>>,
+    /// Threads ready to run on any processor.
+    run_queue: VecDeque<ThreadId>,
+    /// Processors running threads, by [`smp::PerCpu::index`].
+    cpus: BTreeMap<usize, Cpu>,
+    next_id: u64,
+    /// Exited threads whose stack can be freed.
+    zombies: Vec<ThreadId>,
+}
+
+static SCHEDULER: Once<Mutex<Scheduler>> = Once::new();
+
+/// Handle to wait for a thread to exit.
+#[derive(Debug)]
+pub struct JoinHandle {
+    id: ThreadId,
+}
+
+impl JoinHandle {
+    pub fn id(&self) -> ThreadId {
+        self.id
+    }
+
+    /// Waits for the thread to exit.
+    pub fn join(self) {
+        join(self.id);
+    }
+}
+
+impl Scheduler {
+    fn add(&mut self, name: &str, entry: Option<Box<dyn FnOnce() + Send>>) -> ThreadId {
+        let id = ThreadId(self.next_id);
+        self.next_id += 1;
+
+        self.threads.insert(
+            id,
+            Box::new(Thread {
+                name: String::from(name),
+                state: State::Ready,
+                rsp: 0,
+                stack: None,
+                entry,
+                joiners: Vec::new(),
+                unparked: false,
+                on_cpu: AtomicBool::new(false),
+                page_table: global::kernel_level_4_frame(),
+            }),
+        );
+        id
+    }
+
+    /// Adds the code running on the current processor as its thread `current`, with `idle` as its
+    /// idle thread.
+    fn add_cpu(&mut self, current: ThreadId, idle: ThreadId) {
+        let thread = self.threads.get_mut(&current).unwrap();
+        thread.state = State::Running;
+        *thread.on_cpu.get_mut() = true;
+
+        self.cpus.insert(
+            smp::current().index(),
+            Cpu {
+                current,
+                idle,
+                ticks_left: TIME_SLICE,
+            },
+        );
+    }
+
+    /// Returns the scheduling state of the current processor.
+    fn cpu(&mut self) -> &mut Cpu {
+        self.cpus
+            .get_mut(&smp::current().index())
+            .expect("processor not running the scheduler")
+    }
+
+    fn current(&mut self) -> &mut Thread {
+        let current = self.cpu().current;
+        self.threads.get_mut(&current).unwrap()
+    }
+
+    /// Makes a thread ready to run if it is blocked, or makes its next [`park`] return.
+    fn wake(&mut self, id: ThreadId) {
+        let Some(thread) = self.threads.get_mut(&id) else {
+            return;
+        };
+
+        match thread.state {
+            State::Blocked => {
+                thread.state = State::Ready;
+                self.run_queue.push_back(id);
+            }
+            State::Running | State::Ready => thread.unparked = true,
+            State::Exited => {}
+        }
+    }
+}
+
+/// Turns the code that is running into the boot thread, named `main`, and starts scheduling on
+/// the bootstrap processor.
+///
+/// Must be called once memory management and the per-CPU data are initialized, before enabling
+/// interrupts.
+pub fn init() {
+    let mut scheduler = Scheduler {
+        threads: BTreeMap::new(),
+        run_queue: VecDeque::new(),
+        cpus: BTreeMap::new(),
+        next_id: 0,
+        zombies: Vec::new(),
+    };
+
+    let main = scheduler.add("main", None);
+
+    let idle = scheduler.add("idle 0", Some(Box::new(idle)));
+    let stack = stack::allocate("idle", KERNEL_STACK_SIZE).expect("idle stack allocation failed");
+    let thread = scheduler.threads.get_mut(&idle).unwrap();
+    thread.stack = Some(stack);
+    thread.rsp = unsafe { context::initial_stack(stack.end(), thread_entry) };
+
+    scheduler.add_cpu(main, idle);
+    SCHEDULER.call_once(|| Mutex::new(scheduler));
+}
+
+/// Turns the code running on an application processor, on `stack`, into the idle thread of the
+/// processor, and starts scheduling threads on it.
+///
+/// Must be called with interrupts disabled, once [`init`] is called and the per-CPU data of the
+/// processor is initialized.
+pub fn start_processor(stack: StackBounds) -> ! {
+    {
+        let mut scheduler = scheduler().lock();
+        let name = format!("idle {}", smp::current().index());
+        let idle = scheduler.add(&name, None);
+        scheduler.threads.get_mut(&idle).unwrap().stack = Some(stack);
+        scheduler.add_cpu(idle, idle);
+    }
+
+    interrupts::enable();
+    idle();
+    unreachable!("the idle thread returned");
+}
+
+/// Returns `true` once [`init`] is called.
+pub fn is_running() -> bool {
+    SCHEDULER.r#try().is_some()
+}
+
+fn scheduler() -> &'static Mutex<Scheduler> {
+    SCHEDULER.r#try().expect("scheduler not initialized")
+}
+
+/// Starts a kernel thread that runs `f`.
+pub fn spawn(name: &str, f: impl FnOnce() + Send + 'static) -> JoinHandle {
+    free_zombies();
+
+    let stack = stack::allocate(name, KERNEL_STACK_SIZE).expect("thread stack allocation failed");
+    let rsp = unsafe { context::initial_stack(stack.end(), thread_entry) };
+
+    let id = interrupts::without_interrupts(|| {
+        let mut scheduler = scheduler().lock();
+        let id = scheduler.add(name, Some(Box::new(f)));
+
+        let thread = scheduler.threads.get_mut(&id).unwrap();
+        thread.stack = Some(stack);
+        thread.rsp = rsp;
+        scheduler.run_queue.push_back(id);
+        id
+    });
+
+    JoinHandle { id }
+}
+
+/// Returns the identifier of the current thread.
+pub fn current() -> ThreadId {
+    interrupts::without_interrupts(|| scheduler().lock().cpu().current)
+}
+
+/// Returns the name of the thread `id`, if it has not exited.
+pub fn name(id: ThreadId) -> Option<String> {
+    interrupts::without_interrupts(|| {
+        let scheduler = scheduler().lock();
+        let thread = scheduler.threads.get(&id)?;
+        (thread.state != State::Exited).then(|| thread.name.clone())
+    })
+}
+
+/// Gives the CPU to the next ready thread.
+pub fn yield_now() {
+    interrupts::without_interrupts(|| switch(scheduler().lock(), State::Ready));
+}
+
+/// Blocks the current thread until [`unpark`] is called for it.
+///
+/// Returns immediately if the thread was unparked since it last parked, so spurious wakeups are
+/// possible and callers should check their condition in a loop.
+pub fn park() {
+    interrupts::without_interrupts(|| {
+        // checked under the same lock as the switch, so that an unpark from another processor
+        // cannot happen in between and be lost
+        let mut scheduler = scheduler().lock();
+        let current = scheduler.current();
+        if current.unparked {
+            current.unparked = false;
+            return;
+        }
+
+        switch(scheduler, State::Blocked);
+    });
+}
+
+/// Makes the current thread run in the page table whose level 4 table is `level_4_frame`, and
+/// switches to it.
+///
+/// # Safety
+///
+/// The page table must map the kernel, and stay valid until the thread exits or switches to
+/// another one.
+pub unsafe fn set_page_table(level_4_frame: PhysFrame) {
+    interrupts::without_interrupts(|| {
+        scheduler().lock().current().page_table = level_4_frame;
+        unsafe { load_page_table(level_4_frame) };
+    });
+}
+
+/// Wakes the thread `id` up if it is parked, or makes its next [`park`] return immediately.
+pub fn unpark(id: ThreadId) {
+    interrupts::without_interrupts(|| scheduler().lock().wake(id));
+}
+
+/// Exits the current thread, and wakes the threads waiting for it up.
+pub fn exit() -> ! {
+    interrupts::disable();
+
+    let mut scheduler = scheduler().lock();
+    let cpu = scheduler.cpu();
+    let id = cpu.current;
+    assert!(id != cpu.idle, "the idle thread cannot exit");
+
+    for joiner in core::mem::take(&mut scheduler.current().joiners) {
+        scheduler.wake(joiner);
+    }
+    scheduler.zombies.push(id);
+
+    switch(scheduler, State::Exited);
+    unreachable!("an exited thread was scheduled");
+}
+
+/// Waits for the thread `id` to exit.
+pub fn join(id: ThreadId) {
+    loop {
+        let exited = interrupts::without_interrupts(|| {
+            let mut scheduler = scheduler().lock();
+            let current = scheduler.cpu().current;
+            assert!(current != id, "a thread cannot join itself");
+
+            match scheduler.threads.get_mut(&id) {
+                Some(thread) if thread.state != State::Exited => {
+                    thread.joiners.push(current);
+                    false
+                }
+                _ => true,
+            }
+        });
+
+        if exited {
+            return;
+        }
+        park();
+    }
+}
+
+/// Counts a timer tick, and preempts the current thread at the end of its time slice.
+///
+/// Called from the timer interrupt handlers, on every processor.
+pub(crate) fn tick() {
+    let Some(scheduler) = SCHEDULER.r#try() else {
+        return;
+    };
+
+    let mut scheduler = scheduler.lock();
+    // the processor may not have joined the scheduler yet
+    let Some(cpu) = scheduler.cpus.get_mut(&smp::current().index()) else {
+        return;
+    };
+    cpu.ticks_left = cpu.ticks_left.saturating_sub(1);
+
+    if cpu.ticks_left == 0 {
+        switch(scheduler, State::Ready);
+    }
+}
+
+/// Switches the current processor to the next ready thread, leaving the current one in `state`.
+///
+/// Interrupts must be disabled.
+fn switch(mut scheduler: MutexGuard<'static, Scheduler>, state: State) {
+    let cpu = scheduler.cpu();
+    let current = cpu.current;
+    let idle = cpu.idle;
+    cpu.ticks_left = TIME_SLICE;
+
+    let next = match scheduler.run_queue.pop_front() {
+        Some(next) => next,
+        // keep running the current thread if it can
+        None if state == State::Ready => return,
+        None => idle,
+    };
+
+    let current_thread = scheduler.current();
+    current_thread.state = state;
+    let old_rsp = &mut current_thread.rsp as *mut u64;
+    let old_on_cpu = &current_thread.on_cpu as *const AtomicBool;
+
+    if state == State::Ready && current != idle {
+        scheduler.run_queue.push_back(current);
+    }
+    scheduler.cpu().current = next;
+
+    let next_thread = scheduler.threads.get_mut(&next).unwrap();
+    next_thread.state = State::Running;
+    if let Some(stack) = next_thread.stack {
+        // where the thread enters the kernel if it runs user code
+        smp::current().set_kernel_stack(stack.end());
+    }
+    let new_rsp = &next_thread.rsp as *const u64;
+    let new_on_cpu = &next_thread.on_cpu as *const AtomicBool;
+    let page_table = next_thread.page_table;
+    drop(scheduler);
+
+    // Safety: threads are boxed, and exited ones are only freed once no processor runs on them
+    unsafe {
+        // every page table maps the kernel, so its stacks stay mapped
+        load_page_table(page_table);
+        // the processor that ran the next thread last may still be switching away from it
+        while (*new_on_cpu).swap(true, Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+        context::switch(old_rsp, new_rsp.read(), old_on_cpu);
+    }
+}
+
+/// Loads the page table whose level 4 table is `level_4_frame`, unless it is already active.
+///
+/// # Safety
+///
+/// The page table must map the kernel.
+unsafe fn load_page_table(level_4_frame: PhysFrame) {
+    let (active, flags) = Cr3::read();
+    if active != level_4_frame {
+        unsafe { Cr3::write(level_4_frame, flags) };
+    }
+}
+
+/// Frees the stacks of the exited threads.
+fn free_zombies() {
+    let zombies = interrupts::without_interrupts(|| {
+        let mut scheduler = scheduler().lock();
+        let zombies = core::mem::take(&mut scheduler.zombies);
+        let (running, exited): (Vec<_>, Vec<_>) = zombies.into_iter().partition(|id| {
+            let thread = &scheduler.threads[id];
+            thread.on_cpu.load(Ordering::Acquire)
+        });
+        // a processor may still be switching away from a thread that just exited
+        scheduler.zombies = running;
+
+        exited
+            .into_iter()
+            .filter_map(|id| scheduler.threads.remove(&id))
+            .collect::<Vec<_>>()
+    });
+
+    for thread in zombies {
+        if let Some(stack) = thread.stack {
+            unsafe { stack::free(stack) };
+        }
+    }
+}
+
+/// First function run by every thread.
+extern "C" fn thread_entry() -> ! {
+    let entry = scheduler().lock().current().entry.take();
+    interrupts::enable();
+
+    if let Some(entry) = entry {
+        entry();
+    }
+    exit();
+}
+
+fn idle() {
+    loop {
+        x86_64::instructions::hlt();
+        // an interrupt may have woken a thread up
+        yield_now();
+    }
+}
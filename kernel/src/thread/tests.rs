@@ -0,0 +1,42 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use super::{current, spawn, yield_now};
+
+#[test_case]
+fn test_spawn_and_join() {
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let counter = counter.clone();
+            spawn("test", move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_ne!(handle.id(), current());
+        handle.join();
+    }
+    assert_eq!(counter.load(Ordering::Relaxed), 4);
+}
+
+#[test_case]
+fn test_preemption() {
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let spinner_counter = counter.clone();
+    // never yields, so it only stops running when preempted
+    let spinner = spawn("spinner", move || {
+        while spinner_counter.load(Ordering::Relaxed) == 0 {
+            core::hint::spin_loop();
+        }
+    });
+
+    yield_now();
+    counter.store(1, Ordering::Relaxed);
+    spinner.join();
+}
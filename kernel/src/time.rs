@@ -0,0 +1,15 @@
+pub mod clock;
+pub mod constants;
+mod init;
+pub mod pit;
+pub mod rtc;
+mod sleep;
+pub mod timers;
+pub mod tsc;
+
+pub use clock::{now, ticks, uptime};
+pub use init::init;
+pub use sleep::sleep;
+
+#[cfg(test)]
+mod tests;
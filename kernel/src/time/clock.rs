@@ -0,0 +1,54 @@
+use core::sync::atomic::{AtomicU16, AtomicU32, AtomicU64, Ordering};
+use core::time::Duration;
+
+use utils::posix::time::Time;
+
+use super::constants::NANOS_PER_SECOND;
+use super::pit;
+
+static TICKS: AtomicU64 = AtomicU64::new(0);
+/// PIT reload value, which gives the duration of a tick.
+static DIVISOR: AtomicU16 = AtomicU16::new(0);
+/// Wall-clock time read from the RTC when the clock started.
+static BOOT_TIME: AtomicU32 = AtomicU32::new(0);
+
+/// Starts counting ticks of `divisor` PIT oscillations, from the wall-clock time `boot_time`.
+pub(super) fn start(divisor: u16, boot_time: Time) {
+    DIVISOR.store(divisor, Ordering::Relaxed);
+    BOOT_TIME.store(boot_time.unix_timestamp(), Ordering::Relaxed);
+}
+
+/// Counts a timer interrupt, and returns the new number of ticks.
+pub(crate) fn tick() -> u64 {
+    TICKS.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// Returns the number of timer interrupts since the clock started.
+pub fn ticks() -> u64 {
+    TICKS.load(Ordering::Relaxed)
+}
+
+/// Returns the time elapsed since the clock started.
+pub fn uptime() -> Duration {
+    ticks_to_duration(ticks())
+}
+
+/// Returns the wall-clock time.
+pub fn now() -> Time {
+    let boot_time = BOOT_TIME.load(Ordering::Relaxed);
+    Time::from_unix_timestamp(boot_time + uptime().as_secs() as u32)
+}
+
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    let divisor = DIVISOR.load(Ordering::Relaxed) as u128;
+    let nanos = ticks as u128 * divisor * NANOS_PER_SECOND as u128 / pit::BASE_FREQUENCY as u128;
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+/// Returns the number of ticks that last at least `duration`, saturating at `u64::MAX`.
+pub fn duration_to_ticks(duration: Duration) -> u64 {
+    let divisor = (DIVISOR.load(Ordering::Relaxed) as u128).max(1);
+    let oscillations = duration.as_nanos() * pit::BASE_FREQUENCY as u128;
+    let ticks = oscillations.div_ceil(divisor * NANOS_PER_SECOND as u128);
+    u64::try_from(ticks).unwrap_or(u64::MAX)
+}
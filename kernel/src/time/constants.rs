@@ -0,0 +1,4 @@
+/// Frequency of the timer interrupts, in Hz.
+pub const TICK_FREQUENCY: u32 = 1000;
+
+pub const NANOS_PER_SECOND: u64 = 1_000_000_000;
@@ -0,0 +1,14 @@
+use super::constants::TICK_FREQUENCY;
+use super::{clock, pit, rtc, tsc};
+
+/// Programs the PIT to tick at [`TICK_FREQUENCY`], calibrates the TSC and reads the wall-clock
+/// time from the RTC.
+///
+/// Must be called once the ACPI tables are located, before enabling interrupts.
+pub fn init() {
+    let divisor = pit::divisor(TICK_FREQUENCY);
+    clock::start(divisor, rtc::read());
+    pit::start_periodic(divisor);
+
+    tsc::calibrate();
+}
@@ -0,0 +1,54 @@
+use x86_64::instructions::port::Port;
+
+/// Frequency of the oscillator of the Programmable Interval Timer, in Hz.
+pub const BASE_FREQUENCY: u32 = 1_193_182;
+
+const CHANNEL_0: u16 = 0x40;
+const CHANNEL_2: u16 = 0x42;
+const COMMAND: u16 = 0x43;
+/// Gate of channel 2 (bit 0), speaker (bit 1) and output of channel 2 (bit 5).
+const CHANNEL_2_CONTROL: u16 = 0x61;
+
+const CHANNEL_2_GATE: u8 = 1 << 0;
+const SPEAKER: u8 = 1 << 1;
+const CHANNEL_2_OUTPUT: u8 = 1 << 5;
+
+/// Returns the reload value that makes the PIT count at `frequency` as closely as possible.
+pub fn divisor(frequency: u32) -> u16 {
+    (BASE_FREQUENCY / frequency).clamp(1, u16::MAX as u32) as u16
+}
+
+/// Makes channel 0 raise IRQ 0 every `divisor` oscillations.
+pub fn start_periodic(divisor: u16) {
+    unsafe {
+        // channel 0, low then high byte, rate generator
+        Port::<u8>::new(COMMAND).write(0b0011_0100);
+
+        let mut channel_0 = Port::<u8>::new(CHANNEL_0);
+        channel_0.write(divisor as u8);
+        channel_0.write((divisor >> 8) as u8);
+    }
+}
+
+/// Busy-waits for `count` oscillations of channel 2, which does not raise any interrupt.
+pub fn wait(count: u16) {
+    unsafe {
+        let mut control = Port::<u8>::new(CHANNEL_2_CONTROL);
+        let value = control.read() & !(SPEAKER | CHANNEL_2_GATE);
+        control.write(value);
+
+        // channel 2, low then high byte, interrupt on terminal count
+        Port::<u8>::new(COMMAND).write(0b1011_0000);
+        let mut channel_2 = Port::<u8>::new(CHANNEL_2);
+        channel_2.write(count as u8);
+        channel_2.write((count >> 8) as u8);
+
+        // the count starts when the gate goes high
+        control.write(value | CHANNEL_2_GATE);
+        while control.read() & CHANNEL_2_OUTPUT == 0 {
+            core::hint::spin_loop();
+        }
+
+        control.write(value);
+    }
+}
@@ -0,0 +1,143 @@
+use utils::posix::time::Time;
+use x86_64::instructions::port::Port;
+
+use crate::acpi::fadt::Fadt;
+use crate::acpi::find_table;
+
+const CMOS_ADDRESS: u16 = 0x70;
+const CMOS_DATA: u16 = 0x71;
+/// Set in the CMOS address to keep NMIs disabled while accessing the CMOS.
+const NMI_DISABLE: u8 = 1 << 7;
+
+const SECONDS: u8 = 0x00;
+const MINUTES: u8 = 0x02;
+const HOURS: u8 = 0x04;
+const DAY: u8 = 0x07;
+const MONTH: u8 = 0x08;
+const YEAR: u8 = 0x09;
+const STATUS_A: u8 = 0x0a;
+const STATUS_B: u8 = 0x0b;
+
+/// Set in status register A while the RTC updates its registers.
+const UPDATE_IN_PROGRESS: u8 = 1 << 7;
+/// Set in status register B if the hours are in the 24-hour format.
+const HOURS_24: u8 = 1 << 1;
+/// Set in status register B if the values are binary instead of BCD.
+const BINARY: u8 = 1 << 2;
+/// Set in the hours register for PM hours in the 12-hour format.
+const PM: u8 = 1 << 7;
+
+/// Date and time, as read from the RTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+/// Reads the wall-clock time from the RTC, which is expected to be in UTC.
+pub fn read() -> Time {
+    let date_time = read_date_time();
+    let days = days_from_civil(date_time.year as i64, date_time.month, date_time.day);
+    let seconds = days * 86_400
+        + date_time.hours as i64 * 3600
+        + date_time.minutes as i64 * 60
+        + date_time.seconds as i64;
+
+    Time::from_unix_timestamp(seconds.clamp(0, u32::MAX as i64) as u32)
+}
+
+pub fn read_date_time() -> DateTime {
+    let century_register = find_table::<Fadt>().map_or(0, |fadt| fadt.century);
+
+    // read until two consecutive reads agree, to not mix values from before and after an update
+    let mut registers = read_registers(century_register);
+    loop {
+        let next = read_registers(century_register);
+        if next == registers {
+            break;
+        }
+        registers = next;
+    }
+
+    let [seconds, minutes, hours, day, month, year, century] = registers;
+    let status_b = read_register(STATUS_B);
+    let decode = |value: u8| match status_b & BINARY {
+        0 => bcd_to_binary(value),
+        _ => value,
+    };
+
+    let mut hours_24 = decode(hours & !PM);
+    if status_b & HOURS_24 == 0 {
+        // 12 AM is midnight and 12 PM is noon
+        hours_24 %= 12;
+        if hours & PM != 0 {
+            hours_24 += 12;
+        }
+    }
+
+    let year = decode(year) as u16;
+    let year = match century_register {
+        0 if year < 70 => 2000 + year,
+        0 => 1900 + year,
+        _ => decode(century) as u16 * 100 + year,
+    };
+
+    DateTime {
+        year,
+        month: decode(month),
+        day: decode(day),
+        hours: hours_24,
+        minutes: decode(minutes),
+        seconds: decode(seconds),
+    }
+}
+
+fn read_registers(century_register: u8) -> [u8; 7] {
+    while read_register(STATUS_A) & UPDATE_IN_PROGRESS != 0 {
+        core::hint::spin_loop();
+    }
+
+    let century = match century_register {
+        0 => 0,
+        register => read_register(register),
+    };
+
+    [
+        read_register(SECONDS),
+        read_register(MINUTES),
+        read_register(HOURS),
+        read_register(DAY),
+        read_register(MONTH),
+        read_register(YEAR),
+        century,
+    ]
+}
+
+fn read_register(register: u8) -> u8 {
+    unsafe {
+        Port::<u8>::new(CMOS_ADDRESS).write(NMI_DISABLE | register);
+        Port::<u8>::new(CMOS_DATA).read()
+    }
+}
+
+pub fn bcd_to_binary(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0f)
+}
+
+/// Returns the number of days between the Unix epoch and a date of the proleptic Gregorian
+/// calendar, see http://howardhinnant.github.io/date_algorithms.html#days_from_civil.
+pub fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let (month, day) = (month as i64, day as i64);
+    let year = if month <= 2 { year - 1 } else { year };
+
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
@@ -0,0 +1,25 @@
+use core::time::Duration;
+
+use super::{clock, timers};
+use crate::thread;
+
+/// Waits for at least `duration`.
+///
+/// The current thread is parked until then, or the CPU halted between timer interrupts if the
+/// scheduler is not running. Interrupts must be enabled.
+pub fn sleep(duration: Duration) {
+    let deadline = clock::ticks().saturating_add(clock::duration_to_ticks(duration));
+
+    if !thread::is_running() {
+        while clock::ticks() < deadline {
+            x86_64::instructions::hlt();
+        }
+        return;
+    }
+
+    let id = thread::current();
+    timers::after(duration, move || thread::unpark(id));
+    while clock::ticks() < deadline {
+        thread::park();
+    }
+}
@@ -0,0 +1,49 @@
+use super::rtc::{bcd_to_binary, days_from_civil};
+
+#[test_case]
+fn test_days_from_civil() {
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+    assert_eq!(days_from_civil(2024, 2, 29), 19_782);
+    assert_eq!(days_from_civil(1969, 12, 31), -1);
+}
+
+#[test_case]
+fn test_bcd_to_binary() {
+    assert_eq!(bcd_to_binary(0x00), 0);
+    assert_eq!(bcd_to_binary(0x09), 9);
+    assert_eq!(bcd_to_binary(0x59), 59);
+}
+
+#[test_case]
+fn test_duration_to_ticks_saturates() {
+    use core::time::Duration;
+
+    use super::clock::{duration_to_ticks, ticks};
+
+    assert_eq!(duration_to_ticks(Duration::MAX), u64::MAX);
+    assert_eq!(
+        ticks().saturating_add(duration_to_ticks(Duration::MAX)),
+        u64::MAX
+    );
+}
+
+#[test_case]
+fn test_sleep_and_timer() {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::time::Duration;
+
+    use super::{sleep, timers, uptime};
+
+    let fired = Arc::new(AtomicBool::new(false));
+    let flag = fired.clone();
+    timers::after(Duration::from_millis(5), move || {
+        flag.store(true, Ordering::Relaxed)
+    });
+
+    let start = uptime();
+    sleep(Duration::from_millis(10));
+    assert!(uptime() - start >= Duration::from_millis(10));
+    assert!(fired.load(Ordering::Relaxed));
+}
@@ -0,0 +1,88 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+use utils::sync::IrqSpinlock;
+
+use super::clock;
+
+/// Identifies a timer, to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimerId(u64);
+
+struct Timer {
+    /// Number of ticks between two runs of a periodic timer.
+    period: Option<u64>,
+    callback: Box<dyn FnMut() + Send>,
+}
+
+/// Pending timers, ordered by deadline in ticks.
+static TIMERS: IrqSpinlock<BTreeMap<(u64, TimerId), Timer>> =
+    IrqSpinlock::new("TIMERS", BTreeMap::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Runs `callback` once, when the uptime reaches `deadline`.
+///
+/// Callbacks run in the timer interrupt handler, so they must be short and must not block.
+pub fn at(deadline: Duration, callback: impl FnMut() + Send + 'static) -> TimerId {
+    insert(clock::duration_to_ticks(deadline), None, Box::new(callback))
+}
+
+/// Runs `callback` once, after `delay`.
+pub fn after(delay: Duration, callback: impl FnMut() + Send + 'static) -> TimerId {
+    let deadline = clock::ticks().saturating_add(clock::duration_to_ticks(delay));
+    insert(deadline, None, Box::new(callback))
+}
+
+/// Runs `callback` every `period`, starting after one period.
+pub fn every(period: Duration, callback: impl FnMut() + Send + 'static) -> TimerId {
+    let period = clock::duration_to_ticks(period).max(1);
+    insert(
+        clock::ticks().saturating_add(period),
+        Some(period),
+        Box::new(callback),
+    )
+}
+
+/// Cancels a timer, and returns `false` if it already ran or was cancelled.
+pub fn cancel(id: TimerId) -> bool {
+    let mut timers = TIMERS.lock();
+    let key = timers.keys().find(|(_, timer_id)| *timer_id == id).copied();
+    key.and_then(|key| timers.remove(&key)).is_some()
+}
+
+fn insert(deadline: u64, period: Option<u64>, callback: Box<dyn FnMut() + Send>) -> TimerId {
+    let id = TimerId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    let timer = Timer { period, callback };
+
+    TIMERS.lock().insert((deadline, id), timer);
+    id
+}
+
+/// Runs the callbacks of the timers whose deadline is `now` or earlier.
+///
+/// Called from the timer interrupt handler.
+pub(crate) fn run_expired(now: u64) {
+    loop {
+        let expired = {
+            let mut timers = TIMERS.lock();
+            match timers.first_key_value() {
+                Some((&(deadline, _), _)) if deadline <= now => timers.pop_first(),
+                _ => None,
+            }
+        };
+
+        // the lock is released, so that callbacks can add timers
+        let Some(((deadline, id), mut timer)) = expired else {
+            break;
+        };
+        (timer.callback)();
+
+        if let Some(period) = timer.period {
+            TIMERS
+                .lock()
+                .insert((deadline.saturating_add(period), id), timer);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::constants::NANOS_PER_SECOND;
+use super::pit;
+
+/// Frequency of the Time Stamp Counter in Hz, 0 until calibrated.
+static FREQUENCY: AtomicU64 = AtomicU64::new(0);
+
+/// Duration of the calibration, in PIT oscillations (10 ms).
+const CALIBRATION_COUNT: u16 = (pit::BASE_FREQUENCY / 100) as u16;
+
+pub fn read() -> u64 {
+    unsafe { core::arch::x86_64::_rdtsc() }
+}
+
+/// Measures the frequency of the Time Stamp Counter against the PIT.
+pub fn calibrate() -> u64 {
+    let start = read();
+    pit::wait(CALIBRATION_COUNT);
+    let elapsed = read() - start;
+
+    let frequency = elapsed * pit::BASE_FREQUENCY as u64 / CALIBRATION_COUNT as u64;
+    FREQUENCY.store(frequency, Ordering::Relaxed);
+    frequency
+}
+
+/// Returns the frequency of the Time Stamp Counter in Hz, once calibrated.
+pub fn frequency() -> Option<u64> {
+    match FREQUENCY.load(Ordering::Relaxed) {
+        0 => None,
+        frequency => Some(frequency),
+    }
+}
+
+/// Converts a number of Time Stamp Counter cycles to nanoseconds, once calibrated.
+pub fn cycles_to_nanos(cycles: u64) -> Option<u64> {
+    frequency()
+        .map(|frequency| (cycles as u128 * NANOS_PER_SECOND as u128 / frequency as u128) as u64)
+}
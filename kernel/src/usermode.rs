@@ -0,0 +1,180 @@
+use core::arch::naked_asm;
+
+use x86_64::instructions::interrupts;
+use x86_64::instructions::random::RdRand;
+use x86_64::structures::paging::mapper::MapToError;
+use x86_64::structures::paging::{PageTableFlags, PhysFrame, Size4KiB};
+use x86_64::VirtAddr;
+
+use crate::elf::{self, Executable};
+use crate::gdt::{USER_CODE_SELECTOR, USER_DATA_SELECTOR};
+use crate::memory::constants::{USER_REGION_END, USER_REGION_START, USER_STACK_SIZE};
+use crate::memory::AddressSpace;
+use crate::process::{self, Pid};
+use crate::thread;
+
+/// A program loaded in its own address space, ready to run.
+#[derive(Debug)]
+pub struct Program {
+    pub address_space: AddressSpace,
+    pub entry: VirtAddr,
+    pub stack_pointer: VirtAddr,
+}
+
+/// Loads `code`, a flat binary that starts at its first byte, which must be position
+/// independent.
+pub fn load(code: &[u8]) -> Result<Program, MapToError<Size4KiB>> {
+    let mut address_space = AddressSpace::new()?;
+    let entry = VirtAddr::new(USER_REGION_START);
+    address_space.map(entry, code.len().max(1) as u64, PageTableFlags::empty())?;
+    address_space.write(entry, code).expect("code not mapped");
+    map_stack(&mut address_space)?;
+
+    Ok(Program {
+        address_space,
+        entry,
+        stack_pointer: stack_end(),
+    })
+}
+
+/// Loads the ELF executable `image`, with the `arguments` and `environment` strings on its
+/// stack.
+///
+/// The executable must be statically linked, see [`Executable`]. It is read as it is loaded, see
+/// [`elf::Source`].
+pub fn load_executable(
+    image: &(impl elf::Source + ?Sized),
+    arguments: &[&str],
+    environment: &[&str],
+) -> Result<Program, elf::Error> {
+    let executable = Executable::parse(image)?;
+    let stack = elf::initial_stack(
+        &executable,
+        stack_end(),
+        arguments,
+        environment,
+        random_bytes(),
+    )?;
+
+    let mut address_space = AddressSpace::new()?;
+    elf::load(&executable, image, &mut address_space)?;
+    map_stack(&mut address_space)?;
+    address_space
+        .write(stack.pointer, &stack.contents)
+        .expect("stack not mapped");
+
+    Ok(Program {
+        address_space,
+        entry: executable.entry(),
+        stack_pointer: stack.pointer,
+    })
+}
+
+/// Runs `code` as a new process on a thread named `name`, see [`load`].
+///
+/// It can only interact with the kernel through system calls, and must end with the `exit` one.
+pub fn spawn(name: &str, code: &[u8]) -> Result<Pid, MapToError<Size4KiB>> {
+    Ok(process::spawn(name, load(code)?))
+}
+
+/// Runs the ELF executable `image` as a new process on a thread named `name`, see
+/// [`load_executable`].
+pub fn spawn_executable(
+    name: &str,
+    image: &[u8],
+    arguments: &[&str],
+    environment: &[&str],
+) -> Result<Pid, elf::Error> {
+    Ok(process::spawn(
+        name,
+        load_executable(image, arguments, environment)?,
+    ))
+}
+
+/// Switches the current thread to the address space whose level 4 table is `level_4_frame`, and
+/// jumps to `entry` in user mode with the stack pointer `stack_pointer`.
+///
+/// # Safety
+///
+/// The address space must stay alive until the thread switches away from it, and both addresses
+/// must be mapped in it for user code. The current stack is abandoned.
+pub(crate) unsafe fn enter(
+    level_4_frame: PhysFrame,
+    entry: VirtAddr,
+    stack_pointer: VirtAddr,
+) -> ! {
+    interrupts::disable();
+    unsafe {
+        thread::set_page_table(level_4_frame);
+        jump_to_user(entry.as_u64(), stack_pointer.as_u64())
+    }
+}
+
+/// End of the stack of every program, the top of the user region.
+fn stack_end() -> VirtAddr {
+    VirtAddr::new(USER_REGION_END)
+}
+
+fn map_stack(address_space: &mut AddressSpace) -> Result<(), MapToError<Size4KiB>> {
+    address_space.map(
+        stack_end() - USER_STACK_SIZE,
+        USER_STACK_SIZE,
+        PageTableFlags::WRITABLE | PageTableFlags::NO_EXECUTE,
+    )
+}
+
+/// Returns the bytes given to programs to seed their random number generators.
+///
+/// They come from the processor when it has a random number generator, and from the time stamp
+/// counter otherwise.
+fn random_bytes() -> [u8; 16] {
+    let mut bytes = [0; 16];
+    for chunk in bytes.chunks_exact_mut(8) {
+        let value = RdRand::new()
+            .and_then(RdRand::get_u64)
+            .unwrap_or_else(|| unsafe { core::arch::x86_64::_rdtsc() });
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+/// Jumps to `entry` in user mode, with the stack pointer `stack_end`, interrupts enabled and
+/// every other register cleared.
+///
+/// # Safety
+///
+/// Interrupts must be disabled, and both addresses must be mapped for user code. The current
+/// stack is abandoned, and is used again by the interrupts and system calls of the user code.
+#[unsafe(naked)]
+unsafe extern "C" fn jump_to_user(entry: u64, stack_end: u64) -> ! {
+    naked_asm!(
+        "swapgs",
+        "push {user_data}",
+        "push rsi",
+        // interrupts enabled, and the reserved bit
+        "push 0x202",
+        "push {user_code}",
+        "push rdi",
+        "xor eax, eax",
+        "xor ebx, ebx",
+        "xor ecx, ecx",
+        "xor edx, edx",
+        "xor esi, esi",
+        "xor edi, edi",
+        "xor ebp, ebp",
+        "xor r8d, r8d",
+        "xor r9d, r9d",
+        "xor r10d, r10d",
+        "xor r11d, r11d",
+        "xor r12d, r12d",
+        "xor r13d, r13d",
+        "xor r14d, r14d",
+        "xor r15d, r15d",
+        "iretq",
+        user_data = const USER_DATA_SELECTOR.0,
+        user_code = const USER_CODE_SELECTOR.0,
+    );
+}
+
+#[cfg(test)]
+mod tests;
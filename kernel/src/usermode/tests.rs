@@ -0,0 +1,86 @@
+use core::arch::global_asm;
+use core::ptr::addr_of;
+
+use super::{spawn, spawn_executable};
+use crate::elf;
+use crate::process::{self, ExitStatus};
+use crate::syscall::number;
+
+global_asm!(
+    ".pushsection .rodata.test_programs, \"a\"",
+    // prints its identifier and exits
+    "test_program_exit_start:",
+    "    mov eax, {getpid}",
+    "    syscall",
+    "    mov eax, {write}",
+    "    mov edi, 1",
+    "    lea rsi, [rip + 2f]",
+    "    lea rdx, [rip + 3f]",
+    "    sub rdx, rsi",
+    "    syscall",
+    "    mov eax, {exit}",
+    "    xor edi, edi",
+    "    syscall",
+    "    ud2",
+    "2:",
+    "    .ascii \"hello from user mode\\n\"",
+    "3:",
+    "test_program_exit_end:",
+    // runs a privileged instruction
+    "test_program_fault_start:",
+    "    cli",
+    "    ud2",
+    "test_program_fault_end:",
+    ".popsection",
+    getpid = const number::GETPID,
+    write = const number::WRITE,
+    exit = const number::EXIT,
+);
+
+extern "C" {
+    static test_program_exit_start: u8;
+    static test_program_exit_end: u8;
+    static test_program_fault_start: u8;
+    static test_program_fault_end: u8;
+}
+
+fn program(start: *const u8, end: *const u8) -> &'static [u8] {
+    unsafe { core::slice::from_raw_parts(start, end.offset_from(start) as usize) }
+}
+
+#[test_case]
+fn test_program_exits() {
+    let code = program(
+        addr_of!(test_program_exit_start),
+        addr_of!(test_program_exit_end),
+    );
+    let pid = spawn("exit test", code).unwrap();
+    assert_eq!(
+        process::wait(Some(pid)).unwrap(),
+        (pid, ExitStatus::Exited(0))
+    );
+}
+
+#[test_case]
+fn test_faulting_program_is_stopped() {
+    let code = program(
+        addr_of!(test_program_fault_start),
+        addr_of!(test_program_fault_end),
+    );
+    let pid = spawn("fault test", code).unwrap();
+    assert_eq!(process::wait(Some(pid)).unwrap(), (pid, ExitStatus::Killed));
+}
+
+#[test_case]
+fn test_executable_exits() {
+    let code = program(
+        addr_of!(test_program_exit_start),
+        addr_of!(test_program_exit_end),
+    );
+    let image = elf::tests::executable(code);
+    let pid = spawn_executable("executable test", &image, &["executable test"], &[]).unwrap();
+    assert_eq!(
+        process::wait(Some(pid)).unwrap(),
+        (pid, ExitStatus::Exited(0))
+    );
+}
@@ -0,0 +1,97 @@
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(utils::test::runner)]
+#![reexport_test_harness_main = "test_main"]
+
+use core::panic::PanicInfo;
+
+use bootloader_api::config::{BootloaderConfig, Mapping};
+use bootloader_api::{entry_point, BootInfo};
+use kernel::memory::{cow, global};
+use utils::hlt::hlt_loop;
+use x86_64::structures::paging::{FrameAllocator, Mapper, Page, PageTableFlags, Translate};
+use x86_64::VirtAddr;
+
+pub static BOOTLOADER_CONFIG: BootloaderConfig = {
+    let mut config = BootloaderConfig::new_default();
+    config.mappings.physical_memory = Some(Mapping::Dynamic);
+    config
+};
+
+entry_point!(kernel_main, config = &BOOTLOADER_CONFIG);
+
+fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
+    kernel::init(boot_info);
+
+    test_main();
+    hlt_loop();
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    utils::test::panic::handler(info)
+}
+
+/// Maps a fresh writable page at `original` and shares it copy-on-write at `copy`.
+fn map_shared_pages(original: Page, copy: Page) {
+    global::with(|mapper, frame_allocator| {
+        let frame = frame_allocator.allocate_frame().unwrap();
+        let flags = PageTableFlags::PRESENT | PageTableFlags::WRITABLE;
+        unsafe {
+            mapper
+                .map_to(original, frame, flags, frame_allocator)
+                .unwrap()
+                .flush();
+        }
+
+        let (frame, flags) = cow::share(mapper, original, frame_allocator).unwrap();
+        unsafe { cow::map_shared(mapper, copy, frame, flags, frame_allocator).unwrap() };
+    });
+}
+
+#[test_case]
+fn write_splits_shared_frame() {
+    let original = Page::containing_address(VirtAddr::new(0x_6666_0000_0000));
+    let copy = Page::containing_address(VirtAddr::new(0x_6666_0001_0000));
+    map_shared_pages(original, copy);
+
+    let original_ptr = original.start_address().as_mut_ptr::<u64>();
+    let copy_ptr = copy.start_address().as_mut_ptr::<u64>();
+
+    unsafe {
+        original_ptr.write_volatile(41);
+        assert_eq!(copy_ptr.read_volatile(), 41);
+
+        copy_ptr.write_volatile(13);
+        assert_eq!(original_ptr.read_volatile(), 41);
+        assert_eq!(copy_ptr.read_volatile(), 13);
+    }
+
+    global::with(|mapper, _| {
+        let original_frame = mapper.translate_addr(original.start_address());
+        let copy_frame = mapper.translate_addr(copy.start_address());
+        assert_ne!(original_frame, copy_frame);
+    });
+}
+
+#[test_case]
+fn last_reference_is_made_writable() {
+    let original = Page::containing_address(VirtAddr::new(0x_6666_0002_0000));
+    let copy = Page::containing_address(VirtAddr::new(0x_6666_0003_0000));
+    map_shared_pages(original, copy);
+
+    unsafe {
+        // the first write copies the frame, the second one finds it with a single reference
+        original
+            .start_address()
+            .as_mut_ptr::<u64>()
+            .write_volatile(1);
+        copy.start_address().as_mut_ptr::<u64>().write_volatile(2);
+    }
+
+    global::with(|mapper, frame_allocator| {
+        let frame = mapper.translate_page(copy).unwrap();
+        assert_eq!(frame_allocator.reference_count(frame), 1);
+    });
+}
@@ -0,0 +1,121 @@
+#![no_std]
+#![no_main]
+#![feature(abi_x86_interrupt)]
+
+use core::arch::asm;
+use core::panic::PanicInfo;
+
+use bootloader_api::config::{BootloaderConfig, Mapping};
+use bootloader_api::{entry_point, BootInfo};
+use kernel::memory::{constants::KERNEL_STACK_SIZE, stack};
+use utils::print_serial;
+
+const TASK_NAME: &str = "guard_page test";
+
+pub static BOOTLOADER_CONFIG: BootloaderConfig = {
+    let mut config = BootloaderConfig::new_default();
+    config.mappings.physical_memory = Some(Mapping::Dynamic);
+    config
+};
+
+entry_point!(kernel_main, config = &BOOTLOADER_CONFIG);
+
+fn kernel_main(boot_info: &'static mut BootInfo) -> ! {
+    print_serial!("guard_page::stack_overflow_in_task...\t");
+
+    kernel::init(boot_info);
+    test_idt::init();
+
+    let bounds = stack::allocate(TASK_NAME, KERNEL_STACK_SIZE).expect("stack allocation failed");
+
+    // switch to the new stack and overflow it
+    unsafe {
+        asm!(
+            "mov rsp, {stack_end}",
+            "call {overflow}",
+            stack_end = in(reg) bounds.end().as_u64(),
+            overflow = sym overflow,
+            options(noreturn),
+        );
+    }
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    utils::test::panic::handler(info)
+}
+
+extern "C" fn overflow() -> ! {
+    stack_overflow();
+    panic!("Execution continued after stack overflow");
+}
+
+#[allow(unconditional_recursion)]
+fn stack_overflow() {
+    stack_overflow(); // for each recursion, the return address is pushed
+    volatile::Volatile::new(0).read(); // prevent tail recursion optimizations
+}
+
+mod test_idt {
+    use lazy_static::lazy_static;
+    use utils::hlt::hlt_loop;
+    use utils::{println_serial, test::qemu};
+    use x86_64::registers::control::Cr2;
+    use x86_64::structures::idt::{
+        InterruptDescriptorTable, InterruptStackFrame, PageFaultErrorCode,
+    };
+
+    use super::TASK_NAME;
+
+    lazy_static! {
+        static ref IDT: InterruptDescriptorTable = {
+            let mut idt = InterruptDescriptorTable::new();
+
+            unsafe {
+                idt.page_fault
+                    .set_handler_fn(page_fault_handler)
+                    .set_stack_index(kernel::gdt::tss::PAGE_FAULT_IST_INDEX);
+                idt.double_fault
+                    .set_handler_fn(double_fault_handler)
+                    .set_stack_index(kernel::gdt::tss::DOUBLE_FAULT_IST_INDEX);
+            }
+
+            idt
+        };
+    }
+
+    pub fn init() {
+        IDT.load();
+    }
+
+    extern "x86-interrupt" fn page_fault_handler(
+        _stack_frame: InterruptStackFrame,
+        _error_code: PageFaultErrorCode,
+    ) {
+        let detected = kernel::memory::stack::with_overflowed_stack(Cr2::read(), |stack| {
+            stack.name == TASK_NAME
+        });
+
+        if detected == Some(true) {
+            println_serial!("[ok]");
+            qemu::exit(qemu::ExitCode::Success);
+        } else {
+            println_serial!("[failed]\n");
+            println_serial!("Error: page fault outside of the guard page\n");
+            qemu::exit(qemu::ExitCode::Failed);
+        }
+
+        hlt_loop();
+    }
+
+    extern "x86-interrupt" fn double_fault_handler(
+        _stack_frame: InterruptStackFrame,
+        _error_code: u64,
+    ) -> ! {
+        println_serial!("[failed]\n");
+        println_serial!("Error: stack overflow escalated into a double fault\n");
+
+        qemu::exit(qemu::ExitCode::Failed);
+        hlt_loop();
+    }
+}
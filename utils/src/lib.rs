@@ -4,4 +4,6 @@ extern crate alloc;
 
 pub mod hlt;
 pub mod posix;
+pub mod serial;
+pub mod sync;
 pub mod test;
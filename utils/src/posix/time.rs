@@ -1,11 +1,21 @@
 use time::UtcDateTime;
 
+/// Seconds since the Unix epoch, as stored by POSIX file systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Time {
     inner: u32,
 }
 
 impl Time {
+    pub const fn from_unix_timestamp(seconds: u32) -> Self {
+        Self { inner: seconds }
+    }
+
+    pub const fn unix_timestamp(&self) -> u32 {
+        self.inner
+    }
+
     pub fn date_time(&self) -> Result<UtcDateTime, time::error::ComponentRange> {
         UtcDateTime::from_unix_timestamp(self.inner as i64)
     }
@@ -0,0 +1,5 @@
+mod inner;
+mod port;
+
+pub use inner::uart;
+pub use port::{ComPort, DEFAULT_BAUD_RATE, Error, SerialPort};
@@ -0,0 +1,15 @@
+use super::port::{ComPort, SerialPort};
+use crate::sync::IrqSpinlock;
+
+static COM1_UART: IrqSpinlock<SerialPort> =
+    IrqSpinlock::new("COM1 UART", SerialPort::new(ComPort::Com1));
+static COM2_UART: IrqSpinlock<SerialPort> =
+    IrqSpinlock::new("COM2 UART", SerialPort::new(ComPort::Com2));
+
+/// Returns the UART of `port`, shared by the serial terminals and the output of the tests.
+pub fn uart(port: ComPort) -> &'static IrqSpinlock<SerialPort> {
+    match port {
+        ComPort::Com1 => &COM1_UART,
+        ComPort::Com2 => &COM2_UART,
+    }
+}
@@ -0,0 +1,166 @@
+use core::fmt;
+
+use x86_64::instructions::port::Port;
+
+/// Highest baud rate of the UART, the rates it supports divide it.
+const MAX_BAUD_RATE: u32 = 115_200;
+
+/// Baud rate of the serial ports until they are configured, and of the first one when the tests
+/// print to it before the kernel set it up.
+pub const DEFAULT_BAUD_RATE: u32 = 38_400;
+
+/// Registers, as offsets from the base port.
+const DATA: u16 = 0;
+const INTERRUPT_ENABLE: u16 = 1;
+const FIFO_CONTROL: u16 = 2;
+const LINE_CONTROL: u16 = 3;
+const MODEM_CONTROL: u16 = 4;
+const LINE_STATUS: u16 = 5;
+/// The low and high bytes of the divisor replace the data and interrupt enable registers while
+/// the divisor latch is accessible.
+const DIVISOR_LOW: u16 = 0;
+const DIVISOR_HIGH: u16 = 1;
+
+/// Line control: 8 data bits, no parity, 1 stop bit.
+const EIGHT_N_ONE: u8 = 0x03;
+const DIVISOR_LATCH_ACCESS: u8 = 0x80;
+/// FIFO control: enables and clears the FIFOs, with an interrupt once 14 bytes are received.
+const ENABLE_FIFOS: u8 = 0xc7;
+/// Modem control: data terminal ready, request to send, and OUT2 which enables the IRQ.
+const MODEM_READY: u8 = 0x0b;
+/// Modem control: loops the output back to the input, to test the UART.
+const MODEM_LOOPBACK: u8 = 0x1e;
+/// Interrupt enable: when a byte is received.
+const RECEIVED_DATA_INTERRUPT: u8 = 0x01;
+/// Line status bits.
+const DATA_READY: u8 = 0x01;
+const TRANSMITTER_EMPTY: u8 = 0x20;
+
+/// Byte sent to the UART in loopback mode, which it must send back.
+const LOOPBACK_TEST: u8 = 0xae;
+
+/// The standard serial ports of the PC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComPort {
+    Com1,
+    Com2,
+}
+
+impl ComPort {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Com1 => "COM1",
+            Self::Com2 => "COM2",
+        }
+    }
+
+    /// Returns the first I/O port of the UART.
+    pub const fn base(self) -> u16 {
+        match self {
+            Self::Com1 => 0x3f8,
+            Self::Com2 => 0x2f8,
+        }
+    }
+}
+
+impl fmt::Display for ComPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The baud rate doesn't divide the highest one
+    InvalidBaudRate,
+    /// The UART didn't send back the byte of the loopback test, or there is none
+    Faulty,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBaudRate => write!(f, "invalid baud rate"),
+            Self::Faulty => write!(f, "faulty or missing UART"),
+        }
+    }
+}
+
+/// A 16550 UART.
+#[derive(Debug)]
+pub struct SerialPort {
+    base: u16,
+    /// Whether the UART passed its test, nothing should be sent nor received otherwise
+    ready: bool,
+}
+
+impl SerialPort {
+    pub const fn new(port: ComPort) -> Self {
+        Self {
+            base: port.base(),
+            ready: false,
+        }
+    }
+
+    /// Sets up the UART to send and receive bytes of 8 bits at `baud_rate`, and to raise its
+    /// IRQ when it receives one.
+    pub fn init(&mut self, baud_rate: u32) -> Result<(), Error> {
+        self.ready = false;
+        self.write(INTERRUPT_ENABLE, 0);
+        self.set_baud_rate(baud_rate)?;
+        self.write(LINE_CONTROL, EIGHT_N_ONE);
+        self.write(FIFO_CONTROL, ENABLE_FIFOS);
+
+        self.write(MODEM_CONTROL, MODEM_LOOPBACK);
+        self.write(DATA, LOOPBACK_TEST);
+        if self.read(DATA) != LOOPBACK_TEST {
+            return Err(Error::Faulty);
+        }
+        self.write(MODEM_CONTROL, MODEM_READY);
+        self.write(INTERRUPT_ENABLE, RECEIVED_DATA_INTERRUPT);
+
+        self.ready = true;
+        Ok(())
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Sends and receives at `baud_rate`, which must divide 115200.
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Error> {
+        if baud_rate == 0 || !MAX_BAUD_RATE.is_multiple_of(baud_rate) {
+            return Err(Error::InvalidBaudRate);
+        }
+        let [low, high, ..] = (MAX_BAUD_RATE / baud_rate).to_le_bytes();
+
+        let line_control = self.read(LINE_CONTROL);
+        self.write(LINE_CONTROL, line_control | DIVISOR_LATCH_ACCESS);
+        self.write(DIVISOR_LOW, low);
+        self.write(DIVISOR_HIGH, high);
+        self.write(LINE_CONTROL, line_control & !DIVISOR_LATCH_ACCESS);
+
+        Ok(())
+    }
+
+    /// Waits for the UART to be ready, and sends `byte`.
+    pub fn send(&mut self, byte: u8) {
+        while self.read(LINE_STATUS) & TRANSMITTER_EMPTY == 0 {
+            core::hint::spin_loop();
+        }
+        self.write(DATA, byte);
+    }
+
+    /// Returns the next byte received, if there is one.
+    pub fn receive(&mut self) -> Option<u8> {
+        (self.read(LINE_STATUS) & DATA_READY != 0).then(|| self.read(DATA))
+    }
+
+    fn read(&mut self, register: u16) -> u8 {
+        unsafe { Port::new(self.base + register).read() }
+    }
+
+    fn write(&mut self, register: u16, value: u8) {
+        unsafe { Port::new(self.base + register).write(value) }
+    }
+}
@@ -0,0 +1,3 @@
+mod irq_spinlock;
+
+pub use irq_spinlock::{IrqSpinlock, IrqSpinlockGuard};
@@ -0,0 +1,171 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+#[cfg(debug_assertions)]
+use core::sync::atomic::AtomicU32;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use x86_64::instructions::interrupts;
+
+/// A spinlock that disables interrupts while it is held, so that an interrupt handler taking it
+/// cannot deadlock on the code it interrupted.
+///
+/// With debug assertions, acquiring the lock again on the CPU that holds it panics with the name
+/// of the lock instead of spinning forever.
+pub struct IrqSpinlock<T: ?Sized> {
+    name: &'static str,
+    locked: AtomicBool,
+    /// APIC ID + 1 of the CPU holding the lock, 0 if it is free.
+    #[cfg(debug_assertions)]
+    holder: AtomicU32,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Sync for IrqSpinlock<T> {}
+unsafe impl<T: ?Sized + Send> Send for IrqSpinlock<T> {}
+
+/// Gives access to the data of an [`IrqSpinlock`], and releases it when dropped.
+pub struct IrqSpinlockGuard<'a, T: ?Sized> {
+    lock: &'a IrqSpinlock<T>,
+    /// Whether interrupts were enabled before the lock was taken.
+    interrupts_enabled: bool,
+}
+
+impl<T> IrqSpinlock<T> {
+    pub const fn new(name: &'static str, data: T) -> Self {
+        Self {
+            name,
+            locked: AtomicBool::new(false),
+            #[cfg(debug_assertions)]
+            holder: AtomicU32::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> IrqSpinlock<T> {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Disables interrupts and spins until the lock is acquired.
+    pub fn lock(&self) -> IrqSpinlockGuard<'_, T> {
+        let interrupts_enabled = interrupts::are_enabled();
+        interrupts::disable();
+
+        #[cfg(debug_assertions)]
+        self.check_recursion();
+
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+
+        self.acquired(interrupts_enabled)
+    }
+
+    /// Acquires the lock if it is free.
+    pub fn try_lock(&self) -> Option<IrqSpinlockGuard<'_, T>> {
+        let interrupts_enabled = interrupts::are_enabled();
+        interrupts::disable();
+
+        match self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        {
+            Ok(_) => Some(self.acquired(interrupts_enabled)),
+            Err(_) => {
+                if interrupts_enabled {
+                    interrupts::enable();
+                }
+                None
+            }
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    /// Releases the lock without a guard.
+    ///
+    /// # Safety
+    ///
+    /// The data must not be accessed through the guard that held the lock anymore. This is meant
+    /// for panic handlers, which must print even if the panic happened while printing.
+    pub unsafe fn force_unlock(&self) {
+        #[cfg(debug_assertions)]
+        self.holder.store(0, Ordering::Relaxed);
+        self.locked.store(false, Ordering::Release);
+    }
+
+    fn acquired(&self, interrupts_enabled: bool) -> IrqSpinlockGuard<'_, T> {
+        #[cfg(debug_assertions)]
+        self.holder.store(cpu_id() + 1, Ordering::Relaxed);
+
+        IrqSpinlockGuard {
+            lock: self,
+            interrupts_enabled,
+        }
+    }
+
+    /// Panics if the current CPU already holds the lock, which would deadlock.
+    #[cfg(debug_assertions)]
+    fn check_recursion(&self) {
+        let cpu = cpu_id();
+        if self.is_locked() && self.holder.load(Ordering::Relaxed) == cpu + 1 {
+            // the panic handler may need the lock to report the panic
+            unsafe { self.force_unlock() };
+            panic!("deadlock: lock `{}` acquired twice on CPU {cpu}", self.name);
+        }
+    }
+}
+
+/// Returns the initial APIC ID of the current CPU.
+#[cfg(debug_assertions)]
+fn cpu_id() -> u32 {
+    let cpuid = core::arch::x86_64::__cpuid(1);
+    cpuid.ebx >> 24
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for IrqSpinlock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("IrqSpinlock");
+        debug.field("name", &self.name);
+        match self.try_lock() {
+            Some(guard) => debug.field("data", &&*guard),
+            None => debug.field("data", &format_args!("<locked>")),
+        };
+        debug.finish()
+    }
+}
+
+impl<T: ?Sized> Deref for IrqSpinlockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for IrqSpinlockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for IrqSpinlockGuard<'_, T> {
+    fn drop(&mut self) {
+        unsafe { self.lock.force_unlock() };
+        if self.interrupts_enabled {
+            interrupts::enable();
+        }
+    }
+}
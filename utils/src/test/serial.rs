@@ -1,4 +1 @@
 pub mod macros;
-mod port;
-
-pub use port::SERIAL1;
@@ -1,17 +1,30 @@
-use core::fmt::Write;
+use core::fmt::{self, Write};
 
-use x86_64::instructions::interrupts;
+use crate::serial::{self, ComPort, DEFAULT_BAUD_RATE, SerialPort};
 
-use super::SERIAL1;
+/// Sends the output of the tests through the UART of the first serial port.
+struct Writer<'a>(&'a mut SerialPort);
+
+impl Write for Writer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            self.0.send(byte);
+        }
+        Ok(())
+    }
+}
 
 #[doc(hidden)]
-pub fn _print(args: ::core::fmt::Arguments) {
-    interrupts::without_interrupts(|| {
-        SERIAL1
-            .lock()
-            .write_fmt(args)
-            .expect("Printing to serial failed");
-    });
+pub fn _print(args: fmt::Arguments) {
+    let mut uart = serial::uart(ComPort::Com1).lock();
+    // The UART is only set up here if the kernel didn't, to keep its baud rate
+    if !uart.is_ready() && uart.init(DEFAULT_BAUD_RATE).is_err() {
+        return;
+    }
+    let result = Writer(&mut uart).write_fmt(args);
+    // The panic handler prints through the same lock
+    drop(uart);
+    result.expect("Printing to serial failed");
 }
 
 /// Prints to the host through the serial interface.